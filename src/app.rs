@@ -10,10 +10,13 @@ use ratatui::DefaultTerminal;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::level::Level;
+use crate::redact;
 use crate::screen::event_search::EventSearchScreen;
+use crate::screen::motion::Motion;
 use crate::screen::{
-    CurrentScreen, EventsScreen, GroupEventsScreen, MainScreen, NavigateTo, ScreenAction,
-    ViewerScreen,
+    BookmarksScreen, CurrentScreen, DiffScreen, EventsScreen, GroupEventsScreen, MainScreen,
+    NavigateTo, PatternsScreen, ScreenAction, ViewerScreen,
 };
 use crate::ui;
 
@@ -32,6 +35,10 @@ pub struct StatefulList<T> {
     pub loading: bool,
     /// 絞り込み時に表示するアイテムのインデックス一覧（`None` は全件表示）
     pub visible_indices: Option<Vec<usize>>,
+    /// 画面に表示できる行数（ページ単位の移動に使用、描画時に更新）
+    pub page_size: usize,
+    /// 末尾へのジャンプにより、残りのページを全て読み込み中かどうか
+    pub load_all: bool,
 }
 
 impl<T> StatefulList<T> {
@@ -43,35 +50,39 @@ impl<T> StatefulList<T> {
             next_token: None,
             loading: false,
             visible_indices: None,
+            page_size: 1,
+            load_all: false,
         }
     }
 
-    /// 選択を次のアイテムに移動します。末尾では移動しません。
-    pub fn next(&mut self) {
-        let len = match &self.visible_indices {
-            Some(v) => v.len(),
-            None => self.items.len(),
-        };
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= len.saturating_sub(1) {
-                    i
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.state.select(Some(i));
+    /// カーソル移動を適用します。
+    ///
+    /// [`Motion::Last`] で未ロードのページがある場合は [`load_all`](Self::load_all) を立て、
+    /// ページネーションで全ページを読み込みながら末尾を選択し続けます。
+    /// それ以外の移動では全ページの読み込みを止めます。
+    pub fn apply_motion(&mut self, motion: Motion) {
+        self.load_all = motion == Motion::Last && self.next_token.is_some();
+        let len = self.visible_len();
+        if len == 0 {
+            return;
+        }
+        let pos = self.state.selected().unwrap_or(0);
+        self.state
+            .select(Some(motion.apply(pos, len, self.page_size)));
     }
 
-    /// 選択を前のアイテムに移動します。先頭では移動しません。
-    pub fn previous(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => i.saturating_sub(1),
-            None => 0,
-        };
-        self.state.select(Some(i));
+    /// 全ページの読み込み中であれば、追加ロード後に末尾を選択し直します。
+    /// 最後のページまで読み込んだ場合は全ページの読み込みを終了します。
+    pub fn follow_load_all(&mut self) {
+        if !self.load_all {
+            return;
+        }
+        if let Some(last) = self.visible_len().checked_sub(1) {
+            self.state.select(Some(last));
+        }
+        if self.next_token.is_none() {
+            self.load_all = false;
+        }
     }
 
     /// 現在選択中のアイテムへの参照を返します。
@@ -98,6 +109,14 @@ impl<T> StatefulList<T> {
             })
     }
 
+    /// 現在表示対象のアイテム数を返します。
+    pub fn visible_len(&self) -> usize {
+        match &self.visible_indices {
+            Some(v) => v.len(),
+            None => self.items.len(),
+        }
+    }
+
     /// 現在表示対象のアイテム一覧を返します。
     ///
     /// `visible_indices` が設定されている場合は絞り込み後のアイテムのみを返します。
@@ -130,6 +149,8 @@ pub struct LogGroup {
 pub struct LogStream {
     /// ログストリーム名
     pub name: String,
+    /// 最初のイベントのタイムスタンプ（Unix ミリ秒）
+    pub first_event_time: Option<i64>,
     /// 最終イベントのタイムスタンプ（Unix ミリ秒）
     pub last_event_time: Option<i64>,
 }
@@ -141,6 +162,26 @@ pub struct LogEvent {
     pub timestamp: i64,
     /// ログメッセージ本文
     pub message: String,
+    /// メッセージから判定したログレベル
+    pub level: Level,
+    /// イベントが属するログストリーム名（不明な場合は `None`）
+    pub stream: Option<String>,
+}
+
+impl LogEvent {
+    /// タイムスタンプとメッセージから [`LogEvent`] を生成します。ログレベルはメッセージから判定します。
+    ///
+    /// メッセージにはマスキング規則（[`redact::apply`]）を適用します。
+    pub fn new(timestamp: i64, message: String) -> Self {
+        let message = redact::apply(message);
+        let level = Level::detect(&message);
+        Self {
+            timestamp,
+            message,
+            level,
+            stream: None,
+        }
+    }
 }
 
 /// アプリケーション全体の状態を管理する構造体。
@@ -195,11 +236,14 @@ impl App {
                     continue;
                 }
                 let action = match &mut self.screen {
-                    CurrentScreen::Main(s) => s.handle_key(key.code).await?,
-                    CurrentScreen::Events(s) => s.handle_key(key.code).await?,
-                    CurrentScreen::Viewer(s) => s.handle_key(key.code).await?,
-                    CurrentScreen::EventSearch(s) => s.handle_key(key.code).await?,
-                    CurrentScreen::GroupEvents(s) => s.handle_key(key.code).await?,
+                    CurrentScreen::Main(s) => s.handle_key(key).await?,
+                    CurrentScreen::Events(s) => s.handle_key(key).await?,
+                    CurrentScreen::Viewer(s) => s.handle_key(key).await?,
+                    CurrentScreen::EventSearch(s) => s.handle_key(key).await?,
+                    CurrentScreen::GroupEvents(s) => s.handle_key(key).await?,
+                    CurrentScreen::Bookmarks(s) => s.handle_key(key).await?,
+                    CurrentScreen::Diff(s) => s.handle_key(key).await?,
+                    CurrentScreen::Patterns(s) => s.handle_key(key).await?,
                     CurrentScreen::Transitioning => ScreenAction::None,
                 };
                 match action {
@@ -217,7 +261,10 @@ impl App {
                 s.check_pagination().await?;
             }
             if let CurrentScreen::Events(s) = &mut self.screen {
-                s.check_pagination().await?;
+                s.check_pagination().await;
+            }
+            if let CurrentScreen::GroupEvents(s) = &mut self.screen {
+                s.check_pagination().await;
             }
         }
 
@@ -240,6 +287,17 @@ impl App {
                 s.load_log_events().await?;
                 self.screen = CurrentScreen::Events(s);
             }
+            NavigateTo::NewOfflineEvents { path, events } => {
+                let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
+                let mut s = EventsScreen::from_file(
+                    Arc::clone(&self.client),
+                    path,
+                    events,
+                    Box::new(origin),
+                );
+                s.load_log_events().await?;
+                self.screen = CurrentScreen::Events(s);
+            }
             NavigateTo::NewViewer { event } => {
                 let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
                 let viewer = ViewerScreen::new(event, Box::new(origin));
@@ -255,22 +313,34 @@ impl App {
                 start_ms,
                 end_ms,
                 pattern,
-                start_display,
-                end_display,
-                pattern_display,
             } => {
                 let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
-                let mut s = GroupEventsScreen::new(
-                    Arc::clone(&self.client),
-                    group_name,
-                    start_display,
-                    end_display,
-                    pattern_display,
-                    Box::new(origin),
-                );
-                s.load_group_events(start_ms, end_ms, pattern).await?;
+                let mut s =
+                    GroupEventsScreen::new(Arc::clone(&self.client), group_name, Box::new(origin));
+                if let Err(e) = s.load_group_events(start_ms, end_ms, pattern).await {
+                    // パターンが AWS に拒否された場合などは検索フォームに戻ってエラーを表示する
+                    let Some(CurrentScreen::EventSearch(mut search)) = s.origin.take().map(|o| *o)
+                    else {
+                        return Err(e);
+                    };
+                    search.event_search_error = Some(e.to_string());
+                    self.screen = CurrentScreen::EventSearch(search);
+                    return Ok(());
+                }
                 self.screen = CurrentScreen::GroupEvents(s);
             }
+            NavigateTo::NewBookmarks => {
+                let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
+                self.screen = CurrentScreen::Bookmarks(BookmarksScreen::new(Box::new(origin)));
+            }
+            NavigateTo::NewPatterns => {
+                let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
+                self.screen = CurrentScreen::Patterns(PatternsScreen::new(Box::new(origin)));
+            }
+            NavigateTo::NewDiff { left, right } => {
+                let origin = std::mem::replace(&mut self.screen, CurrentScreen::Transitioning);
+                self.screen = CurrentScreen::Diff(DiffScreen::new(left, right, Box::new(origin)));
+            }
             NavigateTo::Restore(screen) => {
                 self.screen = *screen;
             }
@@ -0,0 +1,496 @@
+//! ロググループ全体のログストリームごとのアーカイブ。
+//!
+//! 時間範囲と重なるログストリームを [`aws::fetch_log_streams`] で列挙し、
+//! 上限 [`WORKERS`] 本まで並行して `<dir>/<group>/<stream>.jsonl` に書き出します。
+//! 全ストリームの書き出し後（中断・失敗した場合も）に、ストリームごとのイベント数・バイト数・
+//! SHA-256 を記録した `manifest.json` を同じディレクトリに書き出します。
+//!
+//! ロググループ名・ログストリーム名の `/` はディレクトリの区切りとして扱います。
+//! 異なるストリーム名が同じパスになる場合（`a//b` と `a/b` など）は、ファイル名に `-1`・`-2`… を付けて
+//! 区別し、実際のファイル名はマニフェストに記録します。
+//!
+//! [`aws::fetch_log_streams`]: crate::aws::fetch_log_streams
+
+use anyhow::{Context, Result};
+use aws_sdk_cloudwatchlogs::Client;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::app::LogStream;
+use crate::aws;
+use crate::compress::Writer;
+use crate::download::JobState;
+use crate::export::{Encoder, ExportSource, Format};
+use crate::time;
+
+/// 並行してダウンロードするログストリームの上限
+pub const WORKERS: usize = 4;
+
+/// マニフェストのファイル名
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// `lastEventTimestamp` の反映の遅れを見込んで、範囲の開始より前に終わったとみなすまでの猶予
+/// （CloudWatch Logs はこの値を結果整合的に、最大 1 時間ほど遅れて更新します）
+const LAST_EVENT_LAG_MS: i64 = 3_600_000;
+
+/// アーカイブの進捗。
+#[derive(Debug, Clone)]
+pub struct Progress {
+    /// 状態（ストリームの一部が失敗した場合は [`JobState::Failed`]）
+    pub state: JobState,
+    /// 対象のログストリーム数（列挙中は `None`）
+    pub streams: Option<usize>,
+    /// 書き出しを終えたログストリーム数（失敗を含む）
+    pub done: usize,
+    /// 失敗したログストリーム数
+    pub failed: usize,
+    /// 書き出したイベント数
+    pub events: usize,
+    /// 書き出したバイト数
+    pub bytes: u64,
+    /// 開始してからの経過時間（終了後は所要時間）
+    pub elapsed: Duration,
+}
+
+/// バックグラウンドで実行中（または終了済み）のアーカイブ。
+///
+/// ドロップすると実行中のアーカイブは次のページの取得前に中断します。
+pub struct ArchiveJob {
+    /// 書き出し先のディレクトリ（`<dir>/<group>`）
+    pub dir: PathBuf,
+    progress: Arc<Mutex<Progress>>,
+    started: Instant,
+    cancel: Arc<AtomicBool>,
+}
+
+/// ログストリーム 1 本の書き出し結果。
+struct StreamEntry {
+    stream: String,
+    file: PathBuf,
+    events: usize,
+    bytes: u64,
+    sha256: String,
+    first_ms: Option<i64>,
+    last_ms: Option<i64>,
+    state: JobState,
+}
+
+impl ArchiveJob {
+    /// `group` のうち `start_ms`〜`end_ms` と重なる全ログストリームのアーカイブを開始します。
+    pub fn spawn(
+        client: Arc<Client>,
+        group: String,
+        dir: &str,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+    ) -> Self {
+        let dir = Path::new(dir).join(relative_path(&group));
+        let progress = Arc::new(Mutex::new(Progress {
+            state: JobState::Running,
+            streams: None,
+            done: 0,
+            failed: 0,
+            events: 0,
+            bytes: 0,
+            elapsed: Duration::ZERO,
+        }));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let started = Instant::now();
+        let task = Task {
+            client,
+            group,
+            dir: dir.clone(),
+            start_ms,
+            end_ms,
+            progress: Arc::clone(&progress),
+            cancel: Arc::clone(&cancel),
+        };
+        tokio::spawn(async move {
+            let result = task.run().await;
+            let mut p = task.progress.lock().unwrap_or_else(|e| e.into_inner());
+            p.elapsed = started.elapsed();
+            p.state = match result {
+                Ok(_) if p.failed > 0 => {
+                    JobState::Failed(format!("{} of {} streams failed", p.failed, p.done))
+                }
+                Ok(true) => JobState::Complete,
+                Ok(false) => JobState::Cancelled,
+                Err(e) => JobState::Failed(format!("{:#}", e)),
+            };
+        });
+        Self {
+            dir,
+            progress,
+            started,
+            cancel,
+        }
+    }
+
+    /// 現在の進捗を返します。
+    pub fn progress(&self) -> Progress {
+        let mut p = self
+            .progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if p.state == JobState::Running {
+            p.elapsed = self.started.elapsed();
+        }
+        p
+    }
+
+    /// 実行中かどうかを返します。
+    pub fn is_running(&self) -> bool {
+        self.progress().state == JobState::Running
+    }
+
+    /// 次のページの取得前に中断するよう要求します。
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ArchiveJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// バックグラウンドで実行するアーカイブの条件と共有状態。
+struct Task {
+    client: Arc<Client>,
+    group: String,
+    dir: PathBuf,
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
+    progress: Arc<Mutex<Progress>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Task {
+    /// 全ログストリームを書き出してマニフェストを書き出します。
+    ///
+    /// 全ストリームを書き出した場合は `true`、中断した場合は `false` を返します。
+    async fn run(&self) -> Result<bool> {
+        let streams = self.list_streams().await?;
+        self.update(|p| p.streams = Some(streams.len()));
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        let started_at = time::now();
+        let semaphore = Arc::new(Semaphore::new(WORKERS));
+        let mut set = JoinSet::new();
+        let files = stream_files(streams.iter().map(|s| s.name.as_str()));
+        for (stream, file) in streams.into_iter().zip(files) {
+            if self.cancel.load(Ordering::Relaxed) {
+                break;
+            }
+            let permit = Arc::clone(&semaphore).acquire_owned().await?;
+            let worker = Worker {
+                client: Arc::clone(&self.client),
+                source: ExportSource {
+                    group: self.group.clone(),
+                    stream: Some(stream.name.clone()),
+                    start_ms: self.start_ms,
+                    end_ms: self.end_ms,
+                    pattern: None,
+                },
+                file,
+                dir: self.dir.clone(),
+                progress: Arc::clone(&self.progress),
+                cancel: Arc::clone(&self.cancel),
+            };
+            set.spawn(async move {
+                let entry = worker.run().await;
+                drop(permit);
+                entry
+            });
+        }
+        let mut entries = Vec::new();
+        while let Some(entry) = set.join_next().await {
+            let entry = entry?;
+            self.update(|p| {
+                p.done += 1;
+                if matches!(entry.state, JobState::Failed(_)) {
+                    p.failed += 1;
+                }
+            });
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| a.stream.cmp(&b.stream));
+        let complete = !self.cancel.load(Ordering::Relaxed)
+            && entries.iter().all(|e| e.state == JobState::Complete);
+        self.write_manifest(&entries, &started_at, complete)?;
+        Ok(!self.cancel.load(Ordering::Relaxed))
+    }
+
+    /// 時間範囲と重なるログストリームを全ページ列挙します。
+    ///
+    /// ストリームは最終イベントの新しい順に返るため、範囲の開始より前に終わったストリームに達した時点で打ち切ります。
+    async fn list_streams(&self) -> Result<Vec<LogStream>> {
+        let mut streams = Vec::new();
+        let mut token = None;
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Ok(streams);
+            }
+            let (page, next) = aws::fetch_log_streams(&self.client, &self.group, token).await?;
+            for stream in page {
+                let ended_before = matches!(
+                    (stream.last_event_time, self.start_ms),
+                    (Some(last), Some(start)) if last + LAST_EVENT_LAG_MS < start
+                );
+                if ended_before {
+                    return Ok(streams);
+                }
+                let starts_after = matches!(
+                    (stream.first_event_time, self.end_ms),
+                    (Some(first), Some(end)) if first > end
+                );
+                if !starts_after {
+                    streams.push(stream);
+                }
+            }
+            match next {
+                Some(t) => token = Some(t),
+                None => return Ok(streams),
+            }
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Progress)) {
+        f(&mut self.progress.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    /// ストリームごとの書き出し結果を `manifest.json` に書き出します。
+    fn write_manifest(
+        &self,
+        entries: &[StreamEntry],
+        started_at: &jiff::Zoned,
+        complete: bool,
+    ) -> Result<()> {
+        let streams: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|e| {
+                let (status, error) = match &e.state {
+                    JobState::Running | JobState::Complete => ("complete", None),
+                    JobState::Cancelled => ("cancelled", None),
+                    JobState::Failed(msg) => ("failed", Some(msg.as_str())),
+                };
+                serde_json::json!({
+                    "stream": e.stream,
+                    "file": e.file.to_string_lossy().replace('\\', "/"),
+                    "events": e.events,
+                    "bytes": e.bytes,
+                    "sha256": e.sha256,
+                    "first_ms": e.first_ms,
+                    "last_ms": e.last_ms,
+                    "status": status,
+                    "error": error,
+                })
+            })
+            .collect();
+        let manifest = serde_json::json!({
+            "group": self.group,
+            "start": self.start_ms.map(time::format_ms),
+            "end": self.end_ms.map(time::format_ms),
+            "start_ms": self.start_ms,
+            "end_ms": self.end_ms,
+            "time_zone": time::zone_label(),
+            "started_at": time::format_zoned(started_at),
+            "finished_at": time::format_zoned(&time::now()),
+            "complete": complete,
+            "events": entries.iter().map(|e| e.events).sum::<usize>(),
+            "bytes": entries.iter().map(|e| e.bytes).sum::<u64>(),
+            "streams": streams,
+        });
+        let path = self.dir.join(MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(&manifest)? + "\n";
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// ログストリーム 1 本を書き出すワーカー。
+struct Worker {
+    client: Arc<Client>,
+    source: ExportSource,
+    /// `dir` からの書き出し先の相対パス
+    file: PathBuf,
+    dir: PathBuf,
+    progress: Arc<Mutex<Progress>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Worker {
+    async fn run(self) -> StreamEntry {
+        let mut entry = StreamEntry {
+            stream: self.source.stream.clone().unwrap_or_default(),
+            file: self.file.clone(),
+            events: 0,
+            bytes: 0,
+            sha256: String::new(),
+            first_ms: None,
+            last_ms: None,
+            state: JobState::Running,
+        };
+        let mut hasher = Sha256::new();
+        entry.state = match self.write(&mut entry, &mut hasher).await {
+            Ok(true) => JobState::Complete,
+            Ok(false) => JobState::Cancelled,
+            Err(e) => JobState::Failed(format!("{:#}", e)),
+        };
+        entry.sha256 = format!("{:x}", hasher.finalize());
+        entry
+    }
+
+    /// 全ページを書き出した場合は `true`、中断した場合は `false` を返します。
+    async fn write(&self, entry: &mut StreamEntry, hasher: &mut Sha256) -> Result<bool> {
+        let path = self.dir.join(&self.file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut out = Writer::create(&path.to_string_lossy(), false)?;
+        let mut encoder = Encoder::new(self.source.clone(), Format::Jsonl, Vec::new());
+        let mut emit = |out: &mut Writer, bytes: &mut u64, chunk: String| -> Result<()> {
+            out.write_all(chunk.as_bytes())?;
+            hasher.update(chunk.as_bytes());
+            *bytes += chunk.len() as u64;
+            Ok(())
+        };
+        emit(&mut out, &mut entry.bytes, encoder.header())?;
+        let mut token = None;
+        let finished = loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                break false;
+            }
+            let (events, next) = aws::fetch_log_events(
+                &self.client,
+                &self.source.group,
+                self.source.stream.as_deref().into(),
+                self.source.start_ms,
+                self.source.end_ms,
+                None,
+                token,
+            )
+            .await?;
+            let before = entry.bytes;
+            for e in &events {
+                emit(&mut out, &mut entry.bytes, encoder.event(e))?;
+            }
+            out.flush()?;
+            entry.events += events.len();
+            if let Some(last) = events.last() {
+                entry.first_ms.get_or_insert(events[0].timestamp);
+                entry.last_ms = Some(last.timestamp);
+            }
+            {
+                let mut p = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+                p.events += events.len();
+                p.bytes += entry.bytes - before;
+            }
+            match next {
+                Some(t) => token = Some(t),
+                None => break true,
+            }
+        };
+        out.finish()?;
+        Ok(finished)
+    }
+}
+
+/// ログストリーム名ごとの書き出し先の相対パス（`<stream>.jsonl`）を、`names` の順に返します。
+///
+/// 異なる名前が同じパスになる場合、またはファイルが別のストリームのディレクトリと重なる場合は、
+/// [`paths::unique_path`] と同じく拡張子の前に `-1`・`-2`… を付けます。
+/// 付ける順序は実行ごとに変わらないよう、変換しても変わらない名前を優先した名前順です。
+///
+/// [`paths::unique_path`]: crate::paths::unique_path
+fn stream_files<'a>(names: impl Iterator<Item = &'a str>) -> Vec<PathBuf> {
+    let bases: Vec<(&str, PathBuf)> = names.map(|n| (n, relative_path(n))).collect();
+    // ディレクトリは名前から決まるため、先に全て確保してファイルとは重ならないようにする
+    let dirs: HashSet<&Path> = bases
+        .iter()
+        .flat_map(|(_, base)| base.ancestors().skip(1))
+        .filter(|d| !d.as_os_str().is_empty())
+        .collect();
+    let mut order: Vec<usize> = (0..bases.len()).collect();
+    order.sort_by_key(|&i| {
+        let (name, base) = &bases[i];
+        (base.as_os_str() != *name, *name)
+    });
+    let mut files = HashSet::new();
+    let mut out = vec![PathBuf::new(); bases.len()];
+    for i in order {
+        let base = bases[i].1.display();
+        let file = (0..)
+            .map(|n| match n {
+                0 => PathBuf::from(format!("{}.jsonl", base)),
+                n => PathBuf::from(format!("{}-{}.jsonl", base, n)),
+            })
+            .find(|f| !files.contains(f) && !dirs.contains(f.as_path()))
+            .unwrap_or_default();
+        files.insert(file.clone());
+        out[i] = file;
+    }
+    out
+}
+
+/// ロググループ名・ログストリーム名を、`/` をディレクトリの区切りとした相対パスに変換します。
+///
+/// 空の要素は取り除き、`.`・`..` は `_` に置き換えます。
+fn relative_path(name: &str) -> PathBuf {
+    let path: PathBuf = name
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| if s == "." || s == ".." { "_" } else { s })
+        .collect();
+    if path.as_os_str().is_empty() {
+        PathBuf::from("_")
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(names: &[&str]) -> Vec<String> {
+        stream_files(names.iter().copied())
+            .iter()
+            .map(|f| f.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn distinct_names_keep_their_paths() {
+        assert_eq!(
+            files(&["app/1", "app/2", "web"]),
+            ["app/1.jsonl", "app/2.jsonl", "web.jsonl"]
+        );
+    }
+
+    #[test]
+    fn colliding_names_get_a_suffix() {
+        assert_eq!(
+            files(&["a/b", "a//b", "/a/b/"]),
+            ["a/b.jsonl", "a/b-2.jsonl", "a/b-1.jsonl"]
+        );
+        assert_eq!(files(&["..", "."]), ["_-1.jsonl", "_.jsonl"]);
+    }
+
+    #[test]
+    fn file_and_directory_do_not_overlap() {
+        assert_eq!(files(&["x.jsonl/y", "x"]), ["x.jsonl/y.jsonl", "x-1.jsonl"]);
+        assert_eq!(files(&["x", "x.jsonl/y"]), ["x-1.jsonl", "x.jsonl/y.jsonl"]);
+    }
+}
@@ -32,6 +32,11 @@ pub async fn build_client() -> Result<Client> {
 ///
 /// ページネーションに対応しており、`next_token` を渡すことで続きのページを取得できます。
 ///
+/// # Arguments
+///
+/// * `prefix` - ロググループ名の接頭辞（`None` で全件）
+/// * `next_token` - ページネーショントークン（初回は `None`）
+///
 /// # Returns
 ///
 /// `(ロググループ一覧, 次ページトークン)` のタプルを返します。
@@ -41,9 +46,15 @@ pub async fn build_client() -> Result<Client> {
 /// - AWS API 呼び出しに失敗した場合（認証エラー・ネットワークエラーなど）
 pub async fn fetch_log_groups(
     client: &Client,
+    prefix: Option<&str>,
     next_token: Option<String>,
 ) -> Result<(Vec<LogGroup>, Option<String>)> {
     let mut req = client.describe_log_groups();
+    if let Some(prefix) = prefix
+        && !prefix.is_empty()
+    {
+        req = req.log_group_name_prefix(prefix);
+    }
     if let Some(token) = next_token {
         req = req.next_token(token);
     }
@@ -107,6 +118,7 @@ pub async fn fetch_log_streams(
         .filter_map(|s| {
             s.log_stream_name().map(|n| LogStream {
                 name: n.to_string(),
+                first_event_time: s.first_event_timestamp(),
                 last_event_time: s.last_event_timestamp(),
             })
         })
@@ -115,15 +127,33 @@ pub async fn fetch_log_streams(
     Ok((streams, resp.next_token().map(String::from)))
 }
 
+/// ログイベントを取得するログストリームの指定。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFilter<'a> {
+    /// ロググループ内の全ログストリーム
+    All,
+    /// 名前が一致するログストリーム
+    Name(&'a str),
+    /// 名前が接頭辞で始まるログストリーム
+    Prefix(&'a str),
+}
+
+impl<'a> From<Option<&'a str>> for StreamFilter<'a> {
+    /// ログストリーム名の指定を変換します。`None` の場合はロググループ全体です。
+    fn from(name: Option<&'a str>) -> Self {
+        name.map_or(Self::All, Self::Name)
+    }
+}
+
 /// ログイベントを取得します（`FilterLogEvents` API を使用）。
 ///
-/// ストリーム名・時間範囲・フィルタパターンを任意で指定できます。
-/// `stream_name` が `None` の場合はロググループ全体を検索します。
+/// ログストリーム・時間範囲・フィルタパターンを任意で指定できます。
+/// `streams` が [`StreamFilter::All`] の場合はロググループ全体を検索します。
 ///
 /// # Arguments
 ///
 /// * `group_name` - 対象のロググループ名
-/// * `stream_name` - 対象のログストリーム名（`None` でグループ全体）
+/// * `streams` - 対象のログストリーム（名前または名前の接頭辞）
 /// * `start_time_ms` - 検索開始時刻（Unix ミリ秒、`None` で無制限）
 /// * `end_time_ms` - 検索終了時刻（Unix ミリ秒、`None` で無制限）
 /// * `filter_pattern` - CloudWatch Logs フィルタパターン（`None` または空文字で全件）
@@ -139,15 +169,17 @@ pub async fn fetch_log_streams(
 pub async fn fetch_log_events(
     client: &Client,
     group_name: &str,
-    stream_name: Option<&str>,
+    streams: StreamFilter<'_>,
     start_time_ms: Option<i64>,
     end_time_ms: Option<i64>,
     filter_pattern: Option<String>,
     next_token: Option<String>,
 ) -> Result<(Vec<LogEvent>, Option<String>)> {
     let mut req = client.filter_log_events().log_group_name(group_name);
-    if let Some(name) = stream_name {
-        req = req.log_stream_names(name);
+    match streams {
+        StreamFilter::All => {}
+        StreamFilter::Name(name) => req = req.log_stream_names(name),
+        StreamFilter::Prefix(prefix) => req = req.log_stream_name_prefix(prefix),
     }
     if let Some(start_time) = start_time_ms {
         req = req.start_time(start_time);
@@ -173,9 +205,13 @@ pub async fn fetch_log_events(
     let events = resp
         .events()
         .iter()
-        .map(|e| LogEvent {
-            timestamp: e.timestamp().unwrap_or(0),
-            message: e.message().unwrap_or("").to_string(),
+        .map(|e| {
+            let mut event = LogEvent::new(
+                e.timestamp().unwrap_or(0),
+                e.message().unwrap_or("").to_string(),
+            );
+            event.stream = e.log_stream_name().map(String::from);
+            event
         })
         .collect();
 
@@ -0,0 +1,140 @@
+//! ログイベントのブックマーク。
+//!
+//! 画面をまたいで参照できるよう、ブックマークはプロセス全体で 1 つのリストとして保持します。
+//! ロググループ・ログストリームをまたいでタイムスタンプ順に並べ、JSONL または Markdown で
+//! 書き出せます。
+
+use anyhow::{Context, Result};
+use std::sync::{LazyLock, RwLock};
+
+use crate::app::LogEvent;
+use crate::time;
+
+/// ブックマークしたログイベント。
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    /// イベントのロググループ名（ローカルファイルの場合はファイルパス）
+    pub group: String,
+    /// ブックマークしたイベント
+    pub event: LogEvent,
+}
+
+impl Bookmark {
+    /// 同じイベントを指しているかどうかを返します。
+    fn is(&self, group: &str, event: &LogEvent) -> bool {
+        self.group == group
+            && self.event.timestamp == event.timestamp
+            && self.event.stream == event.stream
+            && self.event.message == event.message
+    }
+
+    /// `group › stream` 形式の表示用の出所を返します。
+    pub fn source(&self) -> String {
+        match &self.event.stream {
+            Some(stream) => format!("{} › {}", self.group, stream),
+            None => self.group.clone(),
+        }
+    }
+}
+
+static BOOKMARKS: LazyLock<RwLock<Vec<Bookmark>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// イベントのブックマークを切り替えます。追加した場合は `true` を返します。
+pub fn toggle(group: &str, event: &LogEvent) -> bool {
+    let mut list = BOOKMARKS.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(i) = list.iter().position(|b| b.is(group, event)) {
+        list.remove(i);
+        return false;
+    }
+    let pos = list.partition_point(|b| b.event.timestamp <= event.timestamp);
+    list.insert(
+        pos,
+        Bookmark {
+            group: group.to_string(),
+            event: event.clone(),
+        },
+    );
+    true
+}
+
+/// イベントがブックマークされているかどうかを返します。
+pub fn contains(group: &str, event: &LogEvent) -> bool {
+    BOOKMARKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .any(|b| b.is(group, event))
+}
+
+/// 全てのブックマークをタイムスタンプ順に返します。
+pub fn all() -> Vec<Bookmark> {
+    BOOKMARKS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// `index` 番目のブックマークを削除します。
+pub fn remove(index: usize) {
+    let mut list = BOOKMARKS.write().unwrap_or_else(|e| e.into_inner());
+    if index < list.len() {
+        list.remove(index);
+    }
+}
+
+/// 全てのブックマークをファイルに書き出し、書き出した件数を返します。
+///
+/// `path` は `~`・`$VAR` を展開済みのパスで、既存のファイルは上書きします。
+/// 拡張子が `.md`・`.markdown` の場合は Markdown、それ以外は JSONL
+/// （`timestamp`・`message`・`group`・`stream`、オフライン閲覧で再読み込み可能）で書き出します。
+///
+/// # Errors
+///
+/// - ファイルの書き込みに失敗した場合
+pub fn export(path: &str) -> Result<usize> {
+    let list = all();
+    let lower = path.to_ascii_lowercase();
+    let content = if lower.ends_with(".md") || lower.ends_with(".markdown") {
+        to_markdown(&list)
+    } else {
+        to_jsonl(&list)
+    };
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path))?;
+    Ok(list.len())
+}
+
+fn to_jsonl(list: &[Bookmark]) -> String {
+    list.iter()
+        .map(|b| {
+            serde_json::json!({
+                "timestamp": b.event.timestamp,
+                "message": b.event.message,
+                "group": b.group,
+                "stream": b.event.stream,
+            })
+            .to_string()
+                + "\n"
+        })
+        .collect()
+}
+
+fn to_markdown(list: &[Bookmark]) -> String {
+    let mut out = format!("# Bookmarks ({})\n", time::zone_label());
+    for b in list {
+        // メッセージ中のバッククォートでコードブロックが閉じないよう、フェンスを長くする
+        let longest = b
+            .event
+            .message
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        out.push_str(&format!(
+            "\n## {} — {}\n\n{}\n{}\n{}\n",
+            time::format_ms(b.event.timestamp),
+            b.source(),
+            fence,
+            b.event.message.trim_end(),
+            fence
+        ));
+    }
+    out
+}
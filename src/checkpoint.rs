@@ -0,0 +1,377 @@
+//! 全ページのダウンロードを再開するためのチェックポイントファイル。
+//!
+//! 書き出し先 `<path>` の隣の `<path>.checkpoint.json` に、取得条件（ロググループ・ログストリーム・
+//! フィルタパターン・時間範囲）と書き出し形式、書き出し済みのページの次を指す `nextToken`、
+//! 最後に書き出したイベントの時刻、その時点のファイル長を保存します。
+//! ページを書き出すたびに更新し、全ページを書き出した時点で削除します。
+//!
+//! 再開時はファイルをチェックポイントのファイル長に切り詰めてから追記するため、
+//! 中断時に書き出した末尾部分や、チェックポイントの保存前に書き出したページは重複しません。
+//!
+//! `nextToken` の期限が切れるなどして拒否された場合は、最後に書き出したイベントの時刻から
+//! 取得し直します（[`Checkpoint::restart`]）。その時刻のイベントのうち書き出し済みのものは、
+//! 保存しておいたイベントのハッシュで見分けて読み飛ばします。
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::app::LogEvent;
+use crate::columns;
+use crate::columns::Column;
+use crate::export::{Encoder, ExportSource, Format};
+
+/// チェックポイントファイルのパスに付ける接尾辞
+const SUFFIX: &str = ".checkpoint.json";
+
+/// 全ページのダウンロードの途中経過。
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// 書き出すイベントの取得条件
+    pub source: ExportSource,
+    /// 書き出し形式
+    pub format: Format,
+    /// CSV・Markdown の表に含める JSON フィールド列
+    pub columns: Vec<Column>,
+    /// 次に取得するページの `nextToken`（最初のページの場合は `None`）
+    pub next_token: Option<String>,
+    /// 最初に書き出したイベントの時刻（Unix ミリ秒）
+    pub first_ms: Option<i64>,
+    /// 最後に書き出したイベントの時刻（Unix ミリ秒）
+    pub last_ms: Option<i64>,
+    /// `last_ms` の時刻に書き出したイベントのハッシュ（[`event_key`]）
+    pub last_keys: Vec<String>,
+    /// `nextToken` が拒否されて取得し直した場合の取得開始時刻（取得条件の開始時刻の代わりに使用）
+    pub restart_ms: Option<i64>,
+    /// 書き出したイベント数
+    pub events: usize,
+    /// 取得したページ数
+    pub pages: usize,
+    /// 書き出したバイト数（圧縮前）
+    pub bytes: u64,
+    /// 書き出し済みのページまでのファイル長（末尾部分を含まない）
+    pub file_len: u64,
+}
+
+impl Checkpoint {
+    /// `encoder` で新しく書き出し始めるダウンロードの [`Checkpoint`] を生成します。
+    pub fn start(encoder: &Encoder) -> Self {
+        Self {
+            source: encoder.source.clone(),
+            format: encoder.format,
+            columns: encoder.columns().to_vec(),
+            next_token: None,
+            first_ms: None,
+            last_ms: None,
+            last_keys: Vec::new(),
+            restart_ms: None,
+            events: 0,
+            pages: 0,
+            bytes: 0,
+            file_len: 0,
+        }
+    }
+
+    /// 書き出し済みのイベントの続きを変換する [`Encoder`] を返します。
+    pub fn encoder(&self) -> Encoder {
+        Encoder::new(self.source.clone(), self.format, self.columns.clone()).resumed(self.events)
+    }
+
+    /// 次のページを取得する際の開始時刻を返します。
+    pub fn start_ms(&self) -> Option<i64> {
+        self.restart_ms.or(self.source.start_ms)
+    }
+
+    /// 書き出したイベントを記録します。
+    pub fn record(&mut self, event: &LogEvent) {
+        self.events += 1;
+        self.first_ms.get_or_insert(event.timestamp);
+        match self.last_ms {
+            Some(last) if event.timestamp < last => return,
+            Some(last) if event.timestamp == last => {}
+            _ => {
+                self.last_ms = Some(event.timestamp);
+                self.last_keys.clear();
+            }
+        }
+        self.last_keys.push(event_key(event));
+    }
+
+    /// `nextToken` を捨て、最後に書き出したイベントの時刻から取得し直すよう切り替えます。
+    ///
+    /// 取得し直した結果のうち、書き出し済みのイベントを読み飛ばすための [`Written`] を返します。
+    pub fn restart(&mut self) -> Written {
+        self.next_token = None;
+        if self.last_ms.is_some() {
+            self.restart_ms = self.last_ms;
+        }
+        Written {
+            ms: self.last_ms,
+            keys: self.last_keys.clone(),
+        }
+    }
+
+    /// 書き出し先 `path` のチェックポイントを読み込みます。
+    ///
+    /// # Errors
+    ///
+    /// - チェックポイントファイルが無い、または読み込めない場合
+    /// - 内容が不正な場合
+    pub fn load(path: &str) -> Result<Self> {
+        let sidecar = sidecar_path(path);
+        let content = std::fs::read_to_string(&sidecar)
+            .with_context(|| format!("No checkpoint to resume ({})", sidecar))?;
+        let value: Value =
+            serde_json::from_str(&content).with_context(|| format!("{}: invalid JSON", sidecar))?;
+        Self::from_json(&value).with_context(|| format!("{}: invalid checkpoint", sidecar))
+    }
+
+    /// 書き出し先 `path` のチェックポイントを保存します。
+    ///
+    /// 途中で中断しても壊れたファイルが残らないよう、一時ファイルに書き込んでから置き換えます。
+    ///
+    /// # Errors
+    ///
+    /// - 書き込みに失敗した場合
+    pub fn save(&self, path: &str) -> Result<()> {
+        let sidecar = sidecar_path(path);
+        let tmp = format!("{}.tmp", sidecar);
+        let content = serde_json::to_string_pretty(&self.to_json())? + "\n";
+        std::fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp))?;
+        std::fs::rename(&tmp, &sidecar).with_context(|| format!("Failed to write {}", sidecar))
+    }
+
+    /// 書き出し先 `path` のチェックポイントを削除します。無い場合は何もしません。
+    ///
+    /// # Errors
+    ///
+    /// - 削除に失敗した場合
+    pub fn remove(path: &str) -> Result<()> {
+        let sidecar = sidecar_path(path);
+        match std::fs::remove_file(&sidecar) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", sidecar))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "group": self.source.group,
+            "stream": self.source.stream,
+            "pattern": self.source.pattern,
+            "start_ms": self.source.start_ms,
+            "end_ms": self.source.end_ms,
+            "format": self.format.label(),
+            "columns": columns::to_spec(&self.columns),
+            "next_token": self.next_token,
+            "first_ms": self.first_ms,
+            "last_ms": self.last_ms,
+            "last_keys": self.last_keys,
+            "restart_ms": self.restart_ms,
+            "events": self.events,
+            "pages": self.pages,
+            "bytes": self.bytes,
+            "file_len": self.file_len,
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self> {
+        let str_field = |key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        let int_field = |key: &str| value.get(key).and_then(Value::as_i64);
+        let count = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| anyhow!("`{}` is missing", key))
+        };
+        let format_label = str_field("format").ok_or_else(|| anyhow!("`format` is missing"))?;
+        let format = Format::ALL
+            .into_iter()
+            .find(|f| f.label() == format_label)
+            .ok_or_else(|| anyhow!("unknown format `{}`", format_label))?;
+        let columns = columns::parse_spec(&str_field("columns").unwrap_or_default())
+            .map_err(|e| anyhow!("columns: {}", e))?;
+        Ok(Self {
+            source: ExportSource {
+                group: str_field("group").ok_or_else(|| anyhow!("`group` is missing"))?,
+                stream: str_field("stream"),
+                start_ms: int_field("start_ms"),
+                end_ms: int_field("end_ms"),
+                pattern: str_field("pattern"),
+            },
+            format,
+            columns,
+            next_token: str_field("next_token"),
+            first_ms: int_field("first_ms"),
+            last_ms: int_field("last_ms"),
+            last_keys: value
+                .get("last_keys")
+                .and_then(Value::as_array)
+                .map(|keys| {
+                    keys.iter()
+                        .filter_map(Value::as_str)
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            restart_ms: int_field("restart_ms"),
+            events: count("events")? as usize,
+            pages: count("pages")? as usize,
+            bytes: count("bytes")?,
+            file_len: count("file_len")?,
+        })
+    }
+}
+
+/// 取得し直した際に読み飛ばす、書き出し済みのイベント。
+#[derive(Debug, Clone, Default)]
+pub struct Written {
+    /// 書き出し済みのイベントの時刻（Unix ミリ秒）
+    ms: Option<i64>,
+    /// その時刻に書き出したイベントのハッシュ（同じ内容のイベントが複数ある場合は件数分）
+    keys: Vec<String>,
+}
+
+impl Written {
+    /// `event` が書き出し済みであれば `true` を返します。同じイベントを読み飛ばすのは書き出した件数分だけです。
+    pub fn skip(&mut self, event: &LogEvent) -> bool {
+        if self.keys.is_empty() || self.ms != Some(event.timestamp) {
+            return false;
+        }
+        let key = event_key(event);
+        match self.keys.iter().position(|k| *k == key) {
+            Some(i) => {
+                self.keys.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// イベントを見分けるためのハッシュ（ログストリーム名とメッセージの SHA-256）を返します。
+fn event_key(event: &LogEvent) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(event.stream.as_deref().unwrap_or("").as_bytes());
+    hasher.update([0]);
+    hasher.update(event.message.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 書き出し先 `path` のチェックポイントファイルのパスを返します。
+fn sidecar_path(path: &str) -> String {
+    format!("{}{}", path, SUFFIX)
+}
+
+/// 書き出し先 `path` に再開できるチェックポイントがあるかどうかを返します。
+pub fn exists(path: &str) -> bool {
+    std::path::Path::new(&sidecar_path(path)).is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("cleam-checkpoint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name).to_string_lossy().into_owned()
+    }
+
+    fn event(timestamp: i64, stream: &str, message: &str) -> LogEvent {
+        let mut e = LogEvent::new(timestamp, message.to_string());
+        e.stream = Some(stream.to_string());
+        e
+    }
+
+    fn checkpoint() -> Checkpoint {
+        let source = ExportSource {
+            group: "/app/web".to_string(),
+            stream: Some("web-1".to_string()),
+            start_ms: Some(1_000),
+            end_ms: None,
+            pattern: Some("ERROR".to_string()),
+        };
+        let columns = columns::parse_spec("level:7, http.status").unwrap();
+        Checkpoint::start(&Encoder::new(source, Format::Csv, columns))
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = temp_path("round-trip.csv");
+        let mut cp = checkpoint();
+        cp.record(&event(2_000, "web-1", "a"));
+        cp.record(&event(2_000, "web-1", "b"));
+        cp.next_token = Some("token".to_string());
+        cp.pages = 1;
+        cp.bytes = 42;
+        cp.file_len = 30;
+        cp.save(&path).unwrap();
+        assert!(exists(&path));
+
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert_eq!(loaded.source.group, "/app/web");
+        assert_eq!(loaded.source.stream.as_deref(), Some("web-1"));
+        assert_eq!(loaded.source.start_ms, Some(1_000));
+        assert_eq!(loaded.source.end_ms, None);
+        assert_eq!(loaded.source.pattern.as_deref(), Some("ERROR"));
+        assert_eq!(loaded.format, Format::Csv);
+        assert_eq!(columns::to_spec(&loaded.columns), "level:7, http.status:12");
+        assert_eq!(loaded.next_token.as_deref(), Some("token"));
+        assert_eq!(
+            (loaded.first_ms, loaded.last_ms),
+            (Some(2_000), Some(2_000))
+        );
+        assert_eq!(loaded.last_keys, cp.last_keys);
+        assert_eq!(loaded.restart_ms, None);
+        assert_eq!((loaded.events, loaded.pages), (2, 1));
+        assert_eq!((loaded.bytes, loaded.file_len), (42, 30));
+
+        Checkpoint::remove(&path).unwrap();
+        assert!(!exists(&path));
+        assert!(Checkpoint::load(&path).is_err());
+    }
+
+    #[test]
+    fn record_keeps_only_events_at_the_last_time() {
+        let mut cp = checkpoint();
+        cp.record(&event(2_000, "web-1", "a"));
+        cp.record(&event(3_000, "web-1", "b"));
+        cp.record(&event(3_000, "web-2", "b"));
+        assert_eq!(cp.first_ms, Some(2_000));
+        assert_eq!(cp.last_ms, Some(3_000));
+        assert_eq!(cp.last_keys.len(), 2);
+        assert_eq!(cp.events, 3);
+    }
+
+    #[test]
+    fn restart_skips_written_events_once() {
+        let mut cp = checkpoint();
+        cp.record(&event(2_000, "web-1", "a"));
+        cp.record(&event(3_000, "web-1", "dup"));
+        cp.record(&event(3_000, "web-1", "dup"));
+        cp.next_token = Some("expired".to_string());
+        assert_eq!(cp.start_ms(), Some(1_000));
+
+        let mut written = cp.restart();
+        assert_eq!(cp.next_token, None);
+        assert_eq!(cp.start_ms(), Some(3_000));
+        // 取得し直した結果は 3_000 の書き出し済みの 2 件から始まる
+        assert!(written.skip(&event(3_000, "web-1", "dup")));
+        assert!(written.skip(&event(3_000, "web-1", "dup")));
+        assert!(!written.skip(&event(3_000, "web-1", "dup")));
+        assert!(!written.skip(&event(3_000, "web-2", "other")));
+        assert!(!written.skip(&event(4_000, "web-1", "a")));
+    }
+
+    #[test]
+    fn restart_before_any_event_uses_the_source_start() {
+        let mut cp = checkpoint();
+        cp.next_token = Some("expired".to_string());
+        let mut written = cp.restart();
+        assert_eq!(cp.start_ms(), Some(1_000));
+        assert!(!written.skip(&event(1_000, "web-1", "a")));
+    }
+}
@@ -0,0 +1,406 @@
+//! スクリプト向けのヘッドレスなサブコマンド。
+//!
+//! サブコマンドを指定した場合は TUI を起動せず、[`aws`] モジュールで取得した結果を
+//! テキスト・JSON・JSONL で標準出力に書き出します。`tail` は `aws logs tail` と同様に、
+//! 到着したイベントを（`--follow` の場合は新しいイベントを待ちながら）書き出します。
+//! 認証・表示タイムゾーン（`CLEAM_TZ`）・時刻の解釈・マスキング規則は TUI と共通です。
+//!
+//! 失敗した場合はエラーを標準エラー出力に書き出して終了コード 1 で、引数が不正な場合は
+//! 終了コード 2 で終了します。出力先のパイプが閉じられた場合（`| head` など）は正常終了します。
+
+use anyhow::{Context, Result, bail};
+use aws_sdk_cloudwatchlogs::Client;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::time::Duration;
+
+use crate::aws::{self, StreamFilter};
+use crate::export::{Encoder, ExportSource, Format};
+use crate::time;
+use crate::ui::ansi;
+
+/// `tail --follow` で新しいイベントを問い合わせる間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// コマンドライン引数。
+#[derive(Debug, Parser)]
+#[command(version, about = "Viewing and downloading Amazon CloudWatch Logs")]
+pub struct Cli {
+    /// 実行するサブコマンド（`None` の場合は TUI を起動）
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// ヘッドレスなサブコマンド。
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// ロググループの一覧
+    #[command(about = "List log groups")]
+    Groups {
+        #[arg(long, help = "Only log groups whose name starts with this prefix")]
+        prefix: Option<String>,
+        #[command(flatten)]
+        output: OutputArg,
+    },
+    /// ロググループのログストリームの一覧（最終イベント時刻の降順）
+    #[command(about = "List the log streams of a log group, most recent first")]
+    Streams {
+        #[arg(help = "Log group name")]
+        group: String,
+        #[command(flatten)]
+        output: OutputArg,
+    },
+    /// 条件に一致するログイベント（全ページ）
+    #[command(about = "Print every log event matching the conditions")]
+    Events {
+        #[arg(help = "Log group name")]
+        group: String,
+        #[arg(long, help = "Only events of this log stream")]
+        stream: Option<String>,
+        #[arg(
+            long,
+            help = "Start time: `2024-01-01 12:00`, `12:00`, `15m ago` or `now`"
+        )]
+        start: Option<String>,
+        #[arg(long, help = "End time, in the same forms as --start")]
+        end: Option<String>,
+        #[arg(long, help = "CloudWatch Logs filter pattern")]
+        filter: Option<String>,
+        #[command(flatten)]
+        output: OutputArg,
+    },
+    /// 最近のログイベントの表示と新しいイベントの追跡
+    #[command(about = "Print recent log events and optionally follow new ones")]
+    Tail(TailArgs),
+}
+
+/// `tail` の引数。
+#[derive(Debug, Args)]
+pub struct TailArgs {
+    /// ロググループ名
+    #[arg(help = "Log group name")]
+    pub group: String,
+    /// ログストリーム名の接頭辞
+    #[arg(long, help = "Only log streams whose name starts with this prefix")]
+    pub stream_prefix: Option<String>,
+    /// CloudWatch Logs フィルタパターン
+    #[arg(long, help = "CloudWatch Logs filter pattern")]
+    pub filter: Option<String>,
+    /// 表示を開始する時刻（`10m` のような期間、または絶対時刻・相対時刻）
+    #[arg(
+        long,
+        default_value = "10m",
+        help = "Start from this long ago (`10m`, `2h`, `1d`) or from a time such as `12:00`"
+    )]
+    pub since: String,
+    /// 新しいイベントを待ち続けるかどうか
+    #[arg(short, long, help = "Keep polling for new events until interrupted")]
+    pub follow: bool,
+    /// JSON メッセージを整形して表示するかどうか
+    #[arg(long, help = "Pretty-print and highlight JSON messages")]
+    pub pretty: bool,
+    /// 色付けの有無
+    #[arg(long, value_enum, default_value_t = ColorWhen::Auto, help = "Color output")]
+    pub color: ColorWhen,
+}
+
+/// 色付けする条件。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorWhen {
+    /// 標準出力が端末で、`NO_COLOR` が未設定の場合
+    #[value(help = "When stdout is a terminal and NO_COLOR is not set")]
+    Auto,
+    /// 常に色付けする
+    #[value(help = "Always")]
+    Always,
+    /// 色付けしない
+    #[value(help = "Never")]
+    Never,
+}
+
+impl ColorWhen {
+    /// 色付けするかどうかを返します。
+    fn enabled(self) -> bool {
+        match self {
+            Self::Auto => {
+                io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// 出力形式の指定。
+#[derive(Debug, Args)]
+pub struct OutputArg {
+    /// 出力形式
+    #[arg(short, long, value_enum, default_value_t = Output::Text, help = "Output format")]
+    pub output: Output,
+}
+
+/// 標準出力に書き出す形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    /// 1 行 1 件のテキスト
+    #[value(help = "One line per item")]
+    Text,
+    /// 整形済みの JSON 配列
+    #[value(help = "A pretty-printed JSON array")]
+    Json,
+    /// 1 行 1 件の JSON
+    #[value(help = "One JSON object per line")]
+    Jsonl,
+}
+
+/// サブコマンドを実行します。
+///
+/// # Errors
+///
+/// - 時刻の指定が不正な場合
+/// - AWS API 呼び出し・標準出力への書き込みに失敗した場合（パイプが閉じられた場合を除く）
+pub async fn run(client: Client, command: Command) -> Result<()> {
+    let mut out = BufWriter::new(io::stdout());
+    let result = match command {
+        Command::Groups { prefix, output } => {
+            groups(&client, prefix.as_deref(), output.output, &mut out).await
+        }
+        Command::Streams { group, output } => {
+            streams(&client, &group, output.output, &mut out).await
+        }
+        Command::Events {
+            group,
+            stream,
+            start,
+            end,
+            filter,
+            output,
+        } => {
+            let source = ExportSource {
+                group,
+                stream,
+                start_ms: parse_time("--start", start.as_deref())?,
+                end_ms: parse_time("--end", end.as_deref())?,
+                pattern: filter.filter(|p| !p.trim().is_empty()),
+            };
+            if let (Some(start), Some(end)) = (source.start_ms, source.end_ms)
+                && start > end
+            {
+                bail!("--start is after --end");
+            }
+            events(&client, source, output.output, &mut out).await
+        }
+        Command::Tail(args) => tail(&client, args, &mut out).await,
+    };
+    match result.and_then(|()| Ok(out.flush()?)) {
+        Err(e) if is_broken_pipe(&e) => Ok(()),
+        result => result,
+    }
+}
+
+/// 出力先のパイプが閉じられたことによるエラーかどうかを返します。
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+/// 時刻の指定を Unix ミリ秒に変換します。指定が無い場合は `None` を返します。
+fn parse_time(flag: &str, spec: Option<&str>) -> Result<Option<i64>> {
+    match spec {
+        Some(spec) if !spec.trim().is_empty() => time::parse_time_spec_to_ms(spec)
+            .map(Some)
+            .with_context(|| format!("{}: {}", flag, spec)),
+        _ => Ok(None),
+    }
+}
+
+/// `tail --since` の指定を Unix ミリ秒に変換します。
+///
+/// `10m` のような期間は現在から遡った時刻、それ以外は絶対時刻・相対時刻として解釈します。
+fn parse_since(spec: &str) -> Result<i64> {
+    time::parse_time_spec_to_ms(&format!("-{}", spec.trim()))
+        .or_else(|_| time::parse_time_spec_to_ms(spec))
+        .with_context(|| format!("--since: {}", spec))
+}
+
+/// `items` を `output` の形式で書き出します。テキストの場合は `text` で 1 行分に変換します。
+fn write_items(
+    out: &mut impl Write,
+    output: Output,
+    items: &[Value],
+    text: impl Fn(&Value) -> String,
+) -> Result<()> {
+    match output {
+        Output::Text => {
+            for item in items {
+                writeln!(out, "{}", text(item))?;
+            }
+        }
+        Output::Json => {
+            writeln!(out, "{}", serde_json::to_string_pretty(items)?)?;
+        }
+        Output::Jsonl => {
+            for item in items {
+                writeln!(out, "{}", item)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn groups(
+    client: &Client,
+    prefix: Option<&str>,
+    output: Output,
+    out: &mut impl Write,
+) -> Result<()> {
+    let mut items = Vec::new();
+    let mut token = None;
+    loop {
+        let (page, next) = aws::fetch_log_groups(client, prefix, token).await?;
+        items.extend(page.into_iter().map(|g| json!({ "name": g.name })));
+        match next {
+            Some(next) => token = Some(next),
+            None => break,
+        }
+    }
+    write_items(out, output, &items, |item| {
+        item["name"].as_str().unwrap_or_default().to_string()
+    })
+}
+
+async fn streams(client: &Client, group: &str, output: Output, out: &mut impl Write) -> Result<()> {
+    let mut items = Vec::new();
+    let mut token = None;
+    loop {
+        let (page, next) = aws::fetch_log_streams(client, group, token).await?;
+        items.extend(page.into_iter().map(|s| {
+            json!({
+                "name": s.name,
+                "first_event_time": s.first_event_time,
+                "last_event_time": s.last_event_time,
+            })
+        }));
+        match next {
+            Some(next) => token = Some(next),
+            None => break,
+        }
+    }
+    // テキストは `最終イベント時刻<TAB>ストリーム名`（イベントが無い場合は `-`）
+    write_items(out, output, &items, |item| {
+        let last = item["last_event_time"]
+            .as_i64()
+            .map(time::format_ms)
+            .unwrap_or_else(|| "-".to_string());
+        format!("{}\t{}", last, item["name"].as_str().unwrap_or_default())
+    })
+}
+
+/// 条件に一致するイベントをページごとに書き出します。
+///
+/// テキストと JSON 配列はファイルへの書き出しと同じ形式です。JSONL は取得条件の先頭行を省き、
+/// イベントの行だけを書き出します。
+async fn events(
+    client: &Client,
+    source: ExportSource,
+    output: Output,
+    out: &mut impl Write,
+) -> Result<()> {
+    let format = match output {
+        Output::Text => Format::Text,
+        Output::Json => Format::JsonArray,
+        Output::Jsonl => Format::Jsonl,
+    };
+    let mut encoder = Encoder::new(source.clone(), format, Vec::new());
+    if format != Format::Jsonl {
+        out.write_all(encoder.header().as_bytes())?;
+    }
+    let mut token = None;
+    loop {
+        let (page, next) = aws::fetch_log_events(
+            client,
+            &source.group,
+            source.stream.as_deref().into(),
+            source.start_ms,
+            source.end_ms,
+            source.pattern.clone(),
+            token,
+        )
+        .await?;
+        for event in &page {
+            out.write_all(encoder.event(event).as_bytes())?;
+        }
+        out.flush()?;
+        match next {
+            Some(next) => token = Some(next),
+            None => break,
+        }
+    }
+    out.write_all(encoder.footer().as_bytes())?;
+    Ok(())
+}
+
+/// `--since` 以降のイベントを書き出し、`--follow` の場合は新しいイベントを待って書き出し続けます。
+///
+/// 問い合わせごとに直前の問い合わせで最も新しかった時刻から取得し直し、
+/// その時刻に書き出し済みのイベントは読み飛ばします。
+async fn tail(client: &Client, args: TailArgs, out: &mut impl Write) -> Result<()> {
+    let streams = match args.stream_prefix.as_deref() {
+        Some(prefix) if !prefix.is_empty() => StreamFilter::Prefix(prefix),
+        _ => StreamFilter::All,
+    };
+    let pattern = args.filter.filter(|p| !p.trim().is_empty());
+    let color = args.color.enabled();
+    let mut start_ms = parse_since(&args.since)?;
+    // `start_ms` の時刻に書き出し済みのイベント
+    let mut printed: HashSet<(Option<String>, String)> = HashSet::new();
+    loop {
+        let mut newest = start_ms;
+        let mut newest_printed = HashSet::new();
+        let mut token = None;
+        loop {
+            let (page, next) = aws::fetch_log_events(
+                client,
+                &args.group,
+                streams,
+                Some(start_ms),
+                None,
+                pattern.clone(),
+                token,
+            )
+            .await?;
+            for event in &page {
+                let key = (event.stream.clone(), event.message.clone());
+                if event.timestamp == start_ms && printed.contains(&key) {
+                    continue;
+                }
+                out.write_all(ansi::event_text(event, args.pretty, color).as_bytes())?;
+                if event.timestamp > newest {
+                    newest = event.timestamp;
+                    newest_printed.clear();
+                }
+                if event.timestamp == newest {
+                    newest_printed.insert(key);
+                }
+            }
+            out.flush()?;
+            match next {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+        if !args.follow {
+            return Ok(());
+        }
+        if newest == start_ms {
+            printed.extend(newest_printed);
+        } else {
+            start_ms = newest;
+            printed = newest_printed;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,49 @@
+//! OSC 52 エスケープシーケンスによるクリップボードへのコピー。
+//!
+//! 端末エミュレータ自身にクリップボードへの書き込みを依頼するため、SSH 越しでも
+//! クリップボード用のデーモンなしで動作します。tmux 内ではパススルー用のシーケンスで包みます。
+
+use anyhow::{Context, Result};
+use std::io::Write;
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// テキストをシステムのクリップボードにコピーします。
+///
+/// # Errors
+///
+/// - 端末への書き込みに失敗した場合
+pub fn copy(text: &str) -> Result<()> {
+    let osc = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    let seq = if std::env::var_os("TMUX").is_some() {
+        // tmux のパススルー: ESC を二重にして DCS で包む
+        format!("\x1bPtmux;{}\x1b\\", osc.replace('\x1b', "\x1b\x1b"))
+    } else {
+        osc
+    };
+    let mut out = std::io::stdout();
+    out.write_all(seq.as_bytes())
+        .and_then(|_| out.flush())
+        .context("Failed to write to terminal")
+}
+
+/// パディング付きの標準 Base64 に変換します。
+fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
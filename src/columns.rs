@@ -0,0 +1,157 @@
+//! イベント一覧に表示する JSON フィールド列。
+//!
+//! `level:7, requestId:36, http.status` のような指定文字列を列定義に変換し、
+//! ロググループごとに `columns.json` として設定ディレクトリに保存します。
+
+use anyhow::Result;
+
+use crate::config;
+use crate::json_path::JsonPath;
+
+/// 列設定を保存するファイル名
+const COLUMNS_FILE: &str = "columns.json";
+
+/// 幅を省略した列の表示幅
+const DEFAULT_WIDTH: usize = 12;
+
+/// 指定できる列幅の上限
+const MAX_WIDTH: usize = 200;
+
+/// JSON フィールド列の定義。
+#[derive(Debug, Clone)]
+pub struct Column {
+    /// 取り出すフィールドのパス
+    pub path: JsonPath,
+    /// 表示幅（文字数）。超える値は `…` で切り詰めます。
+    pub width: usize,
+}
+
+impl Column {
+    /// 列見出し（パス表記）を返します。
+    pub fn label(&self) -> String {
+        self.path.to_string()
+    }
+
+    /// JSON メッセージからこの列の値を取り出して表示文字列にします。
+    ///
+    /// 文字列はクォートせずにそのまま、それ以外は JSON 表記で返します。
+    /// フィールドが無い場合は空文字列を返します。
+    pub fn cell(&self, value: &serde_json::Value) -> String {
+        match self.path.lookup(value) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(v) => v.to_string(),
+        }
+    }
+}
+
+/// `path[:width]` をカンマ区切りで並べた指定文字列を列定義に変換します。
+///
+/// 空文字列は列なし（通常表示）を表します。
+///
+/// # Errors
+///
+/// パスが不正な場合、または幅が 1〜200 の整数でない場合
+pub fn parse_spec(spec: &str) -> Result<Vec<Column>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|item| {
+            // `["a:b"]` のようにクォートしたキーの `:` で分けないよう、パスを読み取った残りを幅とする
+            let (path, rest) =
+                JsonPath::parse_prefix(item).map_err(|e| format!("{}: {}", item, e))?;
+            let width = match rest.trim_start().strip_prefix(':') {
+                Some(width) => width
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|w| (1..=MAX_WIDTH).contains(w))
+                    .ok_or_else(|| {
+                        format!("{}: 幅は 1〜{} の整数で指定してください", item, MAX_WIDTH)
+                    })?,
+                None if rest.trim().is_empty() => DEFAULT_WIDTH,
+                None => {
+                    return Err(format!(
+                        "{}: パスの後に不要な文字があります: {}",
+                        item,
+                        rest.trim()
+                    ));
+                }
+            };
+            Ok(Column { path, width })
+        })
+        .collect()
+}
+
+/// 列定義を [`parse_spec`] で解釈できる指定文字列に戻します。
+pub fn to_spec(columns: &[Column]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("{}:{}", c.label(), c.width))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// ロググループに保存された列定義を読み込みます。
+///
+/// 設定が無い、または読み込めない場合は空の列定義を返します。
+pub fn load(group: &str) -> Vec<Column> {
+    config::load_json(COLUMNS_FILE)
+        .ok()
+        .flatten()
+        .and_then(|v| v.get(group).and_then(|s| s.as_str()).map(str::to_string))
+        .and_then(|spec| parse_spec(&spec).ok())
+        .unwrap_or_default()
+}
+
+/// ロググループの列定義を保存します。空の列定義の場合は設定を削除します。
+///
+/// # Errors
+///
+/// 既存の設定ファイルが壊れている場合、または書き込みに失敗した場合
+pub fn save(group: &str, columns: &[Column]) -> Result<()> {
+    let mut all = match config::load_json(COLUMNS_FILE)? {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    if columns.is_empty() {
+        all.remove(group);
+    } else {
+        all.insert(group.to_string(), to_spec(columns).into());
+    }
+    config::save_json(COLUMNS_FILE, &serde_json::Value::Object(all))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(input: &str) -> Result<String, String> {
+        parse_spec(input).map(|columns| to_spec(&columns))
+    }
+
+    #[test]
+    fn widths_are_optional() {
+        assert_eq!(
+            spec("level:7, http.status,").as_deref(),
+            Ok("level:7, http.status:12")
+        );
+        assert_eq!(spec("").as_deref(), Ok(""));
+    }
+
+    #[test]
+    fn colon_in_quoted_key_is_not_a_width() {
+        assert_eq!(spec(r#"["a:b"]"#).as_deref(), Ok(r#"["a:b"]:12"#));
+        assert_eq!(spec(r#"["a:1"]:30"#).as_deref(), Ok(r#"["a:1"]:30"#));
+        assert_eq!(spec(r#"x["a:b"] : 5"#).as_deref(), Ok(r#"x["a:b"]:5"#));
+    }
+
+    #[test]
+    fn invalid_items_are_rejected() {
+        assert!(spec("level:0").is_err());
+        assert!(spec("level:abc").is_err());
+        assert!(spec("level:").is_err());
+        assert!(spec("level x").is_err());
+        assert!(spec("a.:5").is_err());
+    }
+}
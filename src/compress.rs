@@ -0,0 +1,364 @@
+//! 書き出しファイルの gzip・zstd 圧縮と展開。
+//!
+//! 書き出し先のパスが `.gz`・`.zst` で終わる場合は、書き出しながら圧縮します
+//! （`events.jsonl.gz` のように形式の拡張子の後ろに付けます）。
+//! 読み込み時は拡張子ではなく先頭のマジックバイトで圧縮形式を判定します。
+//! 全ページのダウンロードはページごとに圧縮して書き出し、再開する場合は [`Writer::append`] で
+//! 既存のファイルに追記します。
+
+use anyhow::{Context, Result, bail};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+
+/// gzip ファイルの先頭バイト
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// zstd フレームの先頭バイト
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// 圧縮形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// 圧縮しない
+    None,
+    /// gzip（`.gz`）
+    Gzip,
+    /// zstd（`.zst`）
+    Zstd,
+}
+
+impl Compression {
+    /// 末尾の拡張子から圧縮形式を判定します。
+    pub fn from_path(path: &str) -> Self {
+        Self::split(path).1
+    }
+
+    /// `path` を圧縮の拡張子を除いた部分と圧縮形式に分けます。
+    pub fn split(path: &str) -> (&str, Self) {
+        if let Some((stem, ext)) = path.rsplit_once('.')
+            && !stem.is_empty()
+        {
+            match ext.to_ascii_lowercase().as_str() {
+                "gz" | "gzip" => return (stem, Self::Gzip),
+                "zst" | "zstd" => return (stem, Self::Zstd),
+                _ => {}
+            }
+        }
+        (path, Self::None)
+    }
+
+    /// 画面表示用の名前を返します。圧縮しない場合は `None` です。
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gzip"),
+            Self::Zstd => Some("zstd"),
+        }
+    }
+}
+
+/// 書き出し先のファイル。パスの拡張子に応じて書き込みながら圧縮します。
+///
+/// 通常は圧縮したデータをそのままファイルへ書き出します（ファイル全体が 1 つの gzip メンバー・
+/// zstd フレームになります）。
+///
+/// 再開できる書き出し（`resumable`）では、[`Write::flush`] までに書き込んだ内容を 1 つの
+/// gzip メンバー・zstd フレームとしてメモリ上で圧縮してからファイルへ書き出します。
+/// 連結したメンバー・フレームは 1 つのファイルとして展開できるため、
+/// `flush` した位置でファイルを切り詰めて続きを追記できます。
+/// 最後の内容を書き出すため、書き終えたら [`Writer::finish`] を呼び出します。
+pub struct Writer {
+    sink: Sink,
+    /// 書き出し済みのメンバー・フレームを含むファイル長（再開できる書き出しと非圧縮の場合のみ）
+    file_len: u64,
+    /// 再開できる書き出しで、まだファイルへ書き出していない内容があるかどうか
+    pending: bool,
+}
+
+/// 書き込んだ内容の書き出し先。
+enum Sink {
+    /// 圧縮しない（ファイルへ直接書き込む）
+    Plain(BufWriter<File>),
+    /// gzip で圧縮しながらファイルへ書き込む
+    Gzip(GzEncoder<BufWriter<File>>),
+    /// zstd で圧縮しながらファイルへ書き込む
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+    /// `flush` ごとに圧縮したメンバー・フレームをファイルへ書き出す（再開できる書き出し）
+    Segmented {
+        /// 書き出し先のファイル
+        file: BufWriter<File>,
+        /// 書き出し前の圧縮データ
+        segment: Segment,
+    },
+}
+
+/// 書き出し前の圧縮データ。
+enum Segment {
+    /// gzip
+    Gzip(GzEncoder<Vec<u8>>),
+    /// zstd
+    Zstd(zstd::Encoder<'static, Vec<u8>>),
+}
+
+impl Writer {
+    /// `path` を作成（既存の場合は切り詰め）して書き出し先を開きます。
+    ///
+    /// `resumable` の場合は、`flush` した位置から [`Writer::append`] で再開できるように書き出します。
+    ///
+    /// # Errors
+    ///
+    /// - ファイルを作成できない場合
+    pub fn create(path: &str, resumable: bool) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create {}", path))?;
+        Self::open(path, file, 0, resumable)
+    }
+
+    /// 既存の `path` を `len` バイトに切り詰め、続きを再開できるように追記する書き出し先を開きます。
+    ///
+    /// # Errors
+    ///
+    /// - ファイルを開けない場合、またはファイルが `len` バイトより短い場合
+    pub fn append(path: &str, len: u64) -> Result<Self> {
+        let file = open_truncated(path, len)?;
+        Self::open(path, file, len, true)
+    }
+
+    /// 既存の `path` の末尾に追記する書き出し先を開きます。
+    ///
+    /// `resumable` の場合は、`flush` した位置から [`Writer::append`] で再開できるように書き出します。
+    ///
+    /// # Errors
+    ///
+    /// - ファイルを開けない場合
+    pub fn append_to_end(path: &str, resumable: bool) -> Result<Self> {
+        let len = std::fs::metadata(path)
+            .with_context(|| format!("Failed to open {}", path))?
+            .len();
+        let file = open_truncated(path, len)?;
+        Self::open(path, file, len, resumable)
+    }
+
+    fn open(path: &str, file: File, file_len: u64, resumable: bool) -> Result<Self> {
+        let file = BufWriter::new(file);
+        let level = zstd::DEFAULT_COMPRESSION_LEVEL;
+        let start = || -> io::Result<Sink> {
+            Ok(match (Compression::from_path(path), resumable) {
+                (Compression::None, _) => Sink::Plain(file),
+                (Compression::Gzip, false) => {
+                    Sink::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+                }
+                (Compression::Zstd, false) => Sink::Zstd(zstd::Encoder::new(file, level)?),
+                (Compression::Gzip, true) => Sink::Segmented {
+                    file,
+                    segment: Segment::Gzip(GzEncoder::new(
+                        Vec::new(),
+                        flate2::Compression::default(),
+                    )),
+                },
+                (Compression::Zstd, true) => Sink::Segmented {
+                    file,
+                    segment: Segment::Zstd(zstd::Encoder::new(Vec::new(), level)?),
+                },
+            })
+        };
+        Ok(Self {
+            sink: start().context("Failed to start compression")?,
+            file_len,
+            pending: false,
+        })
+    }
+
+    /// `flush` 済みの内容のファイル長を返します（再開できる書き出しと非圧縮の場合のみ正確です）。
+    pub fn file_len(&self) -> u64 {
+        self.file_len
+    }
+
+    /// 残りの内容を書き出し、ファイルへの書き込みを完了します。
+    ///
+    /// # Errors
+    ///
+    /// - ファイルの書き込みに失敗した場合
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.sink {
+            Sink::Gzip(encoder) => encoder.finish()?.flush(),
+            Sink::Zstd(encoder) => encoder.finish()?.flush(),
+            Sink::Plain(_) | Sink::Segmented { .. } => self.flush(),
+        }
+    }
+}
+
+/// 既存の `path` を `len` バイトに切り詰め、末尾に書き込む位置で開きます。
+fn open_truncated(path: &str, len: u64) -> Result<File> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path))?;
+    let actual = file.metadata()?.len();
+    if actual < len {
+        bail!(
+            "{} is shorter than expected ({} < {} bytes)",
+            path,
+            actual,
+            len
+        );
+    }
+    file.set_len(len)
+        .with_context(|| format!("Failed to truncate {}", path))?;
+    file.seek(SeekFrom::End(0))?;
+    Ok(file)
+}
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.sink {
+            Sink::Plain(file) => {
+                let n = file.write(buf)?;
+                self.file_len += n as u64;
+                Ok(n)
+            }
+            Sink::Gzip(encoder) => encoder.write(buf),
+            Sink::Zstd(encoder) => encoder.write(buf),
+            Sink::Segmented { segment, .. } => {
+                self.pending |= !buf.is_empty();
+                match segment {
+                    Segment::Gzip(encoder) => encoder.write(buf),
+                    Segment::Zstd(encoder) => encoder.write(buf),
+                }
+            }
+        }
+    }
+
+    /// 書き込んだ内容をファイルへ書き出します。
+    ///
+    /// 再開できる書き出しでは、書き込んだ内容を圧縮のメンバー・フレームとして閉じてから書き出します。
+    fn flush(&mut self) -> io::Result<()> {
+        let (file, segment) = match &mut self.sink {
+            Sink::Plain(file) => return file.flush(),
+            Sink::Gzip(encoder) => return encoder.flush(),
+            Sink::Zstd(encoder) => return encoder.flush(),
+            Sink::Segmented { file, segment } => (file, segment),
+        };
+        if self.pending {
+            let bytes = match segment {
+                Segment::Gzip(encoder) => std::mem::replace(
+                    encoder,
+                    GzEncoder::new(Vec::new(), flate2::Compression::default()),
+                )
+                .finish()?,
+                Segment::Zstd(encoder) => std::mem::replace(
+                    encoder,
+                    zstd::Encoder::new(Vec::new(), zstd::DEFAULT_COMPRESSION_LEVEL)?,
+                )
+                .finish()?,
+            };
+            self.pending = false;
+            file.write_all(&bytes)?;
+            self.file_len += bytes.len() as u64;
+        }
+        file.flush()
+    }
+}
+
+/// ファイルを読み込み、gzip・zstd で圧縮されている場合は展開した内容を返します。
+///
+/// 圧縮形式は先頭のマジックバイトで判定します。連結された複数の gzip メンバー・zstd フレームも
+/// 続けて展開します。
+///
+/// # Errors
+///
+/// - ファイルの読み込みまたは展開に失敗した場合
+/// - 展開した内容が UTF-8 でない場合
+pub fn read_to_string(path: &str) -> Result<String> {
+    let raw = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+    let bytes = if raw.starts_with(&GZIP_MAGIC) {
+        let mut out = Vec::new();
+        MultiGzDecoder::new(raw.as_slice())
+            .read_to_end(&mut out)
+            .with_context(|| format!("Failed to decompress {} (gzip)", path))?;
+        out
+    } else if raw.starts_with(&ZSTD_MAGIC) {
+        zstd::stream::decode_all(raw.as_slice())
+            .with_context(|| format!("Failed to decompress {} (zstd)", path))?
+    } else {
+        raw
+    };
+    String::from_utf8(bytes).with_context(|| format!("{} is not valid UTF-8", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("cleam-compress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name).to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn append_truncates_to_the_flushed_length() {
+        let path = temp_path("truncate.txt");
+        let mut out = Writer::create(&path, true).unwrap();
+        out.write_all(b"page 1\n").unwrap();
+        out.flush().unwrap();
+        let len = out.file_len();
+        // 中断時に書き出した末尾部分
+        out.write_all(b"footer\n").unwrap();
+        out.finish().unwrap();
+        assert_eq!(len, 7);
+
+        let mut out = Writer::append(&path, len).unwrap();
+        assert_eq!(out.file_len(), len);
+        out.write_all(b"page 2\n").unwrap();
+        out.finish().unwrap();
+        assert_eq!(read_to_string(&path).unwrap(), "page 1\npage 2\n");
+        assert!(Writer::append(&path, 1_000).is_err());
+    }
+
+    #[test]
+    fn resumed_compressed_output_stays_readable() {
+        for name in ["resume.jsonl.gz", "resume.jsonl.zst"] {
+            let path = temp_path(name);
+            let mut out = Writer::create(&path, true).unwrap();
+            out.write_all(b"{\"n\":1}\n").unwrap();
+            out.flush().unwrap();
+            out.write_all(b"{\"n\":2}\n").unwrap();
+            out.flush().unwrap();
+            let len = out.file_len();
+            out.write_all(b"{\"n\":3}\n").unwrap();
+            out.finish().unwrap();
+            assert_eq!(
+                read_to_string(&path).unwrap(),
+                "{\"n\":1}\n{\"n\":2}\n{\"n\":3}\n"
+            );
+
+            // 3 件目は再開時に切り詰めて取得し直す
+            let mut out = Writer::append(&path, len).unwrap();
+            out.write_all(b"{\"n\":3}\n{\"n\":4}\n").unwrap();
+            out.finish().unwrap();
+            assert_eq!(
+                read_to_string(&path).unwrap(),
+                "{\"n\":1}\n{\"n\":2}\n{\"n\":3}\n{\"n\":4}\n",
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn streamed_compressed_output_is_readable() {
+        for name in ["stream.txt.gz", "stream.txt.zst"] {
+            let path = temp_path(name);
+            let mut out = Writer::create(&path, false).unwrap();
+            out.write_all(b"a\n").unwrap();
+            out.flush().unwrap();
+            out.write_all(b"b\n").unwrap();
+            out.finish().unwrap();
+            let mut out = Writer::append_to_end(&path, false).unwrap();
+            out.write_all(b"c\n").unwrap();
+            out.finish().unwrap();
+            assert_eq!(read_to_string(&path).unwrap(), "a\nb\nc\n", "{}", name);
+        }
+    }
+}
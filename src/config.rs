@@ -0,0 +1,48 @@
+//! ユーザー設定ファイルの読み書き。
+//!
+//! 設定は `$XDG_CONFIG_HOME/cleam/`（未設定の場合は `~/.config/cleam/`）に
+//! JSON ファイルとして保存します。
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// 設定ディレクトリのパスを返します。ホームディレクトリが不明な場合は `None` を返します。
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("cleam"))
+}
+
+/// 設定ディレクトリ内の JSON ファイルを読み込みます。ファイルが無い場合は `None` を返します。
+///
+/// # Errors
+///
+/// ファイルの読み込み、または JSON の解釈に失敗した場合
+pub fn load_json(name: &str) -> Result<Option<serde_json::Value>> {
+    let Some(path) = config_dir().map(|d| d.join(name)) else {
+        return Ok(None);
+    };
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let value = serde_json::from_str(&content)
+        .with_context(|| format!("{}: invalid JSON", path.display()))?;
+    Ok(Some(value))
+}
+
+/// 設定ディレクトリ内に JSON ファイルを書き込みます。ディレクトリが無い場合は作成します。
+///
+/// # Errors
+///
+/// ホームディレクトリが不明な場合、またはディレクトリ作成・書き込みに失敗した場合
+pub fn save_json(name: &str, value: &serde_json::Value) -> Result<()> {
+    let dir = config_dir().context("Cannot determine config directory (HOME is not set)")?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(name);
+    let content = serde_json::to_string_pretty(value)? + "\n";
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,271 @@
+//! 2 つのログメッセージの差分計算。
+//!
+//! 両方が JSON の場合はキー単位の構造的な差分を、それ以外は行単位の差分を計算し、
+//! 左右に並べて表示できる行の列として返します。
+
+use serde_json::Value;
+
+/// 行単位の差分計算で扱う最大の行数の積。これを超える場合は行番号ごとに比較します。
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// JSON ツリー 1 段あたりのインデント幅
+const INDENT: usize = 2;
+
+/// 差分の 1 行の種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// 左右で同じ
+    Same,
+    /// 左にのみある
+    Removed,
+    /// 右にのみある
+    Added,
+    /// 左右で値が異なる
+    Changed,
+}
+
+/// 左右に並べて表示する差分の 1 行。片側にしかない行は反対側が `None` になります。
+#[derive(Debug, Clone)]
+pub struct Row {
+    /// 行の種類
+    pub change: Change,
+    /// 左側（比較元）の行
+    pub left: Option<String>,
+    /// 右側（比較先）の行
+    pub right: Option<String>,
+}
+
+impl Row {
+    fn same(line: String) -> Self {
+        Self {
+            change: Change::Same,
+            left: Some(line.clone()),
+            right: Some(line),
+        }
+    }
+}
+
+/// 2 つのメッセージの差分を計算します。
+///
+/// 両方が JSON のオブジェクトまたは配列として解析できる場合は構造的な差分、
+/// それ以外は行単位の差分を返します。2 番目の戻り値は構造的な差分かどうかです。
+pub fn diff(left: &str, right: &str) -> (Vec<Row>, bool) {
+    match (parse_json(left), parse_json(right)) {
+        (Some(l), Some(r)) => {
+            let mut rows = Vec::new();
+            diff_json("", Some(&l), Some(&r), 0, &mut rows);
+            (rows, true)
+        }
+        _ => (diff_lines(left, right), false),
+    }
+}
+
+fn parse_json(s: &str) -> Option<Value> {
+    let trimmed = s.trim();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(trimmed).ok()
+}
+
+// ── JSON ──────────────────────────────────────────────────────────────────────
+
+/// `prefix`（`"key": ` または空）付きの値の差分を `rows` に追加します。
+fn diff_json(
+    prefix: &str,
+    l: Option<&Value>,
+    r: Option<&Value>,
+    depth: usize,
+    rows: &mut Vec<Row>,
+) {
+    let indent = " ".repeat(depth * INDENT);
+    match (l, r) {
+        (Some(a), Some(b)) if a == b => {
+            for line in tree_lines(prefix, a, depth) {
+                rows.push(Row::same(line));
+            }
+        }
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            rows.push(Row::same(format!("{}{}{{", indent, prefix)));
+            let keys = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k)));
+            for key in keys {
+                let child = format!("{}: ", Value::String(key.clone()));
+                diff_json(&child, a.get(key), b.get(key), depth + 1, rows);
+            }
+            rows.push(Row::same(format!("{}}}", indent)));
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            rows.push(Row::same(format!("{}{}[", indent, prefix)));
+            for i in 0..a.len().max(b.len()) {
+                diff_json("", a.get(i), b.get(i), depth + 1, rows);
+            }
+            rows.push(Row::same(format!("{}]", indent)));
+        }
+        (Some(a), Some(b)) if is_scalar(a) && is_scalar(b) => rows.push(Row {
+            change: Change::Changed,
+            left: Some(format!("{}{}{}", indent, prefix, a)),
+            right: Some(format!("{}{}{}", indent, prefix, b)),
+        }),
+        (a, b) => {
+            // 片側にのみある値、または型の異なる値
+            if let Some(a) = a {
+                for line in tree_lines(prefix, a, depth) {
+                    rows.push(Row {
+                        change: Change::Removed,
+                        left: Some(line),
+                        right: None,
+                    });
+                }
+            }
+            if let Some(b) = b {
+                for line in tree_lines(prefix, b, depth) {
+                    rows.push(Row {
+                        change: Change::Added,
+                        left: None,
+                        right: Some(line),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn is_scalar(v: &Value) -> bool {
+    !matches!(v, Value::Object(_) | Value::Array(_))
+}
+
+/// 値をインデント付きのツリー表示の行に変換します。差分表示と揃えるため区切りのカンマは付けません。
+fn tree_lines(prefix: &str, v: &Value, depth: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    push_tree(prefix, v, depth, &mut lines);
+    lines
+}
+
+fn push_tree(prefix: &str, v: &Value, depth: usize, lines: &mut Vec<String>) {
+    let indent = " ".repeat(depth * INDENT);
+    match v {
+        Value::Object(map) if !map.is_empty() => {
+            lines.push(format!("{}{}{{", indent, prefix));
+            for (key, child) in map {
+                let child_prefix = format!("{}: ", Value::String(key.clone()));
+                push_tree(&child_prefix, child, depth + 1, lines);
+            }
+            lines.push(format!("{}}}", indent));
+        }
+        Value::Array(items) if !items.is_empty() => {
+            lines.push(format!("{}{}[", indent, prefix));
+            for child in items {
+                push_tree("", child, depth + 1, lines);
+            }
+            lines.push(format!("{}]", indent));
+        }
+        _ => lines.push(format!("{}{}{}", indent, prefix, v)),
+    }
+}
+
+// ── plain text ────────────────────────────────────────────────────────────────
+
+/// 最長共通部分列による行単位の差分を計算します。
+///
+/// 削除行の直後に追加行が続く箇所は、先頭から順に対にして変更行にまとめます。
+fn diff_lines(left: &str, right: &str) -> Vec<Row> {
+    let a: Vec<&str> = left.lines().collect();
+    let b: Vec<&str> = right.lines().collect();
+
+    let ops = if a.len().saturating_mul(b.len()) > MAX_LCS_CELLS {
+        // 大きすぎる場合は同じ行番号同士を比較する
+        (0..a.len().max(b.len()))
+            .flat_map(|i| match (a.get(i), b.get(i)) {
+                (Some(x), Some(y)) if x == y => vec![Op::Same(i)],
+                (x, y) => {
+                    let mut ops = Vec::new();
+                    if x.is_some() {
+                        ops.push(Op::Removed(i));
+                    }
+                    if y.is_some() {
+                        ops.push(Op::Added(i));
+                    }
+                    ops
+                }
+            })
+            .collect()
+    } else {
+        lcs_ops(&a, &b)
+    };
+
+    let mut rows = Vec::new();
+    let mut removed: Vec<&str> = Vec::new();
+    let mut added: Vec<&str> = Vec::new();
+    for op in ops {
+        match op {
+            Op::Removed(i) => removed.push(a[i]),
+            Op::Added(j) => added.push(b[j]),
+            Op::Same(i) => {
+                flush_changes(&mut removed, &mut added, &mut rows);
+                rows.push(Row::same(a[i].to_string()));
+            }
+        }
+    }
+    flush_changes(&mut removed, &mut added, &mut rows);
+    rows
+}
+
+enum Op {
+    Same(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+fn lcs_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+    // table[i][j] = a[i..] と b[j..] の最長共通部分列の長さ
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i * width + j] = if a[i] == b[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(Op::Same(i));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            ops.push(Op::Removed(i));
+            i += 1;
+        } else {
+            ops.push(Op::Added(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..a.len()).map(Op::Removed));
+    ops.extend((j..b.len()).map(Op::Added));
+    ops
+}
+
+/// 溜まった削除行と追加行を対にして `rows` に追加します。
+fn flush_changes(removed: &mut Vec<&str>, added: &mut Vec<&str>, rows: &mut Vec<Row>) {
+    for i in 0..removed.len().max(added.len()) {
+        let left = removed.get(i).map(|s| s.to_string());
+        let right = added.get(i).map(|s| s.to_string());
+        let change = match (&left, &right) {
+            (Some(_), Some(_)) => Change::Changed,
+            (Some(_), None) => Change::Removed,
+            _ => Change::Added,
+        };
+        rows.push(Row {
+            change,
+            left,
+            right,
+        });
+    }
+    removed.clear();
+    added.clear();
+}
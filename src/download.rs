@@ -0,0 +1,309 @@
+//! 条件に一致する全ページのバックグラウンドダウンロード。
+//!
+//! ロード済みのページに限らず、`next_token` を最後まで辿って取得したイベントを
+//! 到着した順にファイルへ書き出します。書き出し形式は [`export`] の [`Encoder`] で指定します。
+//! 進捗は描画ごとに [`DownloadJob::progress`] で参照し、[`DownloadJob::cancel`] で
+//! 次のページの取得前に中断できます。
+//! パスが `.gz`・`.zst` で終わる場合は [`compress`] で圧縮しながら書き出します。
+//!
+//! ページを書き出すたびに [`checkpoint`] を保存し、中断・失敗したダウンロードは
+//! [`DownloadJob::resume`] で続きから再開できます。`nextToken` が拒否された場合は、
+//! 最後に書き出したイベントの時刻から取得し直します。
+//!
+//! [`export`]: crate::export
+//! [`compress`]: crate::compress
+//! [`checkpoint`]: crate::checkpoint
+//! [`Encoder`]: crate::export::Encoder
+
+use anyhow::{Context, Result};
+use aws_sdk_cloudwatchlogs::Client;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::app::LogEvent;
+use crate::aws;
+use crate::checkpoint::{Checkpoint, Written};
+use crate::compress::Writer;
+use crate::export::Encoder;
+
+/// ダウンロードの状態。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobState {
+    /// 取得中
+    Running,
+    /// 全ページを書き出した
+    Complete,
+    /// 利用者が中断した（途中までのイベントは書き出し済み）
+    Cancelled,
+    /// 取得または書き込みに失敗した（途中までのイベントは書き出し済み）
+    Failed(String),
+}
+
+/// ダウンロードの進捗。
+#[derive(Debug, Clone)]
+pub struct Progress {
+    /// 状態
+    pub state: JobState,
+    /// 書き出したイベント数
+    pub events: usize,
+    /// 書き出したバイト数（圧縮前）
+    pub bytes: u64,
+    /// 取得したページ数
+    pub pages: usize,
+    /// 取得範囲の開始時刻（Unix ミリ秒、未指定の場合は最初のイベントの時刻）
+    pub range_start_ms: Option<i64>,
+    /// 取得範囲の終了時刻（Unix ミリ秒、未指定の場合は開始した時刻）
+    pub range_end_ms: i64,
+    /// 最後に書き出したイベントの時刻（Unix ミリ秒）
+    pub last_ms: Option<i64>,
+    /// 開始してからの経過時間（終了後は所要時間）
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// 書き出し済みのイベントが取得範囲のどこまで進んだかを `0.0..=1.0` で返します。
+    ///
+    /// イベントがまだ無い場合は `None` を返します。
+    pub fn fraction(&self) -> Option<f64> {
+        let start = self.range_start_ms?;
+        let last = self.last_ms?;
+        if self.state == JobState::Complete {
+            return Some(1.0);
+        }
+        let span = (self.range_end_ms - start).max(1) as f64;
+        Some(((last - start) as f64 / span).clamp(0.0, 1.0))
+    }
+}
+
+/// バックグラウンドで実行中（または終了済み）のダウンロード。
+///
+/// ドロップすると実行中のダウンロードは次のページの取得前に中断します。
+pub struct DownloadJob {
+    /// 書き出し先のパス
+    pub path: String,
+    progress: Arc<Mutex<Progress>>,
+    started: Instant,
+    cancel: Arc<AtomicBool>,
+}
+
+impl DownloadJob {
+    /// 書き出し先を作成し、`encoder` の取得条件に一致する全ページの取得を開始します。
+    ///
+    /// `append` が `true` の場合は既存のファイルの末尾に、先頭部分を書かずに追記します。
+    ///
+    /// # Errors
+    ///
+    /// - 書き出し先のファイルを作成できない（追記の場合は開けない）場合
+    pub fn spawn(
+        client: Arc<Client>,
+        path: String,
+        encoder: Encoder,
+        append: bool,
+    ) -> Result<Self> {
+        let out = if append {
+            Writer::append_to_end(&path, true)?
+        } else {
+            Writer::create(&path, true)?
+        };
+        let mut checkpoint = Checkpoint::start(&encoder);
+        checkpoint.file_len = out.file_len();
+        Ok(Self::start(client, path, out, encoder, checkpoint))
+    }
+
+    /// 書き出し先 `path` のチェックポイントから、中断したダウンロードを再開します。
+    ///
+    /// 取得条件と書き出し形式はチェックポイントに保存したものを使い、
+    /// ファイルを書き出し済みのページまで切り詰めてから続きのページを追記します。
+    ///
+    /// # Errors
+    ///
+    /// - チェックポイントが無い、または読み込めない場合
+    /// - 書き出し先のファイルを開けない場合
+    pub fn resume(client: Arc<Client>, path: String) -> Result<Self> {
+        let checkpoint = Checkpoint::load(&path)?;
+        let out = Writer::append(&path, checkpoint.file_len)?;
+        let encoder = checkpoint.encoder();
+        Ok(Self::start(client, path, out, encoder, checkpoint))
+    }
+
+    fn start(
+        client: Arc<Client>,
+        path: String,
+        out: Writer,
+        encoder: Encoder,
+        checkpoint: Checkpoint,
+    ) -> Self {
+        let progress = Arc::new(Mutex::new(Progress {
+            state: JobState::Running,
+            events: checkpoint.events,
+            bytes: checkpoint.bytes,
+            pages: checkpoint.pages,
+            range_start_ms: encoder.source.start_ms.or(checkpoint.first_ms),
+            range_end_ms: encoder
+                .source
+                .end_ms
+                .unwrap_or_else(|| jiff::Timestamp::now().as_millisecond()),
+            last_ms: checkpoint.last_ms,
+            elapsed: Duration::ZERO,
+        }));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let started = Instant::now();
+        let task_path = path.clone();
+        let task_progress = Arc::clone(&progress);
+        let task_cancel = Arc::clone(&cancel);
+        tokio::spawn(async move {
+            let job = Run {
+                client: &client,
+                path: &task_path,
+                out,
+                encoder,
+                checkpoint,
+                progress: &task_progress,
+                cancel: &task_cancel,
+            };
+            let result = job.run().await;
+            let mut p = task_progress.lock().unwrap_or_else(|e| e.into_inner());
+            p.elapsed = started.elapsed();
+            p.state = match result {
+                Ok(true) => JobState::Complete,
+                Ok(false) => JobState::Cancelled,
+                Err(e) => JobState::Failed(format!("{:#}", e)),
+            };
+        });
+        Self {
+            path,
+            progress,
+            started,
+            cancel,
+        }
+    }
+
+    /// 現在の進捗を返します。
+    pub fn progress(&self) -> Progress {
+        let mut p = self
+            .progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if p.state == JobState::Running {
+            p.elapsed = self.started.elapsed();
+        }
+        p
+    }
+
+    /// 取得中かどうかを返します。
+    pub fn is_running(&self) -> bool {
+        self.progress().state == JobState::Running
+    }
+
+    /// 次のページの取得前に中断するよう要求します。
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for DownloadJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// 実行中のダウンロードの書き出し先と途中経過。
+struct Run<'a> {
+    client: &'a Client,
+    path: &'a str,
+    out: Writer,
+    encoder: Encoder,
+    checkpoint: Checkpoint,
+    progress: &'a Mutex<Progress>,
+    cancel: &'a AtomicBool,
+}
+
+impl Run<'_> {
+    /// 中断されるか `next_token` が無くなるまでページを取得して書き出します。
+    ///
+    /// 全ページを書き出した場合は `true`、中断した場合は `false` を返します。
+    /// 空のファイルに書き出す場合だけ先頭部分を書き出します。
+    /// 中断・失敗した場合も、書き出し済みの部分が有効なファイルになるよう末尾部分を書き出し、
+    /// 再開できるようチェックポイントを残します。
+    async fn run(mut self) -> Result<bool> {
+        if self.checkpoint.file_len == 0 {
+            let header = self.encoder.header();
+            self.out.write_all(header.as_bytes())?;
+            self.out.flush()?;
+            self.checkpoint.bytes += header.len() as u64;
+            self.checkpoint.file_len = self.out.file_len();
+        }
+        self.checkpoint.save(self.path)?;
+        let result = self.fetch_pages().await;
+        self.out.write_all(self.encoder.footer().as_bytes())?;
+        self.out.finish()?;
+        if let Ok(true) = result {
+            Checkpoint::remove(self.path)?;
+        }
+        result
+    }
+
+    async fn fetch_pages(&mut self) -> Result<bool> {
+        let mut written = Written::default();
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            let token = self.checkpoint.next_token.clone();
+            let (events, next) = match self.fetch(token.clone()).await {
+                // トークンの期限切れなどで拒否された場合は、最後に書き出したイベントの時刻から取得し直す
+                Err(e) if token.is_some() => {
+                    written = self.checkpoint.restart();
+                    self.fetch(None)
+                        .await
+                        .with_context(|| format!("retry after the token was rejected ({:#})", e))?
+                }
+                result => result?,
+            };
+            let cp = &mut self.checkpoint;
+            for e in &events {
+                if written.skip(e) {
+                    continue;
+                }
+                let chunk = self.encoder.event(e);
+                self.out.write_all(chunk.as_bytes())?;
+                cp.bytes += chunk.len() as u64;
+                cp.record(e);
+            }
+            self.out.flush()?;
+            cp.pages += 1;
+            cp.file_len = self.out.file_len();
+            cp.next_token = next;
+            {
+                let mut p = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+                p.events = cp.events;
+                p.bytes = cp.bytes;
+                p.pages = cp.pages;
+                p.range_start_ms = p.range_start_ms.or(cp.first_ms);
+                p.last_ms = cp.last_ms;
+            }
+            if cp.next_token.is_none() {
+                return Ok(true);
+            }
+            cp.save(self.path)?;
+        }
+    }
+
+    /// 取得条件の `token` に続くページを取得します。
+    async fn fetch(&self, token: Option<String>) -> Result<(Vec<LogEvent>, Option<String>)> {
+        let source = &self.encoder.source;
+        aws::fetch_log_events(
+            self.client,
+            &source.group,
+            source.stream.as_deref().into(),
+            self.checkpoint.start_ms(),
+            source.end_ms,
+            source.pattern.clone(),
+            token,
+        )
+        .await
+    }
+}
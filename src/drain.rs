@@ -0,0 +1,172 @@
+//! Drain 方式によるログテンプレートの抽出。
+//!
+//! メッセージをトークン列に分割し、トークン数と先頭のトークンで辿る固定深さの木を使って
+//! 似たメッセージを同じクラスタにまとめます。クラスタ内で値が異なる位置は可変部分 `<*>` として
+//! テンプレートに残ります（He et al., "Drain: An Online Log Parsing Approach with Fixed Depth Tree"）。
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::app::LogEvent;
+use crate::normalize;
+
+/// テンプレート中の可変部分
+pub const WILDCARD: &str = "<*>";
+
+/// 木を辿るのに使う先頭トークンの数
+const PREFIX_DEPTH: usize = 1;
+
+/// 既存のクラスタにまとめる最小の類似度（一致するトークンの割合）
+const SIMILARITY_THRESHOLD: f64 = 0.4;
+
+/// 1 つのノードが持つ子ノードの上限。超えた分は `<*>` ノードにまとめます。
+const MAX_CHILDREN: usize = 100;
+
+/// 抽出したテンプレート。
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// テンプレートのトークン列（可変部分は [`WILDCARD`]）
+    pub tokens: Vec<String>,
+}
+
+impl Template {
+    /// メッセージがこのテンプレートに当てはまるかどうかを返します。
+    pub fn matches(&self, message: &str) -> bool {
+        let tokens = tokenize(message);
+        tokens.len() == self.tokens.len()
+            && self
+                .tokens
+                .iter()
+                .zip(&tokens)
+                .all(|(t, token)| t == WILDCARD || t == token)
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tokens.join(" "))
+    }
+}
+
+/// テンプレートごとのイベントのまとまり。
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    /// 現在のテンプレート
+    pub template: Template,
+    /// 属するイベント（`items` 内インデックス）
+    pub members: Vec<usize>,
+    /// 最初のイベントのタイムスタンプ（Unix ミリ秒）
+    pub first_ms: i64,
+    /// 最後のイベントのタイムスタンプ（Unix ミリ秒）
+    pub last_ms: i64,
+}
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    /// 葉ノードに属するクラスタ（`clusters` 内インデックス）
+    clusters: Vec<usize>,
+}
+
+/// イベントを 1 件ずつ投入してテンプレートを抽出するパーサ。
+#[derive(Default)]
+pub struct Drain {
+    /// トークン数ごとの木
+    roots: HashMap<usize, Node>,
+    /// 抽出したクラスタ
+    clusters: Vec<Cluster>,
+}
+
+impl Drain {
+    /// 全イベントを投入し、件数の多い順に並べたクラスタを返します。
+    pub fn cluster(events: &[LogEvent]) -> Vec<Cluster> {
+        let mut drain = Self::default();
+        for (i, event) in events.iter().enumerate() {
+            drain.add(i, event);
+        }
+        let mut clusters = drain.clusters;
+        clusters.sort_by(|a, b| {
+            b.members
+                .len()
+                .cmp(&a.members.len())
+                .then(a.first_ms.cmp(&b.first_ms))
+        });
+        clusters
+    }
+
+    /// `index` 番目のイベントを投入します。
+    fn add(&mut self, index: usize, event: &LogEvent) {
+        let tokens = tokenize(&event.message);
+        let mut node = self.roots.entry(tokens.len()).or_default();
+        for token in tokens.iter().take(PREFIX_DEPTH) {
+            let key = if has_variable(token) {
+                WILDCARD.to_string()
+            } else if node.children.contains_key(token.as_str())
+                || node.children.len() < MAX_CHILDREN
+            {
+                token.clone()
+            } else {
+                WILDCARD.to_string()
+            };
+            node = node.children.entry(key).or_default();
+        }
+
+        let best = node
+            .clusters
+            .iter()
+            .map(|&c| (c, similarity(&self.clusters[c].template.tokens, &tokens)))
+            .filter(|&(_, sim)| sim >= SIMILARITY_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(c, _)| c);
+
+        match best {
+            Some(c) => {
+                let cluster = &mut self.clusters[c];
+                for (t, token) in cluster.template.tokens.iter_mut().zip(&tokens) {
+                    if t != token {
+                        *t = WILDCARD.to_string();
+                    }
+                }
+                cluster.members.push(index);
+                cluster.first_ms = cluster.first_ms.min(event.timestamp);
+                cluster.last_ms = cluster.last_ms.max(event.timestamp);
+            }
+            None => {
+                node.clusters.push(self.clusters.len());
+                self.clusters.push(Cluster {
+                    template: Template { tokens },
+                    members: vec![index],
+                    first_ms: event.timestamp,
+                    last_ms: event.timestamp,
+                });
+            }
+        }
+    }
+}
+
+/// メッセージを正規化して空白で区切ったトークン列を返します。
+fn tokenize(message: &str) -> Vec<String> {
+    normalize::normalize(message)
+        .split(' ')
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 正規化で置き換えられた可変部分を含むトークンかどうか。
+fn has_variable(token: &str) -> bool {
+    token.contains('<') && token.contains('>')
+}
+
+/// テンプレートとトークン列の類似度（可変部分を除いて一致する位置の割合）を返します。
+fn similarity(template: &[String], tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 1.0;
+    }
+    let same = template
+        .iter()
+        .zip(tokens)
+        .filter(|(t, token)| *t != WILDCARD && t == token)
+        .count();
+    same as f64 / tokens.len() as f64
+}
@@ -0,0 +1,414 @@
+//! ロード済みログイベントのファイルへの書き出し。
+//!
+//! イベント一覧画面の `d` で、ロード済みのイベントを書き出します
+//! （全ページのダウンロードは [`download`] が同じ [`Encoder`] で書き出します）。
+//! 形式は JSONL・CSV・テキスト・JSON 配列・Markdown（表またはコードブロック）から選べ、
+//! 既定ではファイルの拡張子から判定します。
+//!
+//! JSONL の先頭行には書き出し元の条件（ロググループ・ログストリーム・時間範囲・フィルタパターン）を
+//! `{"export": {...}}` として記録し、続く各行に `timestamp`・`message`・`group`・`stream` を書き出します。
+//! 書き出した JSONL ファイルは [`offline::read_jsonl`] でそのまま読み込めます。
+//!
+//! パスが `.gz`・`.zst` で終わる場合は [`compress`] で圧縮しながら書き出し、
+//! 形式はその手前の拡張子（`events.jsonl.gz` なら JSONL）から判定します。
+//!
+//! [`download`]: crate::download
+//! [`compress`]: crate::compress
+//! [`offline::read_jsonl`]: crate::offline::read_jsonl
+
+use anyhow::{Context, Result};
+use std::io::Write;
+
+use crate::app::LogEvent;
+use crate::columns::Column;
+use crate::compress::{Compression, Writer};
+use crate::time;
+
+/// Markdown のコードブロックのフェンス（メッセージ中の ``` で閉じないよう 4 つにする）
+const CODE_FENCE: &str = "````";
+
+/// 書き出すイベントの取得条件。
+#[derive(Debug, Clone)]
+pub struct ExportSource {
+    /// ロググループ名（ローカルファイルの場合はファイルパス）
+    pub group: String,
+    /// ログストリーム名（グループ横断検索の場合は `None`、各イベントのストリームを記録）
+    pub stream: Option<String>,
+    /// 取得開始時刻（Unix ミリ秒）
+    pub start_ms: Option<i64>,
+    /// 取得終了時刻（Unix ミリ秒）
+    pub end_ms: Option<i64>,
+    /// CloudWatch Logs フィルタパターン
+    pub pattern: Option<String>,
+}
+
+impl ExportSource {
+    /// `group › stream` 形式の表示用の出所を返します。
+    fn label(&self) -> String {
+        match &self.stream {
+            Some(stream) => format!("{} › {}", self.group, stream),
+            None => self.group.clone(),
+        }
+    }
+
+    /// イベントのログストリーム名（不明な場合は取得条件のストリーム名）を返します。
+    fn stream_of<'a>(&'a self, event: &'a LogEvent) -> Option<&'a str> {
+        event.stream.as_deref().or(self.stream.as_deref())
+    }
+}
+
+/// 書き出し形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// 1 行 1 イベントの JSON（先頭行に取得条件）
+    Jsonl,
+    /// 時刻・ストリーム・JSON フィールド列・メッセージの CSV
+    Csv,
+    /// `aws logs tail` と同様の `時刻 ストリーム メッセージ` のテキスト
+    Text,
+    /// 整形済みの JSON 配列
+    JsonArray,
+    /// Markdown の表
+    MarkdownTable,
+    /// Markdown のコードブロック
+    MarkdownCode,
+}
+
+impl Format {
+    /// 選択肢として巡回する順の全形式
+    pub const ALL: [Self; 6] = [
+        Self::Jsonl,
+        Self::Csv,
+        Self::Text,
+        Self::JsonArray,
+        Self::MarkdownTable,
+        Self::MarkdownCode,
+    ];
+
+    /// 拡張子から形式を判定します。該当しない場合は JSONL とします。
+    ///
+    /// `.gz`・`.zst` はその手前の拡張子で判定します。
+    pub fn from_path(path: &str) -> Self {
+        let lower = Compression::split(path).0.to_ascii_lowercase();
+        match lower.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("csv") => Self::Csv,
+            Some("txt" | "log") => Self::Text,
+            Some("json") => Self::JsonArray,
+            Some("md" | "markdown") => Self::MarkdownTable,
+            _ => Self::Jsonl,
+        }
+    }
+
+    /// この形式の既定の拡張子を返します。
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+            Self::Text => "txt",
+            Self::JsonArray => "json",
+            Self::MarkdownTable | Self::MarkdownCode => "md",
+        }
+    }
+
+    /// 画面表示用の名前を返します。
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Jsonl => "JSONL",
+            Self::Csv => "CSV",
+            Self::Text => "text",
+            Self::JsonArray => "JSON array",
+            Self::MarkdownTable => "Markdown table",
+            Self::MarkdownCode => "Markdown code block",
+        }
+    }
+
+    /// 選択肢で次（`forward` が `false` の場合は前）の形式を返します。
+    pub fn cycle(self, forward: bool) -> Self {
+        let i = Self::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        let n = Self::ALL.len();
+        Self::ALL[if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        }]
+    }
+
+    /// `path` の拡張子がこの形式のものかどうかを返します。
+    pub fn matches_path(self, path: &str) -> bool {
+        Self::from_path(path).extension() == self.extension()
+    }
+
+    /// 既存のファイルの末尾に追記できる（行単位の）形式かどうかを返します。
+    pub fn can_append(self) -> bool {
+        matches!(self, Self::Jsonl | Self::Csv | Self::Text)
+    }
+
+    /// `path` の拡張子をこの形式のものに置き換えたパスを返します。
+    ///
+    /// 既知の拡張子でない場合は拡張子を付け足します。`.gz`・`.zst` は末尾に残します。
+    pub fn with_extension(self, path: &str) -> String {
+        let base = Compression::split(path).0;
+        let suffix = &path[base.len()..];
+        let stem = match base.rsplit_once('.') {
+            Some((stem, ext))
+                if !stem.is_empty()
+                    && matches!(
+                        ext.to_ascii_lowercase().as_str(),
+                        "jsonl" | "csv" | "txt" | "log" | "json" | "md" | "markdown"
+                    ) =>
+            {
+                stem
+            }
+            _ => base,
+        };
+        format!("{}.{}{}", stem, self.extension(), suffix)
+    }
+}
+
+/// イベントを指定形式の文字列に変換するエンコーダ。
+///
+/// [`Encoder::header`]、イベントごとの [`Encoder::event`]、[`Encoder::footer`] の順に
+/// 連結するとファイル全体になります。全ページのダウンロードでは到着したイベントから順に書き出します。
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    /// 書き出すイベントの取得条件
+    pub source: ExportSource,
+    /// 書き出し形式
+    pub format: Format,
+    /// CSV・Markdown の表に含める JSON フィールド列
+    columns: Vec<Column>,
+    /// これまでに変換したイベント数
+    written: usize,
+}
+
+impl Encoder {
+    /// 新しい [`Encoder`] を生成します。
+    pub fn new(source: ExportSource, format: Format, columns: Vec<Column>) -> Self {
+        Self {
+            source,
+            format,
+            columns,
+            written: 0,
+        }
+    }
+
+    /// 既に `written` 件のイベントを書き出したファイルの続きを変換する [`Encoder`] を返します。
+    ///
+    /// 中断した全ページのダウンロードを再開する場合に使います（JSON 配列の区切りが続きから正しくなります）。
+    pub fn resumed(mut self, written: usize) -> Self {
+        self.written = written;
+        self
+    }
+
+    /// CSV・Markdown の表に含める JSON フィールド列を返します。
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// ファイルの先頭部分を返します。
+    pub fn header(&self) -> String {
+        match self.format {
+            Format::Jsonl => {
+                let header = serde_json::json!({
+                    "export": {
+                        "group": self.source.group,
+                        "stream": self.source.stream,
+                        "start": self.source.start_ms.map(time::format_ms),
+                        "end": self.source.end_ms.map(time::format_ms),
+                        "start_ms": self.source.start_ms,
+                        "end_ms": self.source.end_ms,
+                        "pattern": self.source.pattern,
+                        "time_zone": time::zone_label(),
+                        "exported_at": time::format_zoned(&time::now()),
+                    }
+                });
+                header.to_string() + "\n"
+            }
+            Format::Csv => csv_row(&self.column_names()),
+            Format::Text => String::new(),
+            Format::JsonArray => "[".to_string(),
+            Format::MarkdownTable => {
+                let names: Vec<String> = self
+                    .column_names()
+                    .iter()
+                    .map(|n| markdown_cell(n))
+                    .collect();
+                format!(
+                    "{}| {} |\n|{}\n",
+                    self.markdown_heading(),
+                    names.join(" | "),
+                    "---|".repeat(names.len())
+                )
+            }
+            Format::MarkdownCode => format!("{}{}\n", self.markdown_heading(), CODE_FENCE),
+        }
+    }
+
+    /// イベント 1 件分の文字列を返します。
+    pub fn event(&mut self, event: &LogEvent) -> String {
+        let first = self.written == 0;
+        self.written += 1;
+        match self.format {
+            Format::Jsonl => {
+                let line = serde_json::json!({
+                    "timestamp": event.timestamp,
+                    "message": event.message,
+                    "group": self.source.group,
+                    "stream": self.source.stream_of(event),
+                });
+                line.to_string() + "\n"
+            }
+            Format::Csv => csv_row(&self.row(event)),
+            Format::Text | Format::MarkdownCode => {
+                let mut line = time::format_ms(event.timestamp);
+                if let Some(stream) = self.source.stream_of(event) {
+                    line.push(' ');
+                    line.push_str(stream);
+                }
+                line.push(' ');
+                line.push_str(event.message.trim_end());
+                line + "\n"
+            }
+            Format::JsonArray => {
+                let value = serde_json::json!({
+                    "timestamp": event.timestamp,
+                    "time": time::format_ms(event.timestamp),
+                    "group": self.source.group,
+                    "stream": self.source.stream_of(event),
+                    "message": event.message,
+                });
+                let pretty = serde_json::to_string_pretty(&value).unwrap_or_default();
+                format!(
+                    "{}\n  {}",
+                    if first { "" } else { "," },
+                    pretty.replace('\n', "\n  ")
+                )
+            }
+            Format::MarkdownTable => {
+                let cells: Vec<String> = self.row(event).iter().map(|c| markdown_cell(c)).collect();
+                format!("| {} |\n", cells.join(" | "))
+            }
+        }
+    }
+
+    /// ファイルの末尾部分を返します。
+    pub fn footer(&self) -> String {
+        match self.format {
+            Format::JsonArray if self.written == 0 => "]\n".to_string(),
+            Format::JsonArray => "\n]\n".to_string(),
+            Format::MarkdownCode => format!("{}\n", CODE_FENCE),
+            _ => String::new(),
+        }
+    }
+
+    /// CSV・Markdown の表の列名（時刻・ストリーム・JSON フィールド列・メッセージ）を返します。
+    fn column_names(&self) -> Vec<String> {
+        let mut names = vec!["time".to_string(), "stream".to_string()];
+        names.extend(self.columns.iter().map(Column::label));
+        names.push("message".to_string());
+        names
+    }
+
+    /// CSV・Markdown の表の 1 行分の値を返します。JSON でないメッセージのフィールド列は空欄になります。
+    fn row(&self, event: &LogEvent) -> Vec<String> {
+        let mut row = vec![
+            time::format_ms(event.timestamp),
+            self.source.stream_of(event).unwrap_or("").to_string(),
+        ];
+        if !self.columns.is_empty() {
+            let json = serde_json::from_str::<serde_json::Value>(event.message.trim()).ok();
+            row.extend(
+                self.columns
+                    .iter()
+                    .map(|c| json.as_ref().map(|v| c.cell(v)).unwrap_or_default()),
+            );
+        }
+        row.push(event.message.trim_end().to_string());
+        row
+    }
+
+    /// Markdown の見出しと取得条件の箇条書きを返します。
+    fn markdown_heading(&self) -> String {
+        let range = |ms: Option<i64>| ms.map(time::format_ms).unwrap_or_else(|| "*".to_string());
+        let mut out = format!(
+            "# {}\n\n- Time range: {} → {} ({})\n",
+            self.source.label(),
+            range(self.source.start_ms),
+            range(self.source.end_ms),
+            time::zone_label()
+        );
+        if let Some(pattern) = &self.source.pattern {
+            out.push_str(&format!("- Filter pattern: `{}`\n", pattern));
+        }
+        out.push_str(&format!(
+            "- Exported at: {}\n\n",
+            time::format_zoned(&time::now())
+        ));
+        out
+    }
+}
+
+/// CSV の 1 行を返します。区切り文字・引用符・改行を含む値は引用符で囲みます。
+fn csv_row(fields: &[String]) -> String {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.clone()
+            }
+        })
+        .collect();
+    row.join(",") + "\r\n"
+}
+
+/// Markdown の表のセルとして安全な文字列を返します（`|` をエスケープし、改行を `<br>` にします）。
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// `group` の末尾の要素と今日の日付から、既定の書き出しファイル名を返します。
+pub fn default_path(group: &str) -> String {
+    let group_short = group
+        .rsplit('/')
+        .find(|s| !s.is_empty())
+        .unwrap_or("unknown");
+    format!("{}-{}.jsonl", group_short, time::now().date())
+}
+
+/// イベントを `encoder` の形式で書き出し、書き出したイベント数を返します。
+///
+/// `append` が `true` の場合は既存のファイルの末尾に、先頭部分（JSONL の取得条件・CSV の列名）を
+/// 書かずにイベントを追記します（[`Format::can_append`] の形式のみ）。
+///
+/// # Errors
+///
+/// - ファイルの作成・書き込みに失敗した場合
+pub fn write_events(
+    path: &str,
+    mut encoder: Encoder,
+    events: &[LogEvent],
+    append: bool,
+) -> Result<usize> {
+    let mut out = if append {
+        Writer::append_to_end(path, false)?
+    } else {
+        Writer::create(path, false)?
+    };
+    let mut write = || -> std::io::Result<()> {
+        if !append {
+            out.write_all(encoder.header().as_bytes())?;
+        }
+        for e in events {
+            out.write_all(encoder.event(e).as_bytes())?;
+        }
+        out.write_all(encoder.footer().as_bytes())
+    };
+    write().with_context(|| format!("Failed to write {}", path))?;
+    out.finish()
+        .with_context(|| format!("Failed to write {}", path))?;
+    Ok(events.len())
+}
@@ -0,0 +1,328 @@
+//! フィルタパターンの字句解析。
+//!
+//! 入力文字列をバイトオフセット付きの [`Token`] 列に分割します。
+//! 構文ハイライトでも同じトークン列を使用するため、空白以外の全ての文字が
+//! いずれかのトークンに含まれます。
+
+use super::PatternError;
+
+/// トークンの種類。
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// 引用符なしの語（検索語・フィールド名・値）
+    Word(String),
+    /// 二重引用符で囲まれた文字列（引用符を除いた中身）
+    Quoted(String),
+    /// `%...%` で囲まれた正規表現（`%` を除いた中身）
+    Regex(String),
+    /// 数値
+    Number(f64),
+    /// JSON セレクタ（`$.a.b[0]` など）
+    Selector(String),
+    /// `IS`・`NOT`・`EXISTS`、および `IS`・`IS NOT` の直後の `TRUE`・`FALSE`・`NULL`（大文字化済み）
+    Keyword(String),
+    /// 比較演算子（`=`・`!=`・`<`・`<=`・`>`・`>=`）
+    Op(CmpOp),
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// 検索語の前置 `-`（除外）
+    Minus,
+    /// 検索語の前置 `?`（いずれか一致）
+    Question,
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// `[`
+    LBracket,
+    /// `]`
+    RBracket,
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// `,`
+    Comma,
+    /// `...`
+    Ellipsis,
+}
+
+/// 比較演算子。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CmpOp {
+    /// `=`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+/// 入力中の位置（バイトオフセット）付きトークン。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// トークンの種類
+    pub kind: TokenKind,
+    /// 開始位置（バイトオフセット）
+    pub start: usize,
+    /// 終了位置（バイトオフセット、排他的）
+    pub end: usize,
+}
+
+/// 構造化パターン内で常にキーワードとして扱う語
+const KEYWORDS: &[&str] = &["IS", "NOT", "EXISTS"];
+
+/// `IS`・`IS NOT` の直後でだけキーワードとして扱う語（比較の値としては元の綴りのまま扱う）
+const IS_OPERANDS: &[&str] = &["TRUE", "FALSE", "NULL"];
+
+/// フィルタパターン文字列をトークン列に分割します。
+///
+/// `{ }` または `[ ]` の内側では構造化パターン用の演算子を認識し、
+/// 外側では空白区切りの検索語として扱います。
+///
+/// # Errors
+///
+/// - 引用符・正規表現が閉じられていない場合
+/// - 構造化パターン内に解釈できない文字がある場合
+pub fn tokenize(input: &str) -> Result<Vec<Token>, PatternError> {
+    match tokenize_partial(input) {
+        (tokens, None) => Ok(tokens),
+        (_, Some(e)) => Err(e),
+    }
+}
+
+/// [`tokenize`] と同様ですが、エラー発生時もそこまでに読み取れたトークン列を返します。
+///
+/// 入力途中の文字列を構文ハイライトする際に使用します。
+pub fn tokenize_partial(input: &str) -> (Vec<Token>, Option<PatternError>) {
+    let mut lexer = Lexer {
+        input,
+        pos: 0,
+        depth: 0,
+        tokens: Vec::new(),
+    };
+    let error = lexer.run().err();
+    (lexer.tokens, error)
+}
+
+struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+    /// `{`/`[` のネスト深さ（0 は検索語モード）
+    depth: usize,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    fn run(&mut self) -> Result<(), PatternError> {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+                continue;
+            }
+            let start = self.pos;
+            match c {
+                '{' | '[' => {
+                    self.pos += 1;
+                    self.depth += 1;
+                    let kind = if c == '{' {
+                        TokenKind::LBrace
+                    } else {
+                        TokenKind::LBracket
+                    };
+                    self.push(kind, start);
+                }
+                '}' | ']' => {
+                    self.pos += 1;
+                    self.depth = self.depth.saturating_sub(1);
+                    let kind = if c == '}' {
+                        TokenKind::RBrace
+                    } else {
+                        TokenKind::RBracket
+                    };
+                    self.push(kind, start);
+                }
+                '"' => {
+                    let s = self.delimited('"', "閉じられていない引用符があります")?;
+                    self.push(TokenKind::Quoted(s), start);
+                }
+                '%' => {
+                    let s = self.delimited('%', "閉じられていない正規表現 (%...%) があります")?;
+                    self.push(TokenKind::Regex(s), start);
+                }
+                _ if self.depth == 0 => self.term_token(c, start),
+                _ => self.structured_token(c, start)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        self.tokens.push(Token {
+            kind,
+            start,
+            end: self.pos,
+        });
+    }
+
+    /// `quote` で囲まれた文字列を読み取ります。`\` によるエスケープに対応します。
+    fn delimited(&mut self, quote: char, message: &str) -> Result<String, PatternError> {
+        let start = self.pos;
+        self.pos += quote.len_utf8();
+        let mut out = String::new();
+        let mut escaped = false;
+        while let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+            if escaped {
+                // 正規表現ではエスケープをそのまま残す
+                if quote == '%' && c != '%' {
+                    out.push('\\');
+                }
+                out.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                return Ok(out);
+            } else {
+                out.push(c);
+            }
+        }
+        Err(PatternError::new(self.input, start, message))
+    }
+
+    /// 検索語モードのトークン（`-`/`?` 前置と語）を読み取ります。
+    fn term_token(&mut self, c: char, start: usize) {
+        // 前置記号は語の先頭にのみ付く（`--x` の 2 文字目以降は語の一部）
+        let after_prefix = self.tokens.last().is_some_and(|t| {
+            t.end == start && matches!(t.kind, TokenKind::Minus | TokenKind::Question)
+        });
+        let followed_by_term = self.rest()[c.len_utf8()..]
+            .chars()
+            .next()
+            .is_some_and(|next| !next.is_whitespace());
+        if (c == '-' || c == '?') && !after_prefix && followed_by_term {
+            self.pos += 1;
+            let kind = if c == '-' {
+                TokenKind::Minus
+            } else {
+                TokenKind::Question
+            };
+            self.push(kind, start);
+            return;
+        }
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, '"' | '{' | '[') {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        let word = self.input[start..self.pos].to_string();
+        self.push(TokenKind::Word(word), start);
+    }
+
+    /// 直前のトークンが `IS` または `IS NOT` かどうかを返します。
+    fn after_is(&self) -> bool {
+        let keyword = |t: Option<&Token>, word: &str| {
+            t.is_some_and(|t| matches!(&t.kind, TokenKind::Keyword(k) if k == word))
+        };
+        let mut rev = self.tokens.iter().rev();
+        match rev.next() {
+            last if keyword(last, "IS") => true,
+            last if keyword(last, "NOT") => keyword(rev.next(), "IS"),
+            _ => false,
+        }
+    }
+
+    /// 構造化パターン（`{ }`/`[ ]` 内）のトークンを読み取ります。
+    fn structured_token(&mut self, c: char, start: usize) -> Result<(), PatternError> {
+        let rest = self.rest();
+        let two: String = rest.chars().take(2).collect();
+        let (kind, len) = match two.as_str() {
+            "&&" => (TokenKind::And, 2),
+            "||" => (TokenKind::Or, 2),
+            "!=" => (TokenKind::Op(CmpOp::Ne), 2),
+            "<=" => (TokenKind::Op(CmpOp::Le), 2),
+            ">=" => (TokenKind::Op(CmpOp::Ge), 2),
+            _ => match c {
+                '=' => (TokenKind::Op(CmpOp::Eq), 1),
+                '<' => (TokenKind::Op(CmpOp::Lt), 1),
+                '>' => (TokenKind::Op(CmpOp::Gt), 1),
+                '(' => (TokenKind::LParen, 1),
+                ')' => (TokenKind::RParen, 1),
+                ',' => (TokenKind::Comma, 1),
+                _ if rest.starts_with("...") => (TokenKind::Ellipsis, 3),
+                '$' => {
+                    let len = rest
+                        .char_indices()
+                        .find(|&(_, ch)| !is_selector_char(ch))
+                        .map_or(rest.len(), |(i, _)| i);
+                    (TokenKind::Selector(rest[..len].to_string()), len)
+                }
+                _ if is_word_char(c) => {
+                    let len = rest
+                        .char_indices()
+                        .find(|&(_, ch)| !is_word_char(ch))
+                        .map_or(rest.len(), |(i, _)| i);
+                    let word = &rest[..len];
+                    let upper = word.to_ascii_uppercase();
+                    let is_keyword = KEYWORDS.contains(&upper.as_str())
+                        || (IS_OPERANDS.contains(&upper.as_str()) && self.after_is());
+                    let kind = if is_keyword {
+                        TokenKind::Keyword(upper)
+                    } else if let Some(n) = parse_number(word) {
+                        TokenKind::Number(n)
+                    } else {
+                        TokenKind::Word(word.to_string())
+                    };
+                    (kind, len)
+                }
+                _ => {
+                    return Err(PatternError::new(
+                        self.input,
+                        start,
+                        format!("不正な文字 `{}` があります", c),
+                    ));
+                }
+            },
+        };
+        self.pos += len;
+        self.push(kind, start);
+        Ok(())
+    }
+}
+
+fn is_selector_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '$' | '.' | '_' | '-' | '[' | ']' | '*' | '@' | ':')
+}
+
+/// 数字・`-`・`.` で始まる語を有限の数値として読み取ります。
+///
+/// `f64` の解釈では `nan`・`inf`・`infinity`（`+` 付きを含む）も数値になるため、それらは語として扱います。
+fn parse_number(word: &str) -> Option<f64> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    word.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '*' | ':' | '/' | '@' | '+')
+}
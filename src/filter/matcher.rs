@@ -0,0 +1,227 @@
+//! フィルタパターン AST によるログメッセージの照合。
+
+use serde_json::Value as Json;
+
+use super::lexer::CmpOp;
+use super::parser::{
+    Ast, Delimited, DelimitedField, FieldExpr, IsValue, JsonExpr, PathSegment, Term, TermKind,
+    TextMatcher, Value,
+};
+
+/// メッセージが AST に一致するかどうかを判定します。
+pub fn matches(ast: &Ast, message: &str) -> bool {
+    match ast {
+        Ast::Terms(terms) => match_terms(terms, message),
+        Ast::Json(expr) => match serde_json::from_str::<Json>(message.trim()) {
+            Ok(json) => eval_json(expr, &json),
+            Err(_) => false,
+        },
+        Ast::Delimited(d) => match_delimited(d, message),
+    }
+}
+
+// ── terms ────────────────────────────────────────────────────────────────────
+
+fn match_terms(terms: &[Term], message: &str) -> bool {
+    let mut has_optional = false;
+    let mut any_optional = false;
+    for term in terms {
+        let hit = match &term.matcher {
+            TextMatcher::Contains(s) => message.contains(s.as_str()),
+            TextMatcher::Regex(re) => re.is_match(message),
+        };
+        match term.kind {
+            TermKind::Required if !hit => return false,
+            TermKind::Excluded if hit => return false,
+            TermKind::Optional => {
+                has_optional = true;
+                any_optional |= hit;
+            }
+            _ => {}
+        }
+    }
+    !has_optional || any_optional
+}
+
+// ── JSON ─────────────────────────────────────────────────────────────────────
+
+fn eval_json(expr: &JsonExpr, json: &Json) -> bool {
+    match expr {
+        JsonExpr::And(a, b) => eval_json(a, json) && eval_json(b, json),
+        JsonExpr::Or(a, b) => eval_json(a, json) || eval_json(b, json),
+        JsonExpr::Compare { path, op, value } => select(json, path)
+            .iter()
+            .any(|v| compare_json(v, *op, value)),
+        JsonExpr::Is {
+            path,
+            expected,
+            negated,
+        } => select(json, path).iter().any(|v| {
+            let hit = match expected {
+                IsValue::True => v.as_bool() == Some(true),
+                IsValue::False => v.as_bool() == Some(false),
+                IsValue::Null => v.is_null(),
+            };
+            hit != *negated
+        }),
+        JsonExpr::NotExists { path } => select(json, path).is_empty(),
+    }
+}
+
+/// パスに該当する値を全て返します（`[*]` は配列の全要素に展開されます）。
+fn select<'a>(json: &'a Json, path: &[PathSegment]) -> Vec<&'a Json> {
+    let mut current = vec![json];
+    for seg in path {
+        current = current
+            .into_iter()
+            .flat_map(|v| -> Vec<&Json> {
+                match seg {
+                    PathSegment::Key(k) => v.get(k.as_str()).into_iter().collect(),
+                    PathSegment::Index(i) => v.get(*i).into_iter().collect(),
+                    PathSegment::AnyIndex => match v.as_array() {
+                        Some(items) => items.iter().collect(),
+                        None => Vec::new(),
+                    },
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+fn compare_json(actual: &Json, op: CmpOp, expected: &Value) -> bool {
+    match actual {
+        Json::Number(n) => match n.as_f64() {
+            Some(f) => compare_scalar(Some(f), &n.to_string(), op, expected),
+            None => false,
+        },
+        Json::String(s) => compare_scalar(s.parse().ok(), s, op, expected),
+        Json::Bool(b) => compare_scalar(None, if *b { "true" } else { "false" }, op, expected),
+        Json::Null | Json::Array(_) | Json::Object(_) => false,
+    }
+}
+
+// ── space-delimited ──────────────────────────────────────────────────────────
+
+/// フィールド名と、そのフィールドに割り当てられた語の組
+type Binding<'a> = (&'a str, &'a str);
+
+fn match_delimited(d: &Delimited, message: &str) -> bool {
+    let words = split_fields(message);
+    let mut bindings = Vec::new();
+    bind_fields(&d.fields, &words, &mut bindings, &|bound| {
+        d.conditions.iter().all(|c| eval_field(c, bound))
+    })
+}
+
+/// フィールド定義を語の列に割り当て、`accept` を満たす割り当てが存在するかを返します。
+///
+/// `...` は 0 個以上の語に一致するため、全ての割り当てをバックトラックで試します。
+fn bind_fields<'a>(
+    fields: &'a [DelimitedField],
+    words: &[&'a str],
+    bound: &mut Vec<Binding<'a>>,
+    accept: &dyn Fn(&[Binding<'a>]) -> bool,
+) -> bool {
+    let Some((field, rest)) = fields.split_first() else {
+        return words.is_empty() && accept(bound);
+    };
+    match field {
+        DelimitedField::Named(name) => {
+            let Some((word, remaining)) = words.split_first() else {
+                return false;
+            };
+            bound.push((name.as_str(), word));
+            let ok = bind_fields(rest, remaining, bound, accept);
+            bound.pop();
+            ok
+        }
+        DelimitedField::Ellipsis => {
+            (0..=words.len()).any(|skip| bind_fields(rest, &words[skip..], bound, accept))
+        }
+    }
+}
+
+fn eval_field(expr: &FieldExpr, bound: &[Binding]) -> bool {
+    match expr {
+        FieldExpr::And(a, b) => eval_field(a, bound) && eval_field(b, bound),
+        FieldExpr::Or(a, b) => eval_field(a, bound) || eval_field(b, bound),
+        FieldExpr::Compare { field, op, value } => bound
+            .iter()
+            .filter(|(name, _)| name == field)
+            .any(|(_, word)| compare_scalar(word.parse().ok(), word, *op, value)),
+    }
+}
+
+/// メッセージを空白区切りのフィールドに分割します。
+///
+/// `"..."` と `[...]` で囲まれた部分は空白を含んでいても 1 フィールドとして扱い、
+/// 囲み文字は取り除きます（Apache のアクセスログ形式など）。
+fn split_fields(message: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = message.trim();
+    while !rest.is_empty() {
+        let close = match rest.as_bytes()[0] {
+            b'"' => Some('"'),
+            b'[' => Some(']'),
+            _ => None,
+        };
+        let (field, next) = match close.and_then(|c| rest[1..].find(c).map(|i| (c, i))) {
+            Some((_, i)) => (&rest[1..1 + i], &rest[i + 2..]),
+            None => match rest.find(char::is_whitespace) {
+                Some(i) => (&rest[..i], &rest[i..]),
+                None => (rest, ""),
+            },
+        };
+        fields.push(field);
+        rest = next.trim_start();
+    }
+    fields
+}
+
+// ── scalar comparison ────────────────────────────────────────────────────────
+
+/// 数値として解釈できる場合は数値比較、それ以外は文字列比較を行います。
+fn compare_scalar(number: Option<f64>, text: &str, op: CmpOp, expected: &Value) -> bool {
+    match expected {
+        Value::Number(want) => match number {
+            Some(n) => match op {
+                CmpOp::Eq => n == *want,
+                CmpOp::Ne => n != *want,
+                CmpOp::Lt => n < *want,
+                CmpOp::Le => n <= *want,
+                CmpOp::Gt => n > *want,
+                CmpOp::Ge => n >= *want,
+            },
+            None => op == CmpOp::Ne,
+        },
+        Value::Text(pattern) => {
+            let hit = wildcard_match(pattern, text);
+            if op == CmpOp::Ne { !hit } else { hit }
+        }
+        Value::Regex(re) => {
+            let hit = re.is_match(text);
+            if op == CmpOp::Ne { !hit } else { hit }
+        }
+    }
+}
+
+/// `*` を 0 文字以上の任意の文字列として扱う完全一致判定を行います。
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut middle = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match middle.find(part) {
+            Some(i) => middle = &middle[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
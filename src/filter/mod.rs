@@ -0,0 +1,330 @@
+//! CloudWatch Logs フィルタパターンのローカル実装。
+//!
+//! サーバに送信する前の構文チェックと、ロード済みイベントやオフラインの
+//! JSONL ファイルに対するローカルでの絞り込みに使用します。
+//!
+//! 対応する構文:
+//!
+//! - 検索語: `ERROR timeout`（全て含む）、`-healthcheck`（除外）、`?ERROR ?WARN`（いずれか）、
+//!   `"quoted term"`、`%regex%`
+//! - JSON セレクタ: `{ $.level = "error" && $.latency > 500 }`、`{ $.ok = true }`、
+//!   `{ $.user IS NULL }`、`{ $.ok IS NOT TRUE }`、`{ $.id NOT EXISTS }`、`{ $.tags[*] = "prod" }`
+//! - 空白区切り: `[ip, user, ..., status = 5* || status = 429, bytes > 1000]`
+
+pub mod lexer;
+pub mod matcher;
+pub mod parser;
+
+use std::fmt;
+
+use parser::Ast;
+
+/// 位置付きのフィルタパターン構文エラー。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternError {
+    /// エラー位置（0 始まりの文字数オフセット）
+    pub column: usize,
+    /// エラー内容
+    pub message: String,
+}
+
+impl PatternError {
+    /// 入力文字列とバイトオフセットから [`PatternError`] を生成します。
+    pub fn new(input: &str, byte_pos: usize, message: impl Into<String>) -> Self {
+        let byte_pos = byte_pos.min(input.len());
+        Self {
+            column: input[..byte_pos].chars().count(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}文字目: {}", self.column + 1, self.message)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// 入力途中のパターンを検証し、トークン列と最初の構文エラーを返します。
+///
+/// 字句解析に失敗した場合も、エラー位置までのトークン列を返します。
+pub fn analyze(input: &str) -> (Vec<lexer::Token>, Option<PatternError>) {
+    let (tokens, error) = lexer::tokenize_partial(input);
+    if error.is_some() || tokens.is_empty() {
+        return (tokens, error);
+    }
+    let error = parser::parse(input, &tokens).err();
+    (tokens, error)
+}
+
+/// 解析済みのフィルタパターン。
+#[derive(Debug, Clone)]
+pub struct FilterPattern {
+    ast: Option<Ast>,
+}
+
+impl FilterPattern {
+    /// フィルタパターン文字列を解析します。空文字列は全件一致のパターンになります。
+    ///
+    /// # Errors
+    ///
+    /// - 構文が不正な場合（エラー位置を含む [`PatternError`] を返します）
+    pub fn parse(input: &str) -> Result<Self, PatternError> {
+        let tokens = lexer::tokenize(input)?;
+        if tokens.is_empty() {
+            return Ok(Self { ast: None });
+        }
+        let ast = parser::parse(input, &tokens)?;
+        Ok(Self { ast: Some(ast) })
+    }
+
+    /// メッセージがパターンに一致するかどうかを返します。
+    pub fn matches(&self, message: &str) -> bool {
+        match &self.ast {
+            Some(ast) => matcher::matches(ast, message),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, message: &str) -> bool {
+        FilterPattern::parse(pattern)
+            .unwrap_or_else(|e| panic!("{}: {}", pattern, e))
+            .matches(message)
+    }
+
+    fn error(pattern: &str) -> PatternError {
+        FilterPattern::parse(pattern).expect_err(pattern)
+    }
+
+    #[test]
+    fn empty_pattern_matches_everything() {
+        assert!(matches("", "anything"));
+        assert!(matches("   ", ""));
+        assert!(matches(r#""""#, "anything"));
+    }
+
+    #[test]
+    fn terms_are_all_required_and_case_sensitive() {
+        assert!(matches("ERROR timeout", "ERROR: request timeout"));
+        assert!(!matches("ERROR timeout", "ERROR: request failed"));
+        assert!(!matches("error", "ERROR"));
+        assert!(matches(r#""request failed""#, "ERROR request failed"));
+        assert!(!matches(r#""request failed""#, "failed request"));
+    }
+
+    #[test]
+    fn excluded_terms() {
+        assert!(matches("ERROR -healthcheck", "ERROR in /api"));
+        assert!(!matches("ERROR -healthcheck", "ERROR in healthcheck"));
+        assert!(!matches("-DEBUG", "DEBUG noise"));
+        // 語の途中の `-` は除外記号ではない
+        assert!(matches("user-agent", "header user-agent: curl"));
+    }
+
+    #[test]
+    fn optional_terms_match_any() {
+        assert!(matches("?ERROR ?WARN", "WARN disk almost full"));
+        assert!(matches("?ERROR ?WARN", "ERROR disk full"));
+        assert!(!matches("?ERROR ?WARN", "INFO all good"));
+        assert!(matches("?ERROR ?WARN -retry", "ERROR gave up"));
+        assert!(!matches("?ERROR ?WARN -retry", "WARN will retry"));
+    }
+
+    #[test]
+    fn regex_terms() {
+        assert!(matches("%status=5[0-9]{2}%", "GET / status=503"));
+        assert!(!matches("%status=5[0-9]{2}%", "GET / status=404"));
+    }
+
+    #[test]
+    fn json_equality_and_wildcards() {
+        let msg = r#"{"level":"error","user":{"id":"u-42"},"latency":730}"#;
+        assert!(matches(r#"{ $.level = "error" }"#, msg));
+        assert!(matches("{ $.level = error }", msg));
+        assert!(!matches(r#"{ $.level = "warn" }"#, msg));
+        assert!(matches(r#"{ $.user.id = "u-*" }"#, msg));
+        assert!(matches(r#"{ $.level != "warn" }"#, msg));
+        assert!(!matches(r#"{ $.level != "error" }"#, msg));
+        assert!(matches("{ $.latency = 730 }", msg));
+        // JSON でないメッセージには一致しない
+        assert!(!matches(r#"{ $.level = "error" }"#, "level=error"));
+    }
+
+    #[test]
+    fn json_numeric_comparisons() {
+        let msg = r#"{"latency":730,"code":"404"}"#;
+        assert!(matches("{ $.latency > 500 }", msg));
+        assert!(matches("{ $.latency >= 730 }", msg));
+        assert!(!matches("{ $.latency < 500 }", msg));
+        assert!(matches("{ $.latency <= 730 }", msg));
+        assert!(matches("{ $.code < 500 }", msg));
+        assert!(!matches("{ $.missing < 500 }", msg));
+    }
+
+    #[test]
+    fn non_numeric_words_are_not_numbers() {
+        // 数値は JSON 条件の中でだけ読み取るため、`{ }` の中身のトークンを比べる
+        let kinds = |values: &str| -> Vec<lexer::TokenKind> {
+            let tokens = lexer::tokenize(&format!("{{ {} }}", values)).unwrap();
+            tokens[1..tokens.len() - 1]
+                .iter()
+                .map(|t| t.kind.clone())
+                .collect()
+        };
+        let word = |w: &str| lexer::TokenKind::Word(w.to_string());
+        assert_eq!(
+            kinds("nan inf Infinity -inf +inf 1e999"),
+            [
+                word("nan"),
+                word("inf"),
+                word("Infinity"),
+                word("-inf"),
+                word("+inf"),
+                word("1e999")
+            ]
+        );
+        assert_eq!(
+            kinds("1.5 -2 .5"),
+            [
+                lexer::TokenKind::Number(1.5),
+                lexer::TokenKind::Number(-2.0),
+                lexer::TokenKind::Number(0.5)
+            ]
+        );
+        assert!(matches("{ $.mode = nan }", r#"{"mode":"nan"}"#));
+        assert!(matches("{ $.limit = infinity }", r#"{"limit":"infinity"}"#));
+        assert!(!matches("{ $.limit = inf }", r#"{"limit":1e308}"#));
+    }
+
+    #[test]
+    fn json_boolean_and_null_values() {
+        let msg = r#"{"ok":true,"retry":false,"user":null}"#;
+        assert!(matches("{ $.ok = true }", msg));
+        assert!(matches("{ $.ok = TRUE }", r#"{"ok":"TRUE"}"#));
+        assert!(!matches("{ $.ok = false }", msg));
+        assert!(matches("{ $.retry = false }", msg));
+        assert!(matches("{ $.ok IS TRUE }", msg));
+        assert!(matches("{ $.retry is false }", msg));
+        assert!(!matches("{ $.ok IS FALSE }", msg));
+        assert!(matches("{ $.user IS NULL }", msg));
+        assert!(!matches("{ $.ok IS NULL }", msg));
+        assert!(matches("{ $.ok IS NOT NULL }", msg));
+        assert!(!matches("{ $.user IS NOT NULL }", msg));
+        assert!(matches("{ $.retry IS NOT TRUE }", msg));
+    }
+
+    #[test]
+    fn json_exists() {
+        let msg = r#"{"id":"a","tags":["prod","web"]}"#;
+        assert!(matches("{ $.missing NOT EXISTS }", msg));
+        assert!(!matches("{ $.id NOT EXISTS }", msg));
+        assert!(matches(r#"{ $.tags[*] = "prod" }"#, msg));
+        assert!(matches(r#"{ $.tags[1] = "web" }"#, msg));
+        assert!(!matches(r#"{ $.tags[0] = "web" }"#, msg));
+    }
+
+    #[test]
+    fn json_logical_operators() {
+        let msg = r#"{"level":"error","latency":120,"path":"/health"}"#;
+        assert!(matches(r#"{ $.level = "error" && $.latency > 100 }"#, msg));
+        assert!(!matches(r#"{ $.level = "error" && $.latency > 500 }"#, msg));
+        assert!(matches(r#"{ $.level = "warn" || $.latency > 100 }"#, msg));
+        assert!(matches(
+            r#"{ ($.level = "warn" || $.level = "error") && $.path != "/api" }"#,
+            msg
+        ));
+        assert!(!matches(
+            r#"{ $.level = "warn" || ($.level = "error" && $.path = "/api") }"#,
+            msg
+        ));
+    }
+
+    #[test]
+    fn space_delimited_fields() {
+        let line =
+            r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /a.gif HTTP/1.0" 503 2326"#;
+        let pattern = "[ip, id, user, timestamp, request, status_code, size]";
+        assert!(matches(pattern, line));
+        assert!(matches(
+            "[ip, id, user, timestamp, request, status_code = 5*, size > 1000]",
+            line
+        ));
+        assert!(!matches(
+            "[ip, id, user, timestamp, request, status_code = 4*, size]",
+            line
+        ));
+        assert!(matches(
+            "[ip, ..., status_code = 404 || status_code = 503, size]",
+            line
+        ));
+        assert!(matches("[ip = 127.0.0.1, ...]", line));
+        assert!(!matches("[ip, user]", line));
+    }
+
+    #[test]
+    fn error_positions() {
+        let e = error(r#"ERROR "unclosed"#);
+        assert_eq!(e.column, 6);
+        let e = error(r#"{ $.level = "error" "#);
+        assert_eq!(e.column, 20);
+        let e = error("{ $.latency > slow }");
+        assert_eq!(e.column, 14);
+        let e = error("{ level = 1 }");
+        assert_eq!(e.column, 2);
+        let e = error("{ $.a = 1 } extra");
+        assert_eq!(e.column, 12);
+        let e = error("[a, b = 1, c ! 2]");
+        assert_eq!(e.column, 13);
+        assert!(
+            error("{ $.a = 1 } extra")
+                .to_string()
+                .starts_with("13文字目: ")
+        );
+        let e = error("%[%");
+        assert_eq!(e.column, 0);
+    }
+
+    #[test]
+    fn non_ascii_input() {
+        assert!(matches("エラー", "処理中にエラーが発生しました"));
+        assert!(!matches("エラー", "正常終了"));
+        assert!(matches("ERROR é", "ERROR café"));
+        assert!(matches("-エラー", "正常終了"));
+        assert!(matches("?警告 ?エラー", "警告: ディスク残量"));
+        assert!(matches(
+            r#"{ $.msg = "失敗*" }"#,
+            r#"{"msg":"失敗しました"}"#
+        ));
+        assert!(matches("{ $.ユーザー = 太郎 }", r#"{"ユーザー":"太郎"}"#));
+        // エラー位置はバイトではなく文字数
+        let e = error(r#"エラー "閉じない"#);
+        assert_eq!(e.column, 4);
+    }
+
+    #[test]
+    fn analyze_never_panics_on_partial_input() {
+        for input in [
+            "エ",
+            "-エ",
+            "?é",
+            "{ $.a = ",
+            "[a, ",
+            "{ $.ok IS NOT",
+            "\"é",
+            "%é",
+        ] {
+            let mut prefix = String::new();
+            for c in input.chars() {
+                prefix.push(c);
+                let _ = analyze(&prefix);
+            }
+        }
+    }
+}
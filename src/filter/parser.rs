@@ -0,0 +1,535 @@
+//! フィルタパターンの構文解析。
+//!
+//! [`lexer::tokenize`](super::lexer::tokenize) が返すトークン列から AST を構築します。
+
+use regex::Regex;
+
+use super::PatternError;
+use super::lexer::{CmpOp, Token, TokenKind};
+
+/// フィルタパターンの AST。
+#[derive(Debug, Clone)]
+pub enum Ast {
+    /// 空白区切りの検索語（`ERROR -timeout ?WARN` など）
+    Terms(Vec<Term>),
+    /// JSON セレクタ式（`{ $.level = "error" }` など）
+    Json(JsonExpr),
+    /// 空白区切りログ形式（`[ip, user, status = 5*, ...]` など）
+    Delimited(Delimited),
+}
+
+/// 検索語の一致条件の種類。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TermKind {
+    /// 必ず含む
+    Required,
+    /// 含まない（`-term`）
+    Excluded,
+    /// いずれかを含む（`?term`）
+    Optional,
+}
+
+/// 1 つの検索語。
+#[derive(Debug, Clone)]
+pub struct Term {
+    /// 一致条件の種類
+    pub kind: TermKind,
+    /// 照合方法
+    pub matcher: TextMatcher,
+}
+
+/// 文字列に対する照合方法。
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    /// 部分一致（大文字小文字を区別）
+    Contains(String),
+    /// 正規表現（`%...%`）
+    Regex(Regex),
+}
+
+/// JSON セレクタ式。
+#[derive(Debug, Clone)]
+pub enum JsonExpr {
+    /// `a && b`
+    And(Box<JsonExpr>, Box<JsonExpr>),
+    /// `a || b`
+    Or(Box<JsonExpr>, Box<JsonExpr>),
+    /// `$.path op value`
+    Compare {
+        /// セレクタのパス
+        path: Vec<PathSegment>,
+        /// 比較演算子
+        op: CmpOp,
+        /// 比較する値
+        value: Value,
+    },
+    /// `$.path IS [NOT] TRUE|FALSE|NULL`
+    Is {
+        /// セレクタのパス
+        path: Vec<PathSegment>,
+        /// 期待する値
+        expected: IsValue,
+        /// `IS NOT` の場合は `true`
+        negated: bool,
+    },
+    /// `$.path NOT EXISTS`
+    NotExists {
+        /// セレクタのパス
+        path: Vec<PathSegment>,
+    },
+}
+
+/// JSON パスの 1 要素。
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// オブジェクトのキー
+    Key(String),
+    /// 配列のインデックス
+    Index(usize),
+    /// 配列の全要素（`[*]`）
+    AnyIndex,
+}
+
+/// `IS` の右辺。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IsValue {
+    /// `TRUE`
+    True,
+    /// `FALSE`
+    False,
+    /// `NULL`
+    Null,
+}
+
+/// 比較の右辺値。
+#[derive(Debug, Clone)]
+pub enum Value {
+    /// 数値
+    Number(f64),
+    /// 文字列（`*` はワイルドカード）
+    Text(String),
+    /// 正規表現（`%...%`）
+    Regex(Regex),
+}
+
+/// 空白区切りログ形式のパターン。
+#[derive(Debug, Clone)]
+pub struct Delimited {
+    /// フィールド定義（`...` は任意個のフィールドに一致）
+    pub fields: Vec<DelimitedField>,
+    /// フィールドに付随する条件（全て満たす必要がある）
+    pub conditions: Vec<FieldExpr>,
+}
+
+/// 空白区切りログ形式のフィールド。
+#[derive(Debug, Clone, PartialEq)]
+pub enum DelimitedField {
+    /// 名前付きフィールド
+    Named(String),
+    /// `...`
+    Ellipsis,
+}
+
+/// 空白区切りログ形式のフィールド条件式。
+#[derive(Debug, Clone)]
+pub enum FieldExpr {
+    /// `a && b`
+    And(Box<FieldExpr>, Box<FieldExpr>),
+    /// `a || b`
+    Or(Box<FieldExpr>, Box<FieldExpr>),
+    /// `field op value`
+    Compare {
+        /// フィールド名
+        field: String,
+        /// 比較演算子
+        op: CmpOp,
+        /// 比較する値
+        value: Value,
+    },
+}
+
+/// トークン列を AST に変換します。
+///
+/// # Errors
+///
+/// - 構文が不正な場合（位置付きのエラーを返します）
+pub fn parse(input: &str, tokens: &[Token]) -> Result<Ast, PatternError> {
+    let mut p = Parser {
+        input,
+        tokens,
+        pos: 0,
+    };
+    let ast = match tokens.first().map(|t| &t.kind) {
+        Some(TokenKind::LBrace) => Ast::Json(p.json_pattern()?),
+        Some(TokenKind::LBracket) => Ast::Delimited(p.delimited_pattern()?),
+        _ => Ast::Terms(p.terms()?),
+    };
+    if let Some(t) = p.peek() {
+        return Err(p.error_at(t.start, "パターンの後ろに余分な文字があります"));
+    }
+    Ok(ast)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<&Token> {
+        let t = self.tokens.get(self.pos);
+        self.pos += 1;
+        t
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> PatternError {
+        PatternError::new(self.input, pos, message)
+    }
+
+    /// 入力末尾でのエラー（閉じ括弧不足など）を返します。
+    fn error_eof(&self, message: &str) -> PatternError {
+        PatternError::new(self.input, self.input.len(), message)
+    }
+
+    fn expect(&mut self, kind: &TokenKind, message: &str) -> Result<(), PatternError> {
+        match self.peek() {
+            Some(t) if &t.kind == kind => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(t) => Err(self.error_at(t.start, message)),
+            None => Err(self.error_eof(message)),
+        }
+    }
+
+    // ── terms ────────────────────────────────────────────────────────────────
+
+    fn terms(&mut self) -> Result<Vec<Term>, PatternError> {
+        let mut terms = Vec::new();
+        while let Some(t) = self.peek() {
+            let kind = match t.kind {
+                TokenKind::Minus => TermKind::Excluded,
+                TokenKind::Question => TermKind::Optional,
+                _ => TermKind::Required,
+            };
+            if kind != TermKind::Required {
+                self.pos += 1;
+            }
+            let Some(t) = self.bump().cloned() else {
+                return Err(self.error_eof("記号の後ろに検索語がありません"));
+            };
+            let matcher = match t.kind {
+                TokenKind::Word(w) => TextMatcher::Contains(w),
+                // 引用符で囲まれた空文字列は全件一致
+                TokenKind::Quoted(q) if q.is_empty() => continue,
+                TokenKind::Quoted(q) => TextMatcher::Contains(q),
+                TokenKind::Regex(r) => TextMatcher::Regex(self.regex(&r, t.start)?),
+                _ => {
+                    return Err(self.error_at(
+                        t.start,
+                        "検索語の位置に `{`/`[` などの構造化パターンの記号があります",
+                    ));
+                }
+            };
+            terms.push(Term { kind, matcher });
+        }
+        Ok(terms)
+    }
+
+    fn regex(&self, src: &str, pos: usize) -> Result<Regex, PatternError> {
+        Regex::new(src).map_err(|e| {
+            let detail = e.to_string();
+            let last = detail.lines().last().unwrap_or("").trim().to_string();
+            self.error_at(pos, &format!("正規表現が不正です: {}", last))
+        })
+    }
+
+    // ── JSON ─────────────────────────────────────────────────────────────────
+
+    fn json_pattern(&mut self) -> Result<JsonExpr, PatternError> {
+        self.expect(&TokenKind::LBrace, "`{` が必要です")?;
+        let expr = self.json_or()?;
+        self.expect(&TokenKind::RBrace, "`}` が閉じられていません")?;
+        Ok(expr)
+    }
+
+    fn json_or(&mut self) -> Result<JsonExpr, PatternError> {
+        let mut lhs = self.json_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            let rhs = self.json_and()?;
+            lhs = JsonExpr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn json_and(&mut self) -> Result<JsonExpr, PatternError> {
+        let mut lhs = self.json_primary()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            let rhs = self.json_primary()?;
+            lhs = JsonExpr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn json_primary(&mut self) -> Result<JsonExpr, PatternError> {
+        let Some(t) = self.bump().cloned() else {
+            return Err(self.error_eof("条件式が必要です（例: $.level = \"error\"）"));
+        };
+        match t.kind {
+            TokenKind::LParen => {
+                let expr = self.json_or()?;
+                self.expect(&TokenKind::RParen, "`)` が閉じられていません")?;
+                Ok(expr)
+            }
+            TokenKind::Selector(sel) => {
+                let path = self.selector_path(&sel, t.start)?;
+                self.json_condition(path)
+            }
+            _ => Err(self.error_at(t.start, "`$.` で始まるセレクタが必要です")),
+        }
+    }
+
+    fn json_condition(&mut self, path: Vec<PathSegment>) -> Result<JsonExpr, PatternError> {
+        let Some(t) = self.bump().cloned() else {
+            return Err(self.error_eof("比較演算子が必要です（=, !=, <, <=, >, >=）"));
+        };
+        match t.kind {
+            TokenKind::Op(op) => {
+                let value = self.value(op)?;
+                Ok(JsonExpr::Compare { path, op, value })
+            }
+            TokenKind::Keyword(k) if k == "IS" => {
+                let negated = self
+                    .peek()
+                    .is_some_and(|t| matches!(&t.kind, TokenKind::Keyword(k) if k == "NOT"));
+                if negated {
+                    self.pos += 1;
+                }
+                let Some(v) = self.bump().cloned() else {
+                    return Err(self.error_eof("IS の後ろに TRUE/FALSE/NULL が必要です"));
+                };
+                let expected = match &v.kind {
+                    TokenKind::Keyword(k) if k == "TRUE" => IsValue::True,
+                    TokenKind::Keyword(k) if k == "FALSE" => IsValue::False,
+                    TokenKind::Keyword(k) if k == "NULL" => IsValue::Null,
+                    _ => {
+                        return Err(
+                            self.error_at(v.start, "IS の後ろに TRUE/FALSE/NULL が必要です")
+                        );
+                    }
+                };
+                Ok(JsonExpr::Is {
+                    path,
+                    expected,
+                    negated,
+                })
+            }
+            TokenKind::Keyword(k) if k == "NOT" => {
+                match self.bump().cloned() {
+                    Some(Token {
+                        kind: TokenKind::Keyword(k),
+                        ..
+                    }) if k == "EXISTS" => {}
+                    Some(v) => {
+                        return Err(self.error_at(v.start, "NOT の後ろに EXISTS が必要です"));
+                    }
+                    None => return Err(self.error_eof("NOT の後ろに EXISTS が必要です")),
+                }
+                Ok(JsonExpr::NotExists { path })
+            }
+            _ => Err(self.error_at(
+                t.start,
+                "比較演算子が必要です（=, !=, <, <=, >, >=, IS, NOT EXISTS）",
+            )),
+        }
+    }
+
+    fn selector_path(&self, sel: &str, pos: usize) -> Result<Vec<PathSegment>, PatternError> {
+        let Some(rest) = sel.strip_prefix('$') else {
+            return Err(self.error_at(pos, "セレクタは `$` で始まる必要があります"));
+        };
+        let mut path = Vec::new();
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '.' => {
+                    let mut key = String::new();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    if key.is_empty() {
+                        return Err(self.error_at(pos + 1 + i, "`.` の後ろにキー名が必要です"));
+                    }
+                    path.push(PathSegment::Key(key));
+                }
+                '[' => {
+                    let mut idx = String::new();
+                    let mut closed = false;
+                    for (_, c) in chars.by_ref() {
+                        if c == ']' {
+                            closed = true;
+                            break;
+                        }
+                        idx.push(c);
+                    }
+                    if !closed {
+                        return Err(self.error_at(pos + 1 + i, "`[` が閉じられていません"));
+                    }
+                    if idx == "*" {
+                        path.push(PathSegment::AnyIndex);
+                    } else {
+                        let n = idx.parse::<usize>().map_err(|_| {
+                            self.error_at(pos + 1 + i, "配列のインデックスが不正です")
+                        })?;
+                        path.push(PathSegment::Index(n));
+                    }
+                }
+                _ => {
+                    return Err(self.error_at(
+                        pos + 1 + i,
+                        "セレクタは `$.key` や `$.list[0]` の形式で指定してください",
+                    ));
+                }
+            }
+        }
+        if path.is_empty() {
+            return Err(self.error_at(pos, "セレクタにキーがありません（例: $.level）"));
+        }
+        Ok(path)
+    }
+
+    fn value(&mut self, op: CmpOp) -> Result<Value, PatternError> {
+        let Some(t) = self.bump().cloned() else {
+            return Err(self.error_eof("比較する値が必要です"));
+        };
+        let ordering = !matches!(op, CmpOp::Eq | CmpOp::Ne);
+        let value = match t.kind {
+            TokenKind::Number(n) => Value::Number(n),
+            _ if ordering => {
+                return Err(self.error_at(t.start, "大小比較の右辺は数値である必要があります"));
+            }
+            TokenKind::Word(w) => Value::Text(w),
+            TokenKind::Quoted(q) => Value::Text(q),
+            // キーワードと同じ綴りの値は大文字化する前の元の綴りで比較する
+            TokenKind::Keyword(_) => Value::Text(self.input[t.start..t.end].to_string()),
+            TokenKind::Regex(r) => Value::Regex(self.regex(&r, t.start)?),
+            _ => return Err(self.error_at(t.start, "比較する値が必要です")),
+        };
+        Ok(value)
+    }
+
+    // ── space-delimited ──────────────────────────────────────────────────────
+
+    fn delimited_pattern(&mut self) -> Result<Delimited, PatternError> {
+        self.expect(&TokenKind::LBracket, "`[` が必要です")?;
+        let mut fields = Vec::new();
+        let mut conditions = Vec::new();
+        loop {
+            let Some(t) = self.bump().cloned() else {
+                return Err(self.error_eof("`]` が閉じられていません"));
+            };
+            match t.kind {
+                TokenKind::Ellipsis => fields.push(DelimitedField::Ellipsis),
+                TokenKind::Word(name) => {
+                    if self
+                        .peek()
+                        .is_some_and(|t| matches!(t.kind, TokenKind::Op(_)))
+                    {
+                        self.pos -= 1;
+                        conditions.push(self.field_or()?);
+                    }
+                    fields.push(DelimitedField::Named(name));
+                }
+                _ => return Err(self.error_at(t.start, "フィールド名または `...` が必要です")),
+            }
+            match self.bump().cloned() {
+                Some(Token {
+                    kind: TokenKind::Comma,
+                    ..
+                }) => {}
+                Some(Token {
+                    kind: TokenKind::RBracket,
+                    ..
+                }) => break,
+                Some(t) => return Err(self.error_at(t.start, "`,` または `]` が必要です")),
+                None => return Err(self.error_eof("`]` が閉じられていません")),
+            }
+        }
+        // 条件が参照するフィールドが定義されているか検証する
+        for cond in &conditions {
+            let mut names = Vec::new();
+            cond.field_names(&mut names);
+            for name in names {
+                if !fields.contains(&DelimitedField::Named(name.to_string())) {
+                    return Err(self.error_eof(&format!(
+                        "条件で参照しているフィールド `{}` が定義されていません",
+                        name
+                    )));
+                }
+            }
+        }
+        Ok(Delimited { fields, conditions })
+    }
+
+    fn field_or(&mut self) -> Result<FieldExpr, PatternError> {
+        let mut lhs = self.field_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            let rhs = self.field_and()?;
+            lhs = FieldExpr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn field_and(&mut self) -> Result<FieldExpr, PatternError> {
+        let mut lhs = self.field_compare()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            let rhs = self.field_compare()?;
+            lhs = FieldExpr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn field_compare(&mut self) -> Result<FieldExpr, PatternError> {
+        let Some(t) = self.bump().cloned() else {
+            return Err(self.error_eof("フィールド名が必要です"));
+        };
+        let TokenKind::Word(field) = t.kind else {
+            return Err(self.error_at(t.start, "フィールド名が必要です"));
+        };
+        let op = match self.bump().cloned() {
+            Some(Token {
+                kind: TokenKind::Op(op),
+                ..
+            }) => op,
+            Some(t) => return Err(self.error_at(t.start, "比較演算子が必要です")),
+            None => return Err(self.error_eof("比較演算子が必要です")),
+        };
+        let value = self.value(op)?;
+        Ok(FieldExpr::Compare { field, op, value })
+    }
+}
+
+impl FieldExpr {
+    fn field_names<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            FieldExpr::And(a, b) | FieldExpr::Or(a, b) => {
+                a.field_names(out);
+                b.field_names(out);
+            }
+            FieldExpr::Compare { field, .. } => out.push(field),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! JSON メッセージ内のフィールドを指すパス表現。
+//!
+//! `level`・`http.status`・`.items[0].id`・`$.a["key with space"]` のような
+//! ドット区切りのパスを解釈し、[`serde_json::Value`] から値を取り出します。
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use std::fmt;
+use std::ops::Range;
+
+/// パスの1要素。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// オブジェクトのキー
+    Key(String),
+    /// 配列のインデックス
+    Index(usize),
+}
+
+/// JSON フィールドへのパス。空のパスはルート自体を指します。
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    /// ルートから順に辿る要素
+    pub segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// パス文字列を解釈します。先頭の `$` と `.` は省略可能です。
+    ///
+    /// # Errors
+    ///
+    /// 空のキー・閉じていない `[`・数値でも文字列でもない添字がある場合
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (path, rest) = Self::parse_prefix(input)?;
+        if !rest.trim().is_empty() {
+            return Err(format!("パスの後に不要な文字があります: {}", rest.trim()));
+        }
+        Ok(path)
+    }
+
+    /// 入力の先頭からパスを読み取り、パスと残りの文字列を返します。
+    ///
+    /// キーに使えない文字（空白・演算子など）に達した時点で読み取りを終えます。
+    pub fn parse_prefix(input: &str) -> Result<(Self, &str), String> {
+        let start = input.trim_start();
+        let mut rest = start.strip_prefix('$').unwrap_or(start);
+        let mut segments = Vec::new();
+        // 先頭のキーはドットを省略できる（`level` と `.level` は同じ）
+        let len = key_len(rest);
+        if len > 0 {
+            segments.push(Segment::Key(rest[..len].to_string()));
+            rest = &rest[len..];
+        }
+        loop {
+            if let Some(r) = rest.strip_prefix('[') {
+                let (segment, r) = parse_bracket(r)?;
+                segments.push(segment);
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix('.') {
+                let len = key_len(r);
+                if len > 0 {
+                    segments.push(Segment::Key(r[..len].to_string()));
+                    rest = &r[len..];
+                } else if r.starts_with('[') {
+                    rest = r;
+                } else if segments.is_empty() {
+                    // `.` 単体はルートを指す
+                    rest = r;
+                    break;
+                } else {
+                    return Err("`.` の後にキーがありません".to_string());
+                }
+            } else {
+                break;
+            }
+        }
+        if rest.len() == start.len() {
+            return Err("パスが空です".to_string());
+        }
+        Ok((Self { segments }, rest))
+    }
+
+    /// `value` からパスの指す値を取り出します。存在しない場合は `None` を返します。
+    pub fn lookup<'a>(&self, value: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |v, segment| match segment {
+                Segment::Key(k) => v.get(k.as_str()),
+                Segment::Index(i) => v.get(*i),
+            })
+    }
+
+    /// JSON テキスト `json` のうち、パスの指す値が書かれている範囲（バイト位置）を返します。
+    ///
+    /// 同じキーが複数回現れるオブジェクトでは、全ての出現を辿ります。
+    /// 存在しない場合と `json` が JSON として不正な場合は空の一覧を返します。
+    pub fn spans(&self, json: &str) -> Vec<Range<usize>> {
+        let Ok(root) = serde_json::from_str::<&RawValue>(json) else {
+            return Vec::new();
+        };
+        let mut values = vec![root];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for raw in values {
+                match segment {
+                    Segment::Key(k) => {
+                        if let Ok(Entries(entries)) = serde_json::from_str(raw.get()) {
+                            next.extend(
+                                entries
+                                    .into_iter()
+                                    .filter(|(key, _)| key == k)
+                                    .map(|(_, value)| value),
+                            );
+                        }
+                    }
+                    Segment::Index(i) => {
+                        if let Ok(items) = serde_json::from_str::<Vec<&RawValue>>(raw.get())
+                            && let Some(item) = items.get(*i)
+                        {
+                            next.push(*item);
+                        }
+                    }
+                }
+            }
+            values = next;
+        }
+        // `RawValue` は `json` の一部を借用しているため、ポインタの差が位置になる
+        values
+            .into_iter()
+            .map(|raw| {
+                let start = raw.get().as_ptr() as usize - json.as_ptr() as usize;
+                start..start + raw.get().len()
+            })
+            .collect()
+    }
+}
+
+/// JSON オブジェクトのエントリ（重複したキーも含めて出現順）。
+struct Entries<'a>(Vec<(String, &'a RawValue)>);
+
+impl<'de> Deserialize<'de> for Entries<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor;
+
+        impl<'de> Visitor<'de> for EntriesVisitor {
+            type Value = Entries<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor)
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, ".");
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(k) if key_len(k) == k.len() => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", k)?;
+                }
+                Segment::Key(k) => write!(f, "[{}]", serde_json::Value::from(k.as_str()))?,
+                Segment::Index(n) => write!(f, "[{}]", n)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[` の直後から添字を読み取ります。
+fn parse_bracket(input: &str) -> Result<(Segment, &str), String> {
+    let Some(end) = input.find(']') else {
+        return Err("`[` が閉じていません".to_string());
+    };
+    let inner = input[..end].trim();
+    let segment = if let Some(quoted) = inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Segment::Key(quoted.to_string())
+    } else if let Ok(n) = inner.parse::<usize>() {
+        Segment::Index(n)
+    } else {
+        return Err(format!("添字が不正です: [{}]", inner));
+    };
+    Ok((segment, &input[end + 1..]))
+}
+
+/// 先頭からキーとして使える文字の長さ（バイト数）を返します。
+fn key_len(s: &str) -> usize {
+    s.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '@'))
+        .unwrap_or(s.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(input: &str) -> JsonPath {
+        JsonPath::parse(input).unwrap()
+    }
+
+    fn spans<'a>(input: &str, json: &'a str) -> Vec<&'a str> {
+        path(input)
+            .spans(json)
+            .into_iter()
+            .map(|span| &json[span])
+            .collect()
+    }
+
+    #[test]
+    fn parse_forms() {
+        let key = |k: &str| Segment::Key(k.to_string());
+        assert_eq!(path("level").segments, vec![key("level")]);
+        assert_eq!(
+            path("$.http.status").segments,
+            vec![key("http"), key("status")]
+        );
+        assert_eq!(
+            path(".items[0].id").segments,
+            vec![key("items"), Segment::Index(0), key("id")]
+        );
+        assert_eq!(
+            path(r#"$.a["key with space"]"#).segments,
+            vec![key("a"), key("key with space")]
+        );
+        assert!(path(".").segments.is_empty());
+        assert!(JsonPath::parse("").is_err());
+        assert!(JsonPath::parse("a.").is_err());
+        assert!(JsonPath::parse("a[").is_err());
+        assert!(JsonPath::parse("a[x]").is_err());
+        assert!(JsonPath::parse("a b").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for input in [
+            "level",
+            "http.status",
+            "items[0].id",
+            r#"a["key with space"]"#,
+            ".",
+        ] {
+            assert_eq!(path(&path(input).to_string()), path(input));
+        }
+    }
+
+    #[test]
+    fn lookup_values() {
+        let value = json!({ "a": { "b": [10, { "c": "x" }] } });
+        assert_eq!(path("a.b[0]").lookup(&value), Some(&json!(10)));
+        assert_eq!(path("a.b[1].c").lookup(&value), Some(&json!("x")));
+        assert_eq!(path("a.b[2]").lookup(&value), None);
+        assert_eq!(path("a.missing").lookup(&value), None);
+        assert_eq!(path(".").lookup(&value), Some(&value));
+    }
+
+    #[test]
+    fn spans_of_nested_values() {
+        let json = r#" { "a" : { "b" : [ 10 , { "c" : "x" } ] } } "#;
+        assert_eq!(spans("a.b[0]", json), vec!["10"]);
+        assert_eq!(spans("a.b[1].c", json), vec![r#""x""#]);
+        assert_eq!(spans("a.b[1]", json), vec![r#"{ "c" : "x" }"#]);
+        assert_eq!(spans(".", json), vec![json.trim()]);
+        assert!(spans("a.b[5]", json).is_empty());
+        assert!(spans("a.b.c", json).is_empty());
+    }
+
+    #[test]
+    fn spans_of_duplicate_keys() {
+        let json = r#"{"k":1,"o":{"k":2},"k":3,"o":{"k":4}}"#;
+        assert_eq!(spans("k", json), vec!["1", "3"]);
+        assert_eq!(spans("o.k", json), vec!["2", "4"]);
+    }
+
+    #[test]
+    fn spans_of_escaped_keys_and_non_ascii() {
+        // キーはエスケープを解釈してから比較する
+        let json = r#"{"a\u0062":"é","ユーザー":{"名前":"太郎"}}"#;
+        assert_eq!(spans("ab", json), vec![r#""é""#]);
+        assert_eq!(spans("ユーザー.名前", json), vec![r#""太郎""#]);
+        assert!(spans("a", "not json").is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! ログレベル（重要度）の判定。
+//!
+//! JSON メッセージの `level`・`severity` などのフィールド、またはメッセージ本文中の
+//! キーワード（`ERROR`・`WARN` など）からログレベルを推定します。
+
+/// ログイベントの重要度。重要度の高い順に並んでいます。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Level {
+    /// FATAL / CRITICAL / PANIC
+    Fatal,
+    /// ERROR
+    Error,
+    /// WARN / WARNING
+    Warn,
+    /// INFO / NOTICE
+    Info,
+    /// DEBUG
+    Debug,
+    /// TRACE
+    Trace,
+    /// 判定できなかったもの
+    Unknown,
+}
+
+/// JSON メッセージでレベルを表すフィールド名（小文字で比較）
+const LEVEL_FIELDS: &[&str] = &[
+    "level",
+    "severity",
+    "lvl",
+    "loglevel",
+    "log_level",
+    "levelname",
+    "severity_text",
+];
+
+/// 本文から検索するキーワード（長いものを先に照合する）
+const KEYWORDS: &[(&str, Level)] = &[
+    ("CRITICAL", Level::Fatal),
+    ("FATAL", Level::Fatal),
+    ("PANIC", Level::Fatal),
+    ("ERROR", Level::Error),
+    ("ERR", Level::Error),
+    ("WARNING", Level::Warn),
+    ("WARN", Level::Warn),
+    ("INFO", Level::Info),
+    ("NOTICE", Level::Info),
+    ("DEBUG", Level::Debug),
+    ("TRACE", Level::Trace),
+];
+
+impl Level {
+    /// 全てのレベル（重要度の高い順）
+    pub const ALL: [Level; 7] = [
+        Level::Fatal,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+        Level::Unknown,
+    ];
+
+    /// 表示用のラベルを返します。
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Fatal => "FATAL",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+            Level::Unknown => "OTHER",
+        }
+    }
+
+    /// [`Level::ALL`] 内でのインデックスを返します。
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// メッセージからログレベルを判定します。
+    ///
+    /// JSON オブジェクトの場合はレベルを表すフィールドを優先し、
+    /// 見つからない場合は本文中のキーワードから判定します。
+    pub fn detect(message: &str) -> Level {
+        let trimmed = message.trim();
+        if trimmed.starts_with('{')
+            && let Ok(serde_json::Value::Object(map)) =
+                serde_json::from_str::<serde_json::Value>(trimmed)
+        {
+            let field = map
+                .iter()
+                .find(|(k, _)| LEVEL_FIELDS.contains(&k.to_ascii_lowercase().as_str()));
+            if let Some((_, value)) = field {
+                let level = match value {
+                    serde_json::Value::String(s) => Level::from_name(s),
+                    serde_json::Value::Number(n) => n.as_i64().and_then(Level::from_number),
+                    _ => None,
+                };
+                if let Some(level) = level {
+                    return level;
+                }
+            }
+        }
+        Level::from_keyword(message)
+    }
+
+    /// レベル名（大文字小文字不問）を [`Level`] に変換します。
+    fn from_name(name: &str) -> Option<Level> {
+        let level = match name.trim().to_ascii_lowercase().as_str() {
+            "fatal" | "critical" | "crit" | "panic" | "emerg" | "emergency" | "alert" => {
+                Level::Fatal
+            }
+            "error" | "err" => Level::Error,
+            "warn" | "warning" => Level::Warn,
+            "info" | "information" | "notice" => Level::Info,
+            "debug" => Level::Debug,
+            "trace" | "verbose" => Level::Trace,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// 数値のレベル（pino / bunyan 形式: 10=trace … 60=fatal）を [`Level`] に変換します。
+    fn from_number(n: i64) -> Option<Level> {
+        let level = match n {
+            60.. => Level::Fatal,
+            50..=59 => Level::Error,
+            40..=49 => Level::Warn,
+            30..=39 => Level::Info,
+            20..=29 => Level::Debug,
+            10..=19 => Level::Trace,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// 本文中に単語として現れる最初のキーワードからレベルを判定します。
+    fn from_keyword(message: &str) -> Level {
+        let upper = message.to_ascii_uppercase();
+        for (kw, level) in KEYWORDS {
+            let found = upper.match_indices(kw).any(|(pos, _)| {
+                let before = upper[..pos].chars().next_back();
+                let after = upper[pos + kw.len()..].chars().next();
+                !before.is_some_and(char::is_alphanumeric)
+                    && !after.is_some_and(char::is_alphanumeric)
+            });
+            if found {
+                return *level;
+            }
+        }
+        Level::Unknown
+    }
+}
@@ -5,22 +5,66 @@
 //! ## 主な機能
 //!
 //! - ロググループ・ログストリームの一覧表示とキーボードナビゲーション
-//! - ログイベントの閲覧・絞り込み・JSONL ダウンロード
-//! - 時間範囲とフィルタパターンによるクロスストリーム検索
+//! - ログイベントの閲覧・絞り込み・ダウンロード（JSONL・CSV・テキスト・JSON 配列・Markdown、
+//!   条件に一致する全ページのバックグラウンド取得・チェックポイントからの再開と gzip・zstd 圧縮にも対応）
+//! - JSON メッセージのフィールドを列として表示（ロググループごとに保存）
+//! - jq 風のフィールドクエリによるロード済みイベントの絞り込みと値の取り出し
+//! - ロググループ全体のログストリームごとのアーカイブ（並行ダウンロードとチェックサム付きマニフェスト）
+//! - ダウンロード済み JSONL ファイル（圧縮ファイルを含む）のオフライン閲覧とローカルでのフィルタパターン適用
+//! - 時間範囲とフィルタパターンによるクロスストリーム検索と検索結果のダウンロード
+//! - 絶対時刻・相対時刻を指定したイベント一覧内の移動（未ロードの場合はその時刻から取得）
+//! - ログストリーム・ロググループをまたいだイベントのブックマークと書き出し
+//! - 数値・UUID・タイムスタンプを正規化した連続する重複メッセージの折りたたみ
+//! - Drain 方式によるログテンプレートの抽出とテンプレートごとの絞り込み
+//! - 2 つのイベントの差分表示（JSON はキー単位、テキストは行単位）
+//! - OSC 52 によるイベント範囲・フィールドのクリップボードコピー
+//! - スクリプト向けのサブコマンド（`groups`・`streams`・`events`、テキスト・JSON・JSONL を標準出力に出力）
+//! - `aws logs tail` と同様の `tail` サブコマンド（新しいイベントの追跡・レベルの色付け・JSON の整形）
 //! - vim ライクなキーバインド (`j`/`k` で移動、`q` で戻る)
+//! - 正規表現・JSON パスによるメッセージのマスキング（表示・コピー・書き出しのすべてに適用）
+//! - 表示タイムゾーンの切り替え（UTC・ローカル・IANA タイムゾーン）
 
 mod app;
+mod archive;
 mod aws;
+mod bookmarks;
+mod checkpoint;
+mod cli;
+mod clipboard;
+mod columns;
+mod compress;
+mod config;
+mod diff;
+mod download;
+mod drain;
+mod export;
+mod filter;
+mod json_path;
+mod level;
+mod normalize;
+mod offline;
+mod paths;
+mod query;
+mod redact;
 mod screen;
+mod time;
 mod tui;
 mod ui;
 
 use anyhow::Result;
 use app::App;
+use clap::Parser;
+use cli::Cli;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    time::init_from_env()?;
+    redact::init()?;
     let client = aws::build_client().await?;
+    if let Some(command) = cli.command {
+        return cli::run(client, command).await;
+    }
     let mut app = App::new(client);
     let mut terminal = tui::init()?;
     let result = app.run(&mut terminal).await;
@@ -0,0 +1,47 @@
+//! ログメッセージの正規化。
+//!
+//! UUID・タイムスタンプ・16 進 ID・数値などリクエストごとに変わる部分をプレースホルダに
+//! 置き換え、ほぼ同じ内容のメッセージを同一視できるようにします。
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// UUID
+static UUID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b").unwrap()
+});
+
+/// ISO 8601 風の日時、または時刻のみ
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:\d{4}-\d{2}-\d{2}[T ])?\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?")
+        .unwrap()
+});
+
+/// `0x` 付きの 16 進数、または 8 文字以上の 16 進文字列の候補
+static HEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:0x[0-9a-f]+|[0-9a-f]{8,})\b").unwrap());
+
+/// 整数・小数（`12ms` のように単位が続くものも含む）
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+(?:\.\d+)?").unwrap());
+
+/// メッセージの可変部分をプレースホルダ（`<uuid>`・`<ts>`・`<hex>`・`<num>`）に置き換えた文字列を返します。
+///
+/// 前後の空白は取り除き、連続する空白は 1 つにまとめます。
+pub fn normalize(message: &str) -> String {
+    let text = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = UUID.replace_all(&text, "<uuid>");
+    let text = TIMESTAMP.replace_all(&text, "<ts>");
+    let text = HEX.replace_all(&text, |caps: &Captures| {
+        let m = &caps[0];
+        // "deadbeef" のような英単語や数字だけの列は 16 進 ID とみなさない
+        let is_id = m.len() > 2 && m[..2].eq_ignore_ascii_case("0x")
+            || (m.bytes().any(|b| b.is_ascii_digit())
+                && m.bytes().any(|b| b.is_ascii_alphabetic()));
+        if is_id {
+            "<hex>".to_string()
+        } else {
+            m.to_string()
+        }
+    });
+    NUMBER.replace_all(&text, "<num>").into_owned()
+}
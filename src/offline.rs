@@ -0,0 +1,52 @@
+//! ダウンロード済み JSONL ファイルの読み込み。
+//!
+//! `d` でエクスポートした `{"timestamp": ..., "message": ...}` 形式の JSONL を
+//! [`LogEvent`] の一覧として読み込み、オフラインで閲覧できるようにします。
+//! 書き出し元の条件を記録した `{"export": {...}}` の行は読み飛ばします。
+//! gzip・zstd で圧縮したファイル（`.jsonl.gz`・`.jsonl.zst`）は展開して読み込みます。
+
+use anyhow::{Context, Result, bail};
+
+use crate::app::LogEvent;
+use crate::compress;
+
+/// JSONL ファイル（gzip・zstd 圧縮を含む）を読み込み、ログイベントの一覧を返します。
+///
+/// 空行と書き出し元の条件の行は無視します。`timestamp` が無い行は 0 として扱います。
+/// `stream` フィールドがあればログストリーム名として読み込みます。
+///
+/// # Errors
+///
+/// - ファイルの読み込みまたは展開に失敗した場合
+/// - JSON として解釈できない行、または `message` を持たない行がある場合
+pub fn read_jsonl(path: &str) -> Result<Vec<LogEvent>> {
+    let content = compress::read_to_string(path)?;
+    parse_jsonl(&content)
+}
+
+fn parse_jsonl(content: &str) -> Result<Vec<LogEvent>> {
+    let mut events = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value =
+            serde_json::from_str(line).with_context(|| format!("line {}: invalid JSON", i + 1))?;
+        if value.get("export").is_some() && value.get("message").is_none() {
+            continue;
+        }
+        let Some(message) = value.get("message").and_then(|m| m.as_str()) else {
+            bail!("line {}: `message` field is missing", i + 1);
+        };
+        let mut event = LogEvent::new(
+            value.get("timestamp").and_then(|t| t.as_i64()).unwrap_or(0),
+            message.to_string(),
+        );
+        event.stream = value
+            .get("stream")
+            .and_then(|s| s.as_str())
+            .map(String::from);
+        events.push(event);
+    }
+    Ok(events)
+}
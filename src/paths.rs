@@ -0,0 +1,169 @@
+//! パス入力の展開と補完。
+//!
+//! ダウンロード先やローカルファイルのパス入力で、先頭の `~` と `$VAR`・`${VAR}` を展開し、
+//! `Tab` でファイル名を補完します。補完では入力した `~`・`$VAR` をそのまま残します。
+
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+use crate::compress::Compression;
+
+/// 補完候補として返す最大件数
+const MAX_CANDIDATES: usize = 50;
+
+/// 先頭の `~`（ホームディレクトリ）と `$VAR`・`${VAR}`（環境変数）を展開します。
+///
+/// `~user` の形式には対応せず、そのまま残します。名前が続かない `$` もそのまま残します。
+///
+/// # Errors
+///
+/// - `~` を使っていてホームディレクトリ（`HOME`）が不明な場合
+/// - 参照した環境変数が設定されていない場合
+/// - `${` が閉じていない場合
+pub fn expand(input: &str) -> Result<String> {
+    let rest = match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = std::env::var("HOME")
+                .map_err(|_| anyhow!("ホームディレクトリ（HOME）が設定されていません"))?;
+            return Ok(home + &expand_vars(rest)?);
+        }
+        _ => input,
+    };
+    expand_vars(rest)
+}
+
+fn expand_vars(input: &str) -> Result<String> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+        let (name, remaining) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| anyhow!("`${{` が閉じていません: {}", input))?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let value =
+            std::env::var(name).map_err(|_| anyhow!("環境変数 {} が設定されていません", name))?;
+        out.push_str(&value);
+        rest = remaining;
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// パス補完の結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// 補完後の入力（候補が無い場合は元の入力）
+    pub input: String,
+    /// 候補が複数ある場合の候補名（ディレクトリは末尾に `/`）
+    pub candidates: Vec<String>,
+}
+
+/// 入力の最後の要素をファイル名・ディレクトリ名で補完します。
+///
+/// 候補が 1 つの場合はその名前まで（ディレクトリの場合は `/` まで）、
+/// 複数の場合は共通する先頭部分まで補完し、候補名を返します。
+/// `.` で始まる名前は、入力も `.` で始まる場合だけ候補にします。
+pub fn complete(input: &str) -> Completion {
+    let unchanged = || Completion {
+        input: input.to_string(),
+        candidates: Vec::new(),
+    };
+    if input == "~" {
+        return Completion {
+            input: "~/".to_string(),
+            candidates: Vec::new(),
+        };
+    }
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let dir = match dir_part {
+        "" => ".".to_string(),
+        _ => match expand(dir_part) {
+            Ok(dir) => dir,
+            Err(_) => return unchanged(),
+        },
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return unchanged();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = e.path().is_dir();
+            Some(if is_dir { name + "/" } else { name })
+        })
+        .collect();
+    names.sort();
+    match names.as_slice() {
+        [] => unchanged(),
+        [only] => Completion {
+            input: format!("{}{}", dir_part, only),
+            candidates: Vec::new(),
+        },
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |acc, name| {
+                let len = acc
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map(|((i, c), _)| i + c.len_utf8())
+                    .unwrap_or(0);
+                &acc[..len]
+            });
+            let common = common.to_string();
+            names.truncate(MAX_CANDIDATES);
+            Completion {
+                input: format!("{}{}", dir_part, common),
+                candidates: names,
+            }
+        }
+    }
+}
+
+/// `path` の親ディレクトリが存在しない場合、そのディレクトリを返します。
+pub fn missing_parent(path: &str) -> Option<String> {
+    let parent = Path::new(path).parent()?;
+    if parent.as_os_str().is_empty() || parent.is_dir() {
+        None
+    } else {
+        Some(parent.display().to_string())
+    }
+}
+
+/// `path` が既に存在する場合に、拡張子の前に `-1`・`-2`… を付けた存在しないパスを返します。
+///
+/// `events.jsonl.gz` は `events-1.jsonl.gz` のように、圧縮の拡張子を含めて拡張子として扱います。
+pub fn unique_path(path: &str) -> String {
+    let (base, _) = Compression::split(path);
+    let suffix = &path[base.len()..];
+    let file_start = base.rfind('/').map_or(0, |i| i + 1);
+    let (stem, ext) = match base.rfind('.') {
+        Some(i) if i > file_start => base.split_at(i),
+        _ => (base, ""),
+    };
+    (1..)
+        .map(|n| format!("{}-{}{}{}", stem, n, ext, suffix))
+        .find(|candidate| !Path::new(candidate).exists())
+        .unwrap_or_else(|| path.to_string())
+}
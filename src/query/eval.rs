@@ -0,0 +1,137 @@
+//! フィールドクエリの評価。
+//!
+//! jq と同様に存在しないフィールドは `null`、`null` と `false` 以外は真として扱います。
+//! `select` で除外された場合のみ結果が空（`None`）になります。
+
+use serde_json::Value as Json;
+use std::cmp::Ordering;
+
+use super::parser::{Expr, Func};
+use crate::filter::lexer::CmpOp;
+
+/// `input` に対して式を評価します。`select` で除外された場合は `None` を返します。
+pub fn eval(expr: &Expr, input: &Json) -> Option<Json> {
+    let value = match expr {
+        Expr::Path(path) => path.lookup(input).cloned().unwrap_or(Json::Null),
+        Expr::Literal(v) => v.clone(),
+        Expr::Pipe(lhs, rhs) => return eval(rhs, &eval(lhs, input)?),
+        Expr::Compare(lhs, op, rhs) => {
+            let ord = compare(&eval(lhs, input)?, &eval(rhs, input)?);
+            Json::Bool(match op {
+                CmpOp::Eq => ord == Ordering::Equal,
+                CmpOp::Ne => ord != Ordering::Equal,
+                CmpOp::Lt => ord == Ordering::Less,
+                CmpOp::Le => ord != Ordering::Greater,
+                CmpOp::Gt => ord == Ordering::Greater,
+                CmpOp::Ge => ord != Ordering::Less,
+            })
+        }
+        Expr::And(lhs, rhs) => Json::Bool(truthy(&eval(lhs, input)?) && truthy(&eval(rhs, input)?)),
+        Expr::Or(lhs, rhs) => Json::Bool(truthy(&eval(lhs, input)?) || truthy(&eval(rhs, input)?)),
+        Expr::Call(func) => return call(func, input),
+    };
+    Some(value)
+}
+
+/// `null` と `false` 以外を真として扱います。
+pub fn truthy(value: &Json) -> bool {
+    !matches!(value, Json::Null | Json::Bool(false))
+}
+
+fn call(func: &Func, input: &Json) -> Option<Json> {
+    let text = input.as_str();
+    let value = match func {
+        Func::Select(cond) => {
+            return truthy(&eval(cond, input)?).then(|| input.clone());
+        }
+        Func::Not => Json::Bool(!truthy(input)),
+        Func::StartsWith(arg) => {
+            let arg = eval(arg, input)?;
+            Json::Bool(matches!((text, arg.as_str()), (Some(t), Some(a)) if t.starts_with(a)))
+        }
+        Func::EndsWith(arg) => {
+            let arg = eval(arg, input)?;
+            Json::Bool(matches!((text, arg.as_str()), (Some(t), Some(a)) if t.ends_with(a)))
+        }
+        Func::Contains(arg) => {
+            let arg = eval(arg, input)?;
+            Json::Bool(match (input, &arg) {
+                (Json::String(t), Json::String(a)) => t.contains(a.as_str()),
+                (Json::Array(items), a) => items.iter().any(|i| compare(i, a) == Ordering::Equal),
+                _ => false,
+            })
+        }
+        Func::Test(re) => Json::Bool(text.is_some_and(|t| re.is_match(t))),
+        Func::Has(arg) => {
+            let arg = eval(arg, input)?;
+            Json::Bool(match (input, &arg) {
+                (Json::Object(map), Json::String(k)) => map.contains_key(k),
+                (Json::Array(items), Json::Number(n)) => {
+                    n.as_u64().is_some_and(|i| (i as usize) < items.len())
+                }
+                _ => false,
+            })
+        }
+        Func::Length => match input {
+            Json::String(s) => s.chars().count().into(),
+            Json::Array(items) => items.len().into(),
+            Json::Object(map) => map.len().into(),
+            Json::Number(n) => n.as_f64().map_or(Json::Null, |f| f.abs().into()),
+            Json::Null => 0.into(),
+            Json::Bool(_) => Json::Null,
+        },
+        Func::AsciiDowncase => text.map_or(Json::Null, |t| t.to_ascii_lowercase().into()),
+        Func::AsciiUpcase => text.map_or(Json::Null, |t| t.to_ascii_uppercase().into()),
+        Func::ToString => match input {
+            Json::String(_) => input.clone(),
+            other => other.to_string().into(),
+        },
+        Func::ToNumber => match input {
+            Json::Number(_) => input.clone(),
+            Json::String(s) => s.trim().parse::<f64>().map_or(Json::Null, Json::from),
+            _ => Json::Null,
+        },
+    };
+    Some(value)
+}
+
+/// jq と同じ順序（`null` < `false` < `true` < 数値 < 文字列 < 配列 < オブジェクト）で比較します。
+///
+/// 数値は整数・浮動小数点数を区別せずに値で比較します。
+fn compare(a: &Json, b: &Json) -> Ordering {
+    fn rank(v: &Json) -> u8 {
+        match v {
+            Json::Null => 0,
+            Json::Bool(false) => 1,
+            Json::Bool(true) => 2,
+            Json::Number(_) => 3,
+            Json::String(_) => 4,
+            Json::Array(_) => 5,
+            Json::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Json::Number(x), Json::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Json::String(x), Json::String(y)) => x.cmp(y),
+        (Json::Array(x), Json::Array(y)) => {
+            for (i, j) in x.iter().zip(y) {
+                let ord = compare(i, j);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            x.len().cmp(&y.len())
+        }
+        (Json::Object(_), Json::Object(_)) => {
+            if a == b {
+                Ordering::Equal
+            } else {
+                a.to_string().cmp(&b.to_string())
+            }
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
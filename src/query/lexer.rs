@@ -0,0 +1,141 @@
+//! フィールドクエリの字句解析。
+
+use crate::filter::PatternError;
+use crate::filter::lexer::CmpOp;
+use crate::json_path::JsonPath;
+
+/// トークンの種類。
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// `.` で始まるパス（`.` 単体は入力そのもの）
+    Path(JsonPath),
+    /// 二重引用符で囲まれた文字列（引用符を除いた中身）
+    Str(String),
+    /// 数値
+    Number(f64),
+    /// 識別子（`and`・`or`・`true`・関数名など）
+    Ident(String),
+    /// 比較演算子（`==`・`!=`・`<`・`<=`・`>`・`>=`）
+    Op(CmpOp),
+    /// `|`
+    Pipe,
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+}
+
+/// 入力中の位置（バイトオフセット）付きトークン。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// トークンの種類
+    pub kind: TokenKind,
+    /// 開始位置（バイトオフセット）
+    pub start: usize,
+}
+
+/// クエリ文字列をトークン列に分割します。
+///
+/// # Errors
+///
+/// - 引用符が閉じられていない場合
+/// - パスが不正な場合
+/// - 解釈できない文字がある場合
+pub fn tokenize(input: &str) -> Result<Vec<Token>, PatternError> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = input[pos..].chars().next() {
+        if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        }
+        let start = pos;
+        let rest = &input[pos..];
+        let (kind, len) = match c {
+            '.' | '$' => {
+                let (path, after) =
+                    JsonPath::parse_prefix(rest).map_err(|e| PatternError::new(input, start, e))?;
+                (TokenKind::Path(path), rest.len() - after.len())
+            }
+            '"' => {
+                let (s, len) = string_literal(rest).ok_or_else(|| {
+                    PatternError::new(input, start, "閉じられていない引用符があります")
+                })?;
+                (TokenKind::Str(s), len)
+            }
+            '|' => (TokenKind::Pipe, 1),
+            '(' => (TokenKind::LParen, 1),
+            ')' => (TokenKind::RParen, 1),
+            '=' | '!' | '<' | '>' => {
+                let (op, len) = match (c, rest.as_bytes().get(1)) {
+                    ('=', Some(b'=')) => (CmpOp::Eq, 2),
+                    ('!', Some(b'=')) => (CmpOp::Ne, 2),
+                    ('<', Some(b'=')) => (CmpOp::Le, 2),
+                    ('>', Some(b'=')) => (CmpOp::Ge, 2),
+                    ('<', _) => (CmpOp::Lt, 1),
+                    ('>', _) => (CmpOp::Gt, 1),
+                    _ => {
+                        return Err(PatternError::new(
+                            input,
+                            start,
+                            "比較には `==` または `!=` を使用してください",
+                        ));
+                    }
+                };
+                (TokenKind::Op(op), len)
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let len = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '.'))
+                    .map_or(rest.len(), |(i, _)| i);
+                let n = rest[..len].parse::<f64>().map_err(|_| {
+                    PatternError::new(input, start, format!("数値が不正です: {}", &rest[..len]))
+                })?;
+                (TokenKind::Number(n), len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (TokenKind::Ident(rest[..len].to_string()), len)
+            }
+            _ => {
+                return Err(PatternError::new(
+                    input,
+                    start,
+                    format!("解釈できない文字です: {}", c),
+                ));
+            }
+        };
+        tokens.push(Token { kind, start });
+        pos += len;
+    }
+    Ok(tokens)
+}
+
+/// 先頭の `"` から文字列リテラルを読み取り、中身と消費したバイト数を返します。
+///
+/// `\"`・`\\`・`\n`・`\t` のエスケープに対応します。閉じられていない場合は `None` を返します。
+fn string_literal(input: &str) -> Option<(String, usize)> {
+    let mut out = String::new();
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        if escaped {
+            out.push(match c {
+                'n' => '\n',
+                't' => '\t',
+                c => c,
+            });
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            return Some((out, i + 1));
+        } else {
+            out.push(c);
+        }
+    }
+    None
+}
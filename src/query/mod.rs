@@ -0,0 +1,207 @@
+//! ロード済みイベントに対する jq 風のフィールドクエリ。
+//!
+//! JSON として解釈できるメッセージだけを対象に、フィールドの比較で絞り込み、
+//! 必要に応じて1つのフィールドを表示行として取り出します。
+//!
+//! 対応する構文:
+//!
+//! - パス: `.level`・`.http.status`・`.items[0]`・`.["key with space"]`・`.`（入力そのもの）
+//! - 比較: `==`・`!=`・`<`・`<=`・`>`・`>=`、論理演算: `and`・`or`、括弧
+//! - リテラル: 数値・`"文字列"`・`true`・`false`・`null`
+//! - `|` と関数: `select(cond)`・`not`・`startswith(s)`・`endswith(s)`・`contains(x)`・
+//!   `test("regex")`・`has(key)`・`length`・`ascii_downcase`・`ascii_upcase`・`tostring`・`tonumber`
+//!
+//! 結果が `null`・`false` の場合は除外、`true` の場合は元のメッセージを表示し、
+//! それ以外の値（`select(.status >= 500) | .message` など）はその値を表示行にします。
+
+pub mod eval;
+pub mod lexer;
+pub mod parser;
+
+use crate::filter::PatternError;
+use parser::Expr;
+
+/// クエリをメッセージに適用した結果。
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryResult {
+    /// 表示しない（JSON でない、または条件を満たさない）
+    Excluded,
+    /// 元のメッセージを表示する
+    Matched,
+    /// 取り出した値を表示行にする
+    Projected(String),
+}
+
+/// 解析済みのフィールドクエリ。
+#[derive(Debug, Clone)]
+pub struct Query {
+    expr: Expr,
+}
+
+impl Query {
+    /// クエリ文字列を解析します。
+    ///
+    /// # Errors
+    ///
+    /// - 空文字列の場合
+    /// - 構文が不正な場合（エラー位置を含む [`PatternError`] を返します）
+    pub fn parse(input: &str) -> Result<Self, PatternError> {
+        let tokens = lexer::tokenize(input)?;
+        let expr = parser::parse(input, &tokens)?;
+        Ok(Self { expr })
+    }
+
+    /// メッセージにクエリを適用します。
+    pub fn apply(&self, message: &str) -> QueryResult {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(message.trim()) else {
+            return QueryResult::Excluded;
+        };
+        match eval::eval(&self.expr, &json) {
+            None | Some(serde_json::Value::Null | serde_json::Value::Bool(false)) => {
+                QueryResult::Excluded
+            }
+            Some(serde_json::Value::Bool(true)) => QueryResult::Matched,
+            Some(serde_json::Value::String(s)) => QueryResult::Projected(s),
+            Some(other) => QueryResult::Projected(other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(query: &str, message: &str) -> QueryResult {
+        Query::parse(query)
+            .unwrap_or_else(|e| panic!("{}: {}", query, e))
+            .apply(message)
+    }
+
+    fn matches(query: &str, message: &str) -> bool {
+        apply(query, message) == QueryResult::Matched
+    }
+
+    fn error(query: &str) -> PatternError {
+        Query::parse(query).expect_err(query)
+    }
+
+    const REQUEST: &str =
+        r#"{"level":"error","http":{"status":503,"path":"/api/users"},"tags":["a","b"]}"#;
+
+    #[test]
+    fn example_from_the_request() {
+        let query = r#".http.status >= 500 and .http.path | startswith("/api")"#;
+        assert!(matches(query, REQUEST));
+        assert!(!matches(
+            query,
+            r#"{"http":{"status":200,"path":"/api/users"}}"#
+        ));
+        assert!(!matches(
+            query,
+            r#"{"http":{"status":503,"path":"/health"}}"#
+        ));
+    }
+
+    #[test]
+    fn pipe_binds_tighter_than_comparison() {
+        // `(.http.path | length) > 5`
+        assert!(matches(".http.path | length > 5", REQUEST));
+        assert!(!matches(".http.path | length > 50", REQUEST));
+        // `(.level | ascii_upcase) == "ERROR"`
+        assert!(matches(r#".level | ascii_upcase == "ERROR""#, REQUEST));
+        // `and` は比較より弱い
+        assert!(matches(
+            r#".http.status == 503 and .level == "error""#,
+            REQUEST
+        ));
+        assert!(matches(
+            r#".level == "info" or .http.status == 503 and .tags | contains("a")"#,
+            REQUEST
+        ));
+        assert!(!matches(
+            r#"(.level == "info" or .http.status == 503) and .tags | contains("z")"#,
+            REQUEST
+        ));
+    }
+
+    #[test]
+    fn comparisons_between_mixed_types() {
+        let message = r#"{"n":10,"f":10.0,"s":"10","b":true,"z":null}"#;
+        assert!(matches(".n == .f", message));
+        assert!(matches(".n != .s", message));
+        // null < false < true < 数値 < 文字列
+        assert!(matches(".z < .b", message));
+        assert!(matches(".b < .n", message));
+        assert!(matches(".n < .s", message));
+        assert!(matches(r#".s == "10""#, message));
+        assert!(matches(".s | tonumber == 10", message));
+        assert!(matches(r#".n | tostring == "10""#, message));
+        assert!(matches(".b == true and .z == null", message));
+    }
+
+    #[test]
+    fn missing_fields_are_null() {
+        assert!(matches(".missing == null", REQUEST));
+        assert!(!matches(".missing", REQUEST));
+        assert!(!matches(".http.missing.deeper > 0", REQUEST));
+        assert!(matches(".missing | not", REQUEST));
+        assert!(matches(".missing | length == 0", REQUEST));
+        assert!(matches(
+            r#"has("level") and (has("missing") | not)"#,
+            REQUEST
+        ));
+        assert_eq!(
+            apply(".missing | select(. != null)", REQUEST),
+            QueryResult::Excluded
+        );
+    }
+
+    #[test]
+    fn projection_and_select() {
+        assert_eq!(
+            apply("select(.http.status >= 500) | .http.path", REQUEST),
+            QueryResult::Projected("/api/users".to_string())
+        );
+        assert_eq!(
+            apply(".http.status", REQUEST),
+            QueryResult::Projected("503".to_string())
+        );
+        assert_eq!(
+            apply(".tags", REQUEST),
+            QueryResult::Projected(r#"["a","b"]"#.to_string())
+        );
+        assert_eq!(
+            apply("select(.http.status < 500) | .http.path", REQUEST),
+            QueryResult::Excluded
+        );
+        assert!(matches(r#".level | test("^err")"#, REQUEST));
+    }
+
+    #[test]
+    fn non_json_messages_are_excluded() {
+        assert_eq!(apply(".", "plain text"), QueryResult::Excluded);
+        assert_eq!(apply(".level == null", "{broken"), QueryResult::Excluded);
+    }
+
+    #[test]
+    fn parse_errors_report_positions() {
+        for query in ["", "   "] {
+            assert!(Query::parse(query).is_err());
+        }
+        let e = error(".a ==");
+        assert_eq!(e.column, 5);
+        let e = error(".a == 1 )");
+        assert_eq!(e.column, 8);
+        let e = error("(.a == 1");
+        assert_eq!(e.column, 8);
+        let e = error(".a | nosuch");
+        assert_eq!(e.column, 5);
+        assert!(e.message.contains("nosuch"));
+        let e = error(r#".a | test("(")"#);
+        assert_eq!(e.column, 5);
+        let e = error(".a | test(.b)");
+        assert_eq!(e.column, 5);
+        let e = error("select .a");
+        assert_eq!(e.column, 7);
+    }
+}
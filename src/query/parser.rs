@@ -0,0 +1,217 @@
+//! フィールドクエリの構文解析。
+//!
+//! 優先順位は低い順に `or`、`and`、比較、`|` です（jq と異なり `|` が比較より強く結合するため、
+//! `.http.status >= 500 and .path | startswith("/api")` と書けます）。
+
+use regex::Regex;
+
+use super::lexer::{Token, TokenKind};
+use crate::filter::PatternError;
+use crate::filter::lexer::CmpOp;
+use crate::json_path::JsonPath;
+
+/// クエリ式。
+#[derive(Debug, Clone)]
+pub enum Expr {
+    /// フィールドの参照（`.` は入力そのもの）
+    Path(JsonPath),
+    /// リテラル値
+    Literal(serde_json::Value),
+    /// `lhs | rhs`（`lhs` の結果を `rhs` の入力にする）
+    Pipe(Box<Expr>, Box<Expr>),
+    /// 比較
+    Compare(Box<Expr>, CmpOp, Box<Expr>),
+    /// `and`
+    And(Box<Expr>, Box<Expr>),
+    /// `or`
+    Or(Box<Expr>, Box<Expr>),
+    /// 組み込み関数の呼び出し
+    Call(Func),
+}
+
+/// 組み込み関数。
+#[derive(Debug, Clone)]
+pub enum Func {
+    /// `select(cond)`: 条件を満たす場合のみ入力をそのまま返す
+    Select(Box<Expr>),
+    /// `not`
+    Not,
+    /// `startswith(s)`
+    StartsWith(Box<Expr>),
+    /// `endswith(s)`
+    EndsWith(Box<Expr>),
+    /// `contains(x)`: 部分文字列、または配列の要素
+    Contains(Box<Expr>),
+    /// `test("regex")`
+    Test(Regex),
+    /// `has(key)`
+    Has(Box<Expr>),
+    /// `length`
+    Length,
+    /// `ascii_downcase`
+    AsciiDowncase,
+    /// `ascii_upcase`
+    AsciiUpcase,
+    /// `tostring`
+    ToString,
+    /// `tonumber`
+    ToNumber,
+}
+
+/// トークン列をクエリ式に変換します。
+///
+/// # Errors
+///
+/// - 構文が不正な場合（位置付きのエラーを返します）
+pub fn parse(input: &str, tokens: &[Token]) -> Result<Expr, PatternError> {
+    let mut p = Parser {
+        input,
+        tokens,
+        pos: 0,
+    };
+    let expr = p.or()?;
+    if let Some(t) = p.peek() {
+        return Err(p.error_at(t.start, "式の後ろに余分な文字があります"));
+    }
+    Ok(expr)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token { kind: TokenKind::Ident(i), .. }) if i == name)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> PatternError {
+        PatternError::new(self.input, pos, message)
+    }
+
+    fn error_here(&self, message: &str) -> PatternError {
+        let pos = self.peek().map_or(self.input.len(), |t| t.start);
+        self.error_at(pos, message)
+    }
+
+    fn expect(&mut self, kind: &TokenKind, message: &str) -> Result<(), PatternError> {
+        match self.peek() {
+            Some(t) if &t.kind == kind => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.error_here(message)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, PatternError> {
+        let mut lhs = self.and()?;
+        while self.peek_ident("or") {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, PatternError> {
+        let mut lhs = self.compare()?;
+        while self.peek_ident("and") {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.compare()?));
+        }
+        Ok(lhs)
+    }
+
+    fn compare(&mut self) -> Result<Expr, PatternError> {
+        let lhs = self.pipe()?;
+        if let Some(Token {
+            kind: TokenKind::Op(op),
+            ..
+        }) = self.peek()
+        {
+            let op = *op;
+            self.pos += 1;
+            let rhs = self.pipe()?;
+            return Ok(Expr::Compare(Box::new(lhs), op, Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn pipe(&mut self) -> Result<Expr, PatternError> {
+        let mut lhs = self.primary()?;
+        while matches!(self.peek().map(|t| &t.kind), Some(TokenKind::Pipe)) {
+            self.pos += 1;
+            lhs = Expr::Pipe(Box::new(lhs), Box::new(self.primary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn primary(&mut self) -> Result<Expr, PatternError> {
+        let Some(t) = self.peek().cloned() else {
+            return Err(self.error_here("式が必要です"));
+        };
+        self.pos += 1;
+        let expr = match t.kind {
+            TokenKind::Path(p) => Expr::Path(p),
+            TokenKind::Str(s) => Expr::Literal(s.into()),
+            TokenKind::Number(n) => Expr::Literal(n.into()),
+            TokenKind::LParen => {
+                let inner = self.or()?;
+                self.expect(&TokenKind::RParen, "`)` が閉じられていません")?;
+                inner
+            }
+            TokenKind::Ident(name) => match name.as_str() {
+                "true" => Expr::Literal(true.into()),
+                "false" => Expr::Literal(false.into()),
+                "null" => Expr::Literal(serde_json::Value::Null),
+                _ => Expr::Call(self.func(&name, t.start)?),
+            },
+            _ => return Err(self.error_at(t.start, "式が必要です")),
+        };
+        Ok(expr)
+    }
+
+    fn func(&mut self, name: &str, start: usize) -> Result<Func, PatternError> {
+        let func = match name {
+            "select" => Func::Select(self.arg()?),
+            "startswith" => Func::StartsWith(self.arg()?),
+            "endswith" => Func::EndsWith(self.arg()?),
+            "contains" => Func::Contains(self.arg()?),
+            "has" => Func::Has(self.arg()?),
+            "test" => {
+                let arg = self.arg()?;
+                let Expr::Literal(serde_json::Value::String(src)) = *arg else {
+                    return Err(self.error_at(start, "test の引数は文字列で指定してください"));
+                };
+                let re = Regex::new(&src).map_err(|e| {
+                    let detail = e.to_string();
+                    let last = detail.lines().last().unwrap_or("").trim().to_string();
+                    self.error_at(start, &format!("正規表現が不正です: {}", last))
+                })?;
+                Func::Test(re)
+            }
+            "not" => Func::Not,
+            "length" => Func::Length,
+            "ascii_downcase" => Func::AsciiDowncase,
+            "ascii_upcase" => Func::AsciiUpcase,
+            "tostring" => Func::ToString,
+            "tonumber" => Func::ToNumber,
+            _ => return Err(self.error_at(start, &format!("未対応の関数です: {}", name))),
+        };
+        Ok(func)
+    }
+
+    /// `(expr)` 形式の引数を1つ読み取ります。
+    fn arg(&mut self) -> Result<Box<Expr>, PatternError> {
+        self.expect(&TokenKind::LParen, "`(` で引数を指定してください")?;
+        let expr = self.or()?;
+        self.expect(&TokenKind::RParen, "`)` が閉じられていません")?;
+        Ok(Box::new(expr))
+    }
+}
@@ -0,0 +1,272 @@
+//! ログメッセージのマスキング（リダクション）規則。
+//!
+//! 設定ディレクトリの `redaction.json` に、正規表現または JSON パスと置き換え文字列の組を記述します。
+//!
+//! ```json
+//! {
+//!   "rules": [
+//!     { "regex": "[\\w.+-]+@[\\w-]+\\.[\\w.]+", "replacement": "<email>" },
+//!     { "path": "user.token" }
+//!   ]
+//! }
+//! ```
+//!
+//! 規則はイベントの取得時（[`LogEvent::new`]）に適用するため、一覧・ビューア・コピー・
+//! すべての書き出し先でマスク済みのメッセージだけを扱います。
+//! 規則がプロセス全体で 1 つだけ保持される点は表示タイムゾーン（[`crate::time`]）と同じです。
+//!
+//! [`LogEvent::new`]: crate::app::LogEvent::new
+
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use serde_json::Value;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::config;
+use crate::json_path::JsonPath;
+
+/// マスキング規則を保存するファイル名
+pub const REDACTION_FILE: &str = "redaction.json";
+
+/// `replacement` を省略した場合の置き換え文字列
+const DEFAULT_REPLACEMENT: &str = "***";
+
+/// マスクする対象。
+enum Target {
+    /// メッセージ中の正規表現に一致する部分（置き換え文字列で `$1` などのグループを参照可能）
+    Regex(Regex),
+    /// JSON メッセージのパスが指す値
+    Path(JsonPath),
+}
+
+/// 1 つのマスキング規則。
+struct Rule {
+    target: Target,
+    replacement: String,
+}
+
+static RULES: OnceLock<Vec<Rule>> = OnceLock::new();
+
+/// 設定ファイル `redaction.json` からマスキング規則を読み込みます。
+///
+/// ファイルが無い場合は規則なし（マスキングしない）として扱います。
+///
+/// # Errors
+///
+/// - ファイルの読み込み・JSON の解釈に失敗した場合
+/// - 規則の正規表現・JSON パスが不正な場合
+pub fn init() -> Result<()> {
+    let rules = match config::load_json(REDACTION_FILE)? {
+        Some(value) => parse_rules(&value).context(REDACTION_FILE)?,
+        None => Vec::new(),
+    };
+    let _ = RULES.set(rules);
+    Ok(())
+}
+
+fn parse_rules(value: &Value) -> Result<Vec<Rule>> {
+    let rules = value
+        .get("rules")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("`rules` must be an array"))?;
+    rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| parse_rule(rule).with_context(|| format!("rule {}", i + 1)))
+        .collect()
+}
+
+fn parse_rule(value: &Value) -> Result<Rule> {
+    let field = |key: &str| value.get(key).and_then(Value::as_str);
+    let target = match (field("regex"), field("path")) {
+        (Some(pattern), None) => Target::Regex(
+            Regex::new(pattern).with_context(|| format!("invalid regex: {}", pattern))?,
+        ),
+        (None, Some(path)) => Target::Path(
+            JsonPath::parse(path).map_err(|e| anyhow!("invalid path `{}`: {}", path, e))?,
+        ),
+        (Some(_), Some(_)) => return Err(anyhow!("specify either `regex` or `path`, not both")),
+        (None, None) => return Err(anyhow!("`regex` or `path` is missing")),
+    };
+    Ok(Rule {
+        target,
+        replacement: field("replacement")
+            .unwrap_or(DEFAULT_REPLACEMENT)
+            .to_string(),
+    })
+}
+
+fn rules() -> &'static [Rule] {
+    RULES.get().map_or(&[], Vec::as_slice)
+}
+
+/// 画面ヘッダーに付けるマスキング中の表示を返します。規則が無い場合は空文字列です。
+pub fn indicator() -> String {
+    match rules().len() {
+        0 => String::new(),
+        1 => "  │  masking active (1 rule)".to_string(),
+        n => format!("  │  masking active ({} rules)", n),
+    }
+}
+
+/// メッセージにマスキング規則を適用します。
+///
+/// JSON パスの規則はメッセージ全体が JSON の場合だけ適用し、パスの指す値の部分だけを
+/// 置き換え文字列（JSON の文字列）に書き換えます（同じキーが複数回現れる場合は全て）。その後、正規表現の規則を順に適用します。
+pub fn apply(message: String) -> String {
+    apply_rules(rules(), message)
+}
+
+fn apply_rules(rules: &[Rule], message: String) -> String {
+    if rules.is_empty() {
+        return message;
+    }
+    let mut message = redact_paths(rules, message);
+    for rule in rules {
+        if let Target::Regex(re) = &rule.target
+            && re.is_match(&message)
+        {
+            message = re
+                .replace_all(&message, rule.replacement.as_str())
+                .into_owned();
+        }
+    }
+    message
+}
+
+fn redact_paths(rules: &[Rule], message: String) -> String {
+    let mut paths = rules.iter().filter_map(|rule| match &rule.target {
+        Target::Path(path) => Some((path, &rule.replacement)),
+        Target::Regex(_) => None,
+    });
+    let Some(first) = paths.next() else {
+        return message;
+    };
+    if !message.trim_start().starts_with(['{', '[']) {
+        return message;
+    }
+    // 値を置き換える範囲。外側の値を置き換えると内側の値は残らないため、重なる範囲は外側だけを残す
+    let mut spans: Vec<(Range<usize>, &str)> = Vec::new();
+    for (path, replacement) in std::iter::once(first).chain(paths) {
+        for span in path.spans(&message) {
+            let inside = |outer: &Range<usize>| outer.start <= span.start && span.end <= outer.end;
+            if let Some(same) = spans.iter_mut().find(|(s, _)| *s == span) {
+                same.1 = replacement;
+            } else if !spans.iter().any(|(s, _)| inside(s)) {
+                spans.retain(|(s, _)| !(span.start <= s.start && s.end <= span.end));
+                spans.push((span, replacement));
+            }
+        }
+    }
+    if spans.is_empty() {
+        return message;
+    }
+    // 値の部分だけを置き換え、キーの順序や空白は元のメッセージのまま残す
+    spans.sort_by_key(|(s, _)| s.start);
+    let mut out = String::with_capacity(message.len());
+    let mut last = 0;
+    for (span, replacement) in spans {
+        out.push_str(&message[last..span.start]);
+        out.push_str(&Value::from(replacement).to_string());
+        last = span.end;
+    }
+    out.push_str(&message[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redact(rules: Value, message: &str) -> String {
+        let rules = parse_rules(&json!({ "rules": rules })).unwrap();
+        apply_rules(&rules, message.to_string())
+    }
+
+    #[test]
+    fn nested_path_and_array_index() {
+        let rules =
+            json!([{ "path": "user.token" }, { "path": "items[1].id", "replacement": "<id>" }]);
+        assert_eq!(
+            redact(
+                rules,
+                r#"{"user":{"token":"abc","name":"x"},"items":[{"id":1},{"id":2}]}"#
+            ),
+            r#"{"user":{"token":"***","name":"x"},"items":[{"id":1},{"id":"<id>"}]}"#
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_all_redacted() {
+        let rules = json!([{ "path": "token" }, { "path": "a.b" }]);
+        assert_eq!(
+            redact(
+                rules,
+                r#"{"token":"first","a":{"b":1},"token":"second","a":{"b":2}}"#
+            ),
+            r#"{"token":"***","a":{"b":"***"},"token":"***","a":{"b":"***"}}"#
+        );
+    }
+
+    #[test]
+    fn non_json_messages_are_left_to_regex_rules() {
+        let rules = json!([{ "path": "token" }]);
+        for message in ["token=abc", "{not json", "[1, 2"] {
+            assert_eq!(redact(rules.clone(), message), message);
+        }
+    }
+
+    #[test]
+    fn key_order_and_whitespace_are_kept() {
+        let rules = json!([{ "path": "password" }]);
+        let message = "{ \"z\": 1,\n  \"password\" :  \"hunter2\",  \"a\": [ 1, 2 ] }";
+        assert_eq!(
+            redact(rules, message),
+            "{ \"z\": 1,\n  \"password\" :  \"***\",  \"a\": [ 1, 2 ] }"
+        );
+    }
+
+    #[test]
+    fn outer_path_wins_over_nested_path() {
+        let rules = json!([{ "path": "user.token" }, { "path": "user", "replacement": "<user>" }]);
+        assert_eq!(
+            redact(rules, r#"{"user":{"token":"abc"},"n":1}"#),
+            r#"{"user":"<user>","n":1}"#
+        );
+    }
+
+    #[test]
+    fn regex_and_path_rules_together() {
+        let rules = json!([
+            { "regex": "[\\w.+-]+@[\\w-]+\\.[\\w.]+", "replacement": "<email>" },
+            { "path": "token" },
+            { "regex": "id=(\\d+)", "replacement": "id=<$1>" }
+        ]);
+        assert_eq!(
+            redact(
+                rules.clone(),
+                r#"{"token":"a@b.com","mail":"c@d.org","msg":"id=42"}"#
+            ),
+            r#"{"token":"***","mail":"<email>","msg":"id=<42>"}"#
+        );
+        assert_eq!(
+            redact(rules, "sent to c@d.org id=7"),
+            "sent to <email> id=<7>"
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for rules in [
+            json!([{}]),
+            json!([{ "regex": "(" }]),
+            json!([{ "path": "a[" }]),
+            json!([{ "regex": "a", "path": "b" }]),
+        ] {
+            assert!(parse_rules(&json!({ "rules": rules })).is_err());
+        }
+        assert!(parse_rules(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! メインスクリーンのロググループアーカイブ入力状態。
+//!
+//! `a` で書き出し先ディレクトリと時間範囲を入力し、選択中のロググループの
+//! ログストリームごとのアーカイブ（[`ArchiveJob`]）をバックグラウンドで実行します。
+//!
+//! [`ArchiveJob`]: crate::archive::ArchiveJob
+
+use aws_sdk_cloudwatchlogs::Client;
+use crossterm::event::KeyCode;
+use std::sync::Arc;
+
+use crate::archive::ArchiveJob;
+use crate::paths;
+use crate::time;
+
+/// 書き出し先ディレクトリの既定値
+const DEFAULT_DIR: &str = "archive";
+/// 開始時刻の既定値
+const DEFAULT_START: &str = "1d ago";
+/// 終了時刻の既定値
+const DEFAULT_END: &str = "now";
+
+/// 入力中のフィールド。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveField {
+    /// 書き出し先ディレクトリ
+    Dir,
+    /// 開始時刻
+    Start,
+    /// 終了時刻
+    End,
+}
+
+/// ロググループアーカイブの入力状態。
+pub struct ArchivePrompt {
+    /// 入力モードがアクティブかどうか
+    pub editing: bool,
+    /// アーカイブするロググループ名
+    pub group: String,
+    /// 書き出し先ディレクトリ（この下に `<group>/` を作成、`~`・`$VAR` は展開）
+    pub dir: String,
+    /// 開始時刻（絶対時刻・相対時刻、空欄の場合は無制限）
+    pub start: String,
+    /// 終了時刻（絶対時刻・相対時刻、空欄の場合は無制限）
+    pub end: String,
+    /// 入力中のフィールド
+    pub focused: ArchiveField,
+    /// 入力エラー・開始エラーのメッセージ
+    pub error: Option<String>,
+    /// 実行中または終了直後のアーカイブ
+    pub job: Option<ArchiveJob>,
+}
+
+impl ArchivePrompt {
+    /// 入力前の [`ArchivePrompt`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            editing: false,
+            group: String::new(),
+            dir: DEFAULT_DIR.to_string(),
+            start: DEFAULT_START.to_string(),
+            end: DEFAULT_END.to_string(),
+            focused: ArchiveField::Dir,
+            error: None,
+            job: None,
+        }
+    }
+
+    /// `group` のアーカイブの入力を開始します。前回の入力内容は引き継ぎます。
+    pub fn start(&mut self, group: String) {
+        self.group = group;
+        self.focused = ArchiveField::Dir;
+        self.error = None;
+        self.editing = true;
+    }
+
+    /// 入力モード中のキーを処理します。`Enter` で確定した場合は `true` を返します。
+    ///
+    /// `Tab`/`BackTab` でフィールドを移動します。
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Enter => return true,
+            KeyCode::Esc => {
+                self.editing = false;
+                self.error = None;
+            }
+            KeyCode::Tab => {
+                self.focused = match self.focused {
+                    ArchiveField::Dir => ArchiveField::Start,
+                    ArchiveField::Start => ArchiveField::End,
+                    ArchiveField::End => ArchiveField::Dir,
+                };
+            }
+            KeyCode::BackTab => {
+                self.focused = match self.focused {
+                    ArchiveField::Dir => ArchiveField::End,
+                    ArchiveField::Start => ArchiveField::Dir,
+                    ArchiveField::End => ArchiveField::Start,
+                };
+            }
+            KeyCode::Backspace => {
+                self.field_mut().pop();
+            }
+            KeyCode::Char(c) => self.field_mut().push(c),
+            _ => {}
+        }
+        false
+    }
+
+    fn field_mut(&mut self) -> &mut String {
+        match self.focused {
+            ArchiveField::Dir => &mut self.dir,
+            ArchiveField::Start => &mut self.start,
+            ArchiveField::End => &mut self.end,
+        }
+    }
+
+    /// 入力内容を検証し、アーカイブをバックグラウンドで開始します。
+    ///
+    /// 時刻を解釈できない場合や既に実行中の場合は、入力モードのままエラーを設定します。
+    pub fn spawn(&mut self, client: Arc<Client>) {
+        if self.job.as_ref().is_some_and(ArchiveJob::is_running) {
+            self.error = Some("an archive is already running (x to cancel)".to_string());
+            return;
+        }
+        let dir = match paths::expand(self.dir.trim()) {
+            Ok(dir) if dir.is_empty() => {
+                self.error = Some("enter an output directory".to_string());
+                return;
+            }
+            Ok(dir) => dir,
+            Err(e) => {
+                self.focused = ArchiveField::Dir;
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        let parse = |s: &str| {
+            if s.trim().is_empty() {
+                Ok(None)
+            } else {
+                time::parse_time_spec_to_ms(s).map(Some)
+            }
+        };
+        let (start_ms, end_ms) = match (parse(&self.start), parse(&self.end)) {
+            (Ok(start), Ok(end)) => (start, end),
+            (Err(e), _) => {
+                self.focused = ArchiveField::Start;
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+            (_, Err(e)) => {
+                self.focused = ArchiveField::End;
+                self.error = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        if let (Some(start), Some(end)) = (start_ms, end_ms)
+            && start > end
+        {
+            self.error = Some("the start is after the end".to_string());
+            return;
+        }
+        self.editing = false;
+        self.error = None;
+        self.job = Some(ArchiveJob::spawn(
+            client,
+            self.group.clone(),
+            &dir,
+            start_ms,
+            end_ms,
+        ));
+    }
+
+    /// 終了済みのアーカイブの結果表示を消去します。画面のキー処理の最初に呼び出します。
+    pub fn clear_finished(&mut self) {
+        if self.job.as_ref().is_some_and(|job| !job.is_running()) {
+            self.job = None;
+        }
+    }
+
+    /// 実行中のアーカイブを中断します。
+    pub fn cancel(&self) {
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+    }
+}
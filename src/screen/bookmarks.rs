@@ -0,0 +1,187 @@
+//! ブックマーク一覧スクリーンの状態管理。
+//!
+//! ロググループ・ログストリームをまたいでブックマークしたイベントを一覧表示し、
+//! 詳細表示・削除・書き出しをサポートします。
+//!
+//! 書き出しパスはダウンロードと同じく `Tab` で補完でき、確定時に `~`・`$VAR` を展開します。
+//! 親ディレクトリが無い場合は作成するか、書き出し先が既にある場合は上書き・別名のいずれにするかを確認します。
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::download_prompt::{self, Confirm};
+use super::motion::{Keystroke, MotionKeys};
+use super::{CurrentScreen, NavigateTo, ScreenAction};
+use crate::app::StatefulList;
+use crate::bookmarks::{self, Bookmark};
+use crate::paths;
+use crate::time;
+
+/// ブックマーク一覧を表示するスクリーン。
+///
+/// `j`/`k` でリスト移動（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`Enter` で詳細表示、`x` で削除、`e` で書き出しパス入力、
+/// `z` で表示タイムゾーン切替、`q` で前の画面に戻ります。
+pub struct BookmarksScreen {
+    /// ブックマークのリスト状態
+    pub bookmarks: StatefulList<Bookmark>,
+    /// 書き出しパス入力モードがアクティブかどうか
+    pub export_editing: bool,
+    /// 書き出しパス入力バッファ
+    pub export_path_buffer: String,
+    /// 書き出し先の確定時の確認中の事項
+    pub export_confirm: Option<Confirm>,
+    /// `Tab` で補完した際の候補（候補が複数の場合）
+    pub export_candidates: Vec<String>,
+    /// パスの展開・ディレクトリ作成のエラー
+    pub export_error: Option<String>,
+    /// 書き出し結果メッセージ（成功またはエラー）
+    pub export_status: Option<String>,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// 前の画面（`q` で戻るため保持）
+    pub origin: Option<Box<CurrentScreen>>,
+}
+
+impl BookmarksScreen {
+    /// 現在のブックマークを読み込んで [`BookmarksScreen`] を生成します。
+    pub fn new(origin: Box<CurrentScreen>) -> Self {
+        let mut s = Self {
+            bookmarks: StatefulList::new(),
+            export_editing: false,
+            export_path_buffer: String::new(),
+            export_confirm: None,
+            export_candidates: Vec::new(),
+            export_error: None,
+            export_status: None,
+            keys: MotionKeys::new(),
+            origin: Some(origin),
+        };
+        s.reload();
+        s
+    }
+
+    /// キー入力を処理して [`ScreenAction`] を返します。
+    ///
+    /// 書き出しパス入力モード中は入力をパスとして処理します。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        self.export_status = None;
+        if self.export_editing {
+            self.handle_export_key(code);
+            return Ok(ScreenAction::None);
+        }
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                self.bookmarks.apply_motion(motion);
+                return Ok(ScreenAction::None);
+            }
+            Keystroke::Pending => return Ok(ScreenAction::None),
+            Keystroke::Unhandled => {}
+        }
+        match code {
+            KeyCode::Char('q') => {
+                if let Some(origin) = self.origin.take() {
+                    return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(i) = self.bookmarks.state.selected() {
+                    bookmarks::remove(i);
+                    self.reload();
+                }
+            }
+            KeyCode::Char('e') => {
+                self.export_path_buffer = format!("bookmarks-{}.md", time::now().date());
+                self.export_confirm = None;
+                self.export_candidates.clear();
+                self.export_error = None;
+                self.export_editing = true;
+            }
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            KeyCode::Enter => {
+                if let Some(b) = self.bookmarks.selected() {
+                    let event = b.event.clone();
+                    return Ok(ScreenAction::Navigate(NavigateTo::NewViewer { event }));
+                }
+            }
+            _ => {}
+        }
+        Ok(ScreenAction::None)
+    }
+
+    /// 書き出しパス入力モード中のキーを処理します。
+    ///
+    /// `Tab` でパスを補完し、`Enter` でパスを展開して親ディレクトリと既存のファイルを確認します。
+    fn handle_export_key(&mut self, code: KeyCode) {
+        if let Some(confirm) = self.export_confirm.take() {
+            self.handle_export_confirm(confirm, code);
+            return;
+        }
+        self.export_candidates.clear();
+        self.export_error = None;
+        match code {
+            KeyCode::Enter => match download_prompt::expand_input(&self.export_path_buffer) {
+                Ok(path) => self.confirm_or_export(path),
+                Err(e) => self.export_error = Some(e),
+            },
+            KeyCode::Esc => {
+                self.export_editing = false;
+                self.export_path_buffer.clear();
+            }
+            KeyCode::Tab => {
+                let completion = paths::complete(&self.export_path_buffer);
+                self.export_path_buffer = completion.input;
+                self.export_candidates = completion.candidates;
+            }
+            KeyCode::Backspace => {
+                self.export_path_buffer.pop();
+            }
+            KeyCode::Char(c) => self.export_path_buffer.push(c),
+            _ => {}
+        }
+    }
+
+    /// 確認中のキーを処理します。`Esc` で入力に戻ります。
+    fn handle_export_confirm(&mut self, confirm: Confirm, code: KeyCode) {
+        match (confirm, code) {
+            (Confirm::CreateDir { dir, path }, KeyCode::Char('y') | KeyCode::Enter) => {
+                match std::fs::create_dir_all(&dir) {
+                    Ok(()) => self.confirm_or_export(path),
+                    Err(e) => self.export_error = Some(format!("Failed to create {}: {}", dir, e)),
+                }
+            }
+            (Confirm::Exists(path), KeyCode::Char('o')) => self.export(path),
+            (Confirm::Exists(path), KeyCode::Char('r')) => self.export(paths::unique_path(&path)),
+            (_, KeyCode::Esc | KeyCode::Char('n')) => {}
+            (confirm, _) => self.export_confirm = Some(confirm),
+        }
+    }
+
+    fn confirm_or_export(&mut self, path: String) {
+        match Confirm::needed(&path) {
+            Some(confirm) => self.export_confirm = Some(confirm),
+            None => self.export(path),
+        }
+    }
+
+    /// 展開済みの `path` にブックマークを書き出し、入力モードを終了します。
+    fn export(&mut self, path: String) {
+        self.export_status = Some(match bookmarks::export(&path) {
+            Ok(n) => format!("Saved {} bookmarks: {}", n, path),
+            Err(e) => format!("Error: {:#}", e),
+        });
+        self.export_editing = false;
+    }
+
+    /// ブックマークを読み直し、選択位置を範囲内に収めます。
+    fn reload(&mut self) {
+        self.bookmarks.items = bookmarks::all();
+        let len = self.bookmarks.items.len();
+        let selected = match self.bookmarks.state.selected() {
+            _ if len == 0 => None,
+            Some(i) => Some(i.min(len - 1)),
+            None => Some(0),
+        };
+        self.bookmarks.state.select(selected);
+    }
+}
@@ -0,0 +1,76 @@
+//! JSON フィールド列の編集状態。
+//!
+//! `c` で列指定の入力モードに入り、確定した列定義をロググループごとに保存します。
+
+use crossterm::event::KeyCode;
+
+use crate::columns::{self, Column};
+
+/// イベント一覧の JSON フィールド列と、その入力状態。
+pub struct ColumnEditor {
+    /// 列定義を保存するキー（ロググループ名）
+    group: String,
+    /// 現在表示中の列（空の場合はメッセージのみ表示）
+    pub columns: Vec<Column>,
+    /// 列指定の入力モードがアクティブかどうか
+    pub editing: bool,
+    /// 列指定の入力バッファ（`path[:width]` のカンマ区切り）
+    pub buffer: String,
+    /// 列指定の解釈エラー、または保存エラーのメッセージ
+    pub error: Option<String>,
+}
+
+impl ColumnEditor {
+    /// ロググループに保存済みの列定義を読み込んで [`ColumnEditor`] を生成します。
+    pub fn new(group: &str) -> Self {
+        Self {
+            group: group.to_string(),
+            columns: columns::load(group),
+            editing: false,
+            buffer: String::new(),
+            error: None,
+        }
+    }
+
+    /// 現在の列定義を入力バッファに展開して入力モードを開始します。
+    pub fn start(&mut self) {
+        self.editing = true;
+        self.buffer = columns::to_spec(&self.columns);
+        self.error = None;
+    }
+
+    /// 入力モード中のキーを処理します。
+    ///
+    /// `Enter` で列定義を適用して保存、`Esc` で変更を破棄します。
+    /// 解釈できない指定の場合は入力モードのままエラーを表示します。
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => match columns::parse_spec(&self.buffer) {
+                Ok(parsed) => {
+                    self.columns = parsed;
+                    match columns::save(&self.group, &self.columns) {
+                        Ok(()) => {
+                            self.editing = false;
+                            self.error = None;
+                        }
+                        Err(e) => self.error = Some(format!("保存に失敗しました: {:#}", e)),
+                    }
+                }
+                Err(e) => self.error = Some(e),
+            },
+            KeyCode::Esc => {
+                self.editing = false;
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) => {
+                self.buffer.push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+    }
+}
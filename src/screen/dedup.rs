@@ -0,0 +1,190 @@
+//! 連続する同一メッセージの折りたたみ。
+//!
+//! 数値・UUID・タイムスタンプなどを正規化した上で同じになる連続したイベントを 1 行にまとめ、
+//! 件数と最初・最後のタイムスタンプを表示します。まとめた行は個別のイベントに展開できます。
+//!
+//! 正規化したメッセージはイベントごとに一度だけ計算して保持します。イベントを置き換えた
+//! （読み込み直した）場合は [`Dedup::reset`] で保持した内容と展開状態を破棄します。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::app::LogEvent;
+use crate::normalize;
+
+/// 重複折りたたみモードの状態。
+pub struct Dedup {
+    /// 折りたたみモードが有効かどうか
+    pub enabled: bool,
+    /// 連続する同一メッセージの塊（`items` 内インデックスの列）
+    runs: Vec<Vec<usize>>,
+    /// `items` 内インデックスから所属する塊（`runs` のインデックス）への対応
+    run_of: HashMap<usize, usize>,
+    /// 展開中の塊（先頭イベントの `items` 内インデックス）
+    expanded: HashSet<usize>,
+    /// `items` の先頭から順に、正規化したメッセージ
+    keys: Vec<String>,
+}
+
+impl Dedup {
+    /// 無効状態の [`Dedup`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            runs: Vec::new(),
+            run_of: HashMap::new(),
+            expanded: HashSet::new(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// イベントを置き換えた場合に、正規化したメッセージと展開状態を破棄します。
+    pub fn reset(&mut self) {
+        self.runs.clear();
+        self.run_of.clear();
+        self.expanded.clear();
+        self.keys.clear();
+    }
+
+    /// 折りたたみモードを切り替えます。無効にすると展開状態もクリアします。
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        if !self.enabled {
+            self.runs.clear();
+            self.run_of.clear();
+            self.expanded.clear();
+        }
+    }
+
+    /// `i` 番目のイベントを含む塊の展開・折りたたみを切り替えます。
+    ///
+    /// 1 件だけの塊の場合は何もせず `false` を返します。
+    pub fn toggle_expand(&mut self, i: usize) -> bool {
+        let Some(run) = self.run_of.get(&i).map(|&r| &self.runs[r]) else {
+            return false;
+        };
+        if run.len() < 2 {
+            return false;
+        }
+        let first = run[0];
+        if !self.expanded.remove(&first) {
+            self.expanded.insert(first);
+        }
+        true
+    }
+
+    /// 折りたたまれた塊に含まれるイベントの場合は、塊の先頭イベントのインデックスを返します。
+    pub fn representative(&self, i: usize) -> usize {
+        match self.run_of.get(&i).map(|&r| self.runs[r][0]) {
+            Some(first) if self.enabled && !self.expanded.contains(&first) => first,
+            _ => i,
+        }
+    }
+
+    /// 表示対象のイベント（`items` 内インデックスの昇順）を塊にまとめ、
+    /// 表示するインデックスを返します。折りたたまれた塊は先頭イベントのみを表示します。
+    ///
+    /// 追加ロードで増えたイベントだけを正規化します。絞り込みが変わって塊の先頭でなくなった
+    /// イベントの展開状態は破棄します。
+    pub fn collapse(
+        &mut self,
+        items: &[LogEvent],
+        indices: impl Iterator<Item = usize>,
+    ) -> Vec<usize> {
+        self.runs.clear();
+        self.run_of.clear();
+        if self.keys.len() > items.len() {
+            self.reset();
+        }
+        let start = self.keys.len();
+        self.keys.extend(
+            items[start..]
+                .iter()
+                .map(|e| normalize::normalize(&e.message)),
+        );
+        let mut last_key: Option<&str> = None;
+        for i in indices {
+            let key = self.keys[i].as_str();
+            if last_key != Some(key) {
+                self.runs.push(Vec::new());
+            }
+            let r = self.runs.len() - 1;
+            self.runs[r].push(i);
+            self.run_of.insert(i, r);
+            last_key = Some(key);
+        }
+        let runs = &self.runs;
+        let run_of = &self.run_of;
+        self.expanded
+            .retain(|first| run_of.get(first).is_some_and(|&r| runs[r][0] == *first));
+        self.runs
+            .iter()
+            .flat_map(|run| {
+                if self.expanded.contains(&run[0]) {
+                    run.as_slice()
+                } else {
+                    &run[..1]
+                }
+            })
+            .copied()
+            .collect()
+    }
+
+    /// `i` 番目のイベントが 2 件以上の塊の先頭の場合、塊のイベントと展開中かどうかを返します。
+    pub fn run(&self, i: usize) -> Option<(&[usize], bool)> {
+        let run = &self.runs[*self.run_of.get(&i)?];
+        (run.len() > 1 && run[0] == i).then(|| (run.as_slice(), self.expanded.contains(&i)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(messages: &[&str]) -> Vec<LogEvent> {
+        messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| LogEvent::new(i as i64, m.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn collapses_runs_and_keeps_expansion_across_appended_pages() {
+        let mut dedup = Dedup::new();
+        dedup.toggle();
+        let mut items = events(&["retry 1", "retry 2", "done"]);
+        assert_eq!(dedup.collapse(&items, 0..items.len()), vec![0, 2]);
+        assert!(dedup.toggle_expand(1));
+        assert_eq!(dedup.collapse(&items, 0..items.len()), vec![0, 1, 2]);
+
+        items.extend(events(&["done", "other"]));
+        assert_eq!(dedup.collapse(&items, 0..items.len()), vec![0, 1, 2, 4]);
+        assert_eq!(dedup.run(2).map(|(run, _)| run.to_vec()), Some(vec![2, 3]));
+    }
+
+    #[test]
+    fn expansion_is_dropped_when_the_run_changes() {
+        let mut dedup = Dedup::new();
+        dedup.toggle();
+        let items = events(&["a", "b 1", "b 2", "c"]);
+        dedup.collapse(&items, 0..items.len());
+        assert!(dedup.toggle_expand(2));
+        // 絞り込みで `b 1` が外れても、`b 2` から始まる塊は展開しない
+        assert_eq!(dedup.collapse(&items, [0, 2, 3].into_iter()), vec![0, 2, 3]);
+        assert_eq!(dedup.run(2), None);
+        assert_eq!(dedup.collapse(&items, 0..items.len()), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn reset_forgets_keys_and_expansion() {
+        let mut dedup = Dedup::new();
+        dedup.toggle();
+        let items = events(&["x 1", "x 2"]);
+        dedup.collapse(&items, 0..items.len());
+        assert!(dedup.toggle_expand(0));
+
+        let items = events(&["y 1", "y 2", "z"]);
+        dedup.reset();
+        assert_eq!(dedup.collapse(&items, 0..items.len()), vec![0, 2]);
+    }
+}
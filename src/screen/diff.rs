@@ -0,0 +1,114 @@
+//! イベント差分スクリーンの状態管理。
+//!
+//! 2 つのログイベントのメッセージを左右に並べ、差分を色分けして表示します。
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::motion::{Keystroke, MotionKeys};
+use super::{CurrentScreen, NavigateTo, ScreenAction};
+use crate::app::LogEvent;
+use crate::diff::{self, Change, Row};
+use crate::time;
+
+/// 2 つのログイベントの差分を左右に並べて表示するスクリーン。
+///
+/// `j`/`k` でスクロール（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`n`/`N` で次/前の差分へ移動、`z` で表示タイムゾーン切替、
+/// `q` で前の画面に戻ります。
+pub struct DiffScreen {
+    /// 左側（比較元）のログイベント
+    pub left: LogEvent,
+    /// 右側（比較先）のログイベント
+    pub right: LogEvent,
+    /// 差分の各行
+    pub rows: Vec<Row>,
+    /// JSON の構造的な差分かどうか（`false` は行単位の差分）
+    pub structural: bool,
+    /// 現在のスクロールオフセット（行数）
+    pub scroll: u16,
+    /// 差分の表示領域の行数（描画時に更新）
+    pub page_size: usize,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// 前の画面（`q` で戻るため保持）
+    pub origin: Option<Box<CurrentScreen>>,
+}
+
+impl DiffScreen {
+    /// 2 つのログイベントの差分を計算して [`DiffScreen`] を生成します。
+    pub fn new(left: LogEvent, right: LogEvent, origin: Box<CurrentScreen>) -> Self {
+        let (rows, structural) = diff::diff(&left.message, &right.message);
+        let mut s = Self {
+            left,
+            right,
+            rows,
+            structural,
+            scroll: 0,
+            page_size: 1,
+            keys: MotionKeys::new(),
+            origin: Some(origin),
+        };
+        // 最初の差分が見えるようにする
+        let first = s.change_starts().next();
+        if let Some(i) = first {
+            s.scroll = i.min(u16::MAX as usize) as u16;
+        }
+        s
+    }
+
+    /// 差分のある行数を返します。
+    pub fn change_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|r| r.change != Change::Same)
+            .count()
+    }
+
+    /// キー入力を処理して [`ScreenAction`] を返します。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                let positions = self.rows.len().saturating_sub(self.page_size) + 1;
+                let pos = motion.apply(self.scroll as usize, positions, self.page_size);
+                self.scroll = pos.min(u16::MAX as usize) as u16;
+                return Ok(ScreenAction::None);
+            }
+            Keystroke::Pending => return Ok(ScreenAction::None),
+            Keystroke::Unhandled => {}
+        }
+        match code {
+            KeyCode::Char('q') => {
+                if let Some(origin) = self.origin.take() {
+                    return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
+                }
+            }
+            KeyCode::Char('n') => self.jump_change(true),
+            KeyCode::Char('N') => self.jump_change(false),
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            _ => {}
+        }
+        Ok(ScreenAction::None)
+    }
+
+    /// 次（`forward` が `false` の場合は前）の差分の塊の先頭が画面上端に来るようにスクロールします。
+    fn jump_change(&mut self, forward: bool) {
+        let current = self.scroll as usize;
+        let target = if forward {
+            self.change_starts().find(|&i| i > current)
+        } else {
+            self.change_starts().filter(|&i| i < current).last()
+        };
+        if let Some(i) = target {
+            self.scroll = i.min(u16::MAX as usize) as u16;
+        }
+    }
+
+    /// 差分の塊の先頭行（直前の行が同じ行のもの）の位置を返します。
+    fn change_starts(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rows.iter().enumerate().filter_map(|(i, r)| {
+            (r.change != Change::Same && (i == 0 || self.rows[i - 1].change == Change::Same))
+                .then_some(i)
+        })
+    }
+}
@@ -0,0 +1,351 @@
+//! イベント一覧のダウンロードパス入力状態。
+//!
+//! [`EventsScreen`] と [`GroupEventsScreen`] の `d` で共通のパス入力と、
+//! 全ページのバックグラウンドダウンロード（チェックポイントからの再開を含む）を管理します。
+//!
+//! パスは `Tab` で補完でき、確定時に `~`・`$VAR` を展開します。親ディレクトリが無い場合は作成するか、
+//! 書き出し先が既にある場合は上書き・追記・別名のいずれにするかを確認します。
+//!
+//! [`EventsScreen`]: crate::screen::events::EventsScreen
+//! [`GroupEventsScreen`]: crate::screen::group_events::GroupEventsScreen
+
+use aws_sdk_cloudwatchlogs::Client;
+use crossterm::event::KeyCode;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::app::LogEvent;
+use crate::checkpoint;
+use crate::columns::Column;
+use crate::compress::Compression;
+use crate::download::DownloadJob;
+use crate::export::{self, Encoder, ExportSource, Format};
+use crate::paths;
+
+/// 書き出すイベントの範囲。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// ロード済みのイベント
+    Loaded,
+    /// 条件に一致する全ページ
+    AllPages,
+    /// 書き出し先のチェックポイントから中断したダウンロードを再開
+    Resume,
+}
+
+impl Scope {
+    /// 画面表示用の名前を返します。
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Loaded => "loaded events",
+            Self::AllPages => "all matching pages",
+            Self::Resume => "resume from checkpoint",
+        }
+    }
+}
+
+/// 確定時の確認事項。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirm {
+    /// 親ディレクトリ `dir` が無いため、作成してから `path` に書き出すか
+    CreateDir {
+        /// 作成するディレクトリ
+        dir: String,
+        /// 展開済みの書き出し先
+        path: String,
+    },
+    /// 展開済みの書き出し先が既にあるため、上書き・追記・別名のいずれにするか
+    Exists(String),
+}
+
+impl Confirm {
+    /// 展開済みの `path` に書き出す前に確認が必要な事項を返します。確認不要の場合は `None` です。
+    pub fn needed(path: &str) -> Option<Self> {
+        match paths::missing_parent(path) {
+            Some(dir) => Some(Self::CreateDir {
+                dir,
+                path: path.to_string(),
+            }),
+            None if Path::new(path).exists() => Some(Self::Exists(path.to_string())),
+            None => None,
+        }
+    }
+}
+
+/// 入力されたパスの前後の空白を除いて `~`・`$VAR` を展開します。
+///
+/// 展開に失敗した場合と空の場合は画面表示用のエラーメッセージを返します。
+pub fn expand_input(input: &str) -> Result<String, String> {
+    match paths::expand(input.trim()) {
+        Ok(path) if path.is_empty() => Err("enter a file path".to_string()),
+        Ok(path) => Ok(path),
+        Err(e) => Err(format!("{:#}", e)),
+    }
+}
+
+/// 確定した書き出し先。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// `~`・`$VAR` を展開した書き出し先のパス
+    pub path: String,
+    /// 既存のファイルの末尾に追記するかどうか
+    pub append: bool,
+}
+
+/// ダウンロードパス入力の状態。
+pub struct DownloadPrompt {
+    /// パス入力モードがアクティブかどうか
+    pub editing: bool,
+    /// パス入力バッファ
+    pub buffer: String,
+    /// `↑`/`↓` で選んだ書き出し形式（`None` またはパスの拡張子と合わない場合は拡張子から判定）
+    chosen_format: Option<Format>,
+    /// 書き出すイベントの範囲
+    pub scope: Scope,
+    /// 確定時の確認中の事項
+    pub confirm: Option<Confirm>,
+    /// `Tab` で補完した際の候補（候補が複数の場合）
+    pub candidates: Vec<String>,
+    /// パスの展開・ディレクトリ作成のエラー
+    pub error: Option<String>,
+    /// 全ページのダウンロード・再開を選べるかどうか（ローカルファイルの場合は `false`）
+    pub can_fetch: bool,
+    /// ダウンロード結果メッセージ（成功またはエラー）
+    pub status: Option<String>,
+    /// 実行中または終了直後の全ページのダウンロード
+    pub job: Option<DownloadJob>,
+}
+
+impl DownloadPrompt {
+    /// 入力前の [`DownloadPrompt`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            editing: false,
+            buffer: String::new(),
+            chosen_format: None,
+            scope: Scope::Loaded,
+            confirm: None,
+            candidates: Vec::new(),
+            error: None,
+            can_fetch: false,
+            status: None,
+            job: None,
+        }
+    }
+
+    /// 既定のパスを入力バッファに展開して入力モードを開始します。
+    ///
+    /// `can_fetch` が `false` の場合はロード済みのイベントだけを書き出します。
+    pub fn start(&mut self, default_path: String, can_fetch: bool) {
+        self.buffer = default_path;
+        self.can_fetch = can_fetch;
+        if !can_fetch || self.scope == Scope::Resume {
+            self.scope = Scope::Loaded;
+        }
+        self.confirm = None;
+        self.candidates.clear();
+        self.error = None;
+        self.editing = true;
+    }
+
+    /// 入力モード中のキーを処理します。書き出し先が確定した場合はそれを返します。
+    ///
+    /// `Tab` でパスを補完し、`Shift+Tab` でロード済みのイベント・全ページ・再開
+    /// （パスにチェックポイントがある場合のみ）を、`↑`/`↓` で書き出し形式を切り替えます。
+    /// 形式を切り替えるとパスの拡張子も置き換えます。
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<Target> {
+        if let Some(confirm) = self.confirm.take() {
+            return self.handle_confirm(confirm, code);
+        }
+        self.candidates.clear();
+        self.error = None;
+        match code {
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Esc => {
+                self.editing = false;
+                self.buffer.clear();
+            }
+            KeyCode::Tab => {
+                let completion = paths::complete(&self.buffer);
+                self.buffer = completion.input;
+                self.candidates = completion.candidates;
+            }
+            KeyCode::BackTab if self.can_fetch => {
+                let resumable = paths::expand(&self.buffer).is_ok_and(|p| checkpoint::exists(&p));
+                self.scope = match self.scope {
+                    Scope::Loaded => Scope::AllPages,
+                    Scope::AllPages if resumable => Scope::Resume,
+                    Scope::AllPages | Scope::Resume => Scope::Loaded,
+                };
+            }
+            KeyCode::Up | KeyCode::Down => {
+                let format = self.format().cycle(code == KeyCode::Down);
+                self.buffer = format.with_extension(&self.buffer);
+                self.chosen_format = Some(format);
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+            }
+            KeyCode::Char(c) => self.buffer.push(c),
+            _ => {}
+        }
+        None
+    }
+
+    /// パスを展開し、親ディレクトリと既存のファイルを確認します。
+    fn submit(&mut self) -> Option<Target> {
+        let path = match expand_input(&self.buffer) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error = Some(e);
+                return None;
+            }
+        };
+        // 再開は既存のファイルへの追記のため確認しない
+        if self.scope == Scope::Resume {
+            return self.finish(path, false);
+        }
+        self.confirm_or_finish(path)
+    }
+
+    fn confirm_or_finish(&mut self, path: String) -> Option<Target> {
+        match Confirm::needed(&path) {
+            Some(confirm) => {
+                self.confirm = Some(confirm);
+                None
+            }
+            None => self.finish(path, false),
+        }
+    }
+
+    fn finish(&mut self, path: String, append: bool) -> Option<Target> {
+        self.editing = false;
+        Some(Target { path, append })
+    }
+
+    /// 確認中のキーを処理します。`Esc` で入力に戻ります。
+    fn handle_confirm(&mut self, confirm: Confirm, code: KeyCode) -> Option<Target> {
+        match (confirm, code) {
+            (Confirm::CreateDir { dir, path }, KeyCode::Char('y') | KeyCode::Enter) => {
+                match std::fs::create_dir_all(&dir) {
+                    Ok(()) => self.confirm_or_finish(path),
+                    Err(e) => {
+                        self.error = Some(format!("Failed to create {}: {}", dir, e));
+                        None
+                    }
+                }
+            }
+            (Confirm::Exists(path), KeyCode::Char('o')) => self.finish(path, false),
+            (Confirm::Exists(path), KeyCode::Char('a')) => {
+                let format = self.format();
+                if format.can_append() {
+                    self.finish(path, true)
+                } else {
+                    self.error = Some(format!("cannot append to a {} file", format.label()));
+                    None
+                }
+            }
+            (Confirm::Exists(path), KeyCode::Char('r')) => {
+                let path = paths::unique_path(&path);
+                self.finish(path, false)
+            }
+            (_, KeyCode::Esc | KeyCode::Char('n')) => None,
+            (confirm, _) => {
+                self.confirm = Some(confirm);
+                None
+            }
+        }
+    }
+
+    /// 書き出し形式を返します。
+    ///
+    /// `↑`/`↓` で選んだ形式がパスの拡張子と合う場合はその形式、それ以外は拡張子から判定した形式です。
+    pub fn format(&self) -> Format {
+        match self.chosen_format {
+            Some(format) if format.matches_path(&self.buffer) => format,
+            _ => Format::from_path(&self.buffer),
+        }
+    }
+
+    /// パスの拡張子から判定した圧縮形式を返します。
+    pub fn compression(&self) -> Compression {
+        Compression::from_path(&self.buffer)
+    }
+
+    /// 直前のキー入力の結果メッセージと、終了済みのダウンロードの結果表示を消去します。
+    ///
+    /// 画面のキー処理の最初に呼び出します。
+    pub fn clear_status(&mut self) {
+        self.status = None;
+        if self.job.as_ref().is_some_and(|job| !job.is_running()) {
+            self.job = None;
+        }
+    }
+
+    /// 取得中の全ページのダウンロードを中断します。
+    pub fn cancel(&self) {
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+    }
+
+    /// 確定した書き出し先に、選んだ範囲のイベントを書き出します。
+    ///
+    /// ロード済みのイベントは `events` をその場で書き出し、全ページ・再開の場合は `source` の条件で
+    /// バックグラウンドのダウンロードを開始します。`columns` は CSV・Markdown の表に含める列です。
+    pub fn run(
+        &mut self,
+        target: Target,
+        client: &Arc<Client>,
+        source: ExportSource,
+        columns: Vec<Column>,
+        events: &[LogEvent],
+    ) {
+        let encoder = Encoder::new(source, self.format(), columns);
+        match self.scope {
+            Scope::Loaded => self.write_events(&target, encoder, events),
+            Scope::AllPages => self.spawn(Arc::clone(client), target, encoder),
+            Scope::Resume => self.resume(Arc::clone(client), target.path),
+        }
+    }
+
+    fn write_events(&mut self, target: &Target, encoder: Encoder, events: &[LogEvent]) {
+        let format = encoder.format;
+        let result = export::write_events(&target.path, encoder, events, target.append);
+        self.status = Some(match result {
+            Ok(n) => format!(
+                "{} {} events as {}: {}",
+                if target.append { "Appended" } else { "Saved" },
+                n,
+                format.label(),
+                target.path
+            ),
+            Err(e) => format!("Error: {:#}", e),
+        });
+    }
+
+    /// `encoder` の取得条件に一致する全ページのダウンロードをバックグラウンドで開始します。
+    ///
+    /// 既に取得中の場合と書き出し先を作成できない場合は、エラーを結果メッセージに設定します。
+    fn spawn(&mut self, client: Arc<Client>, target: Target, encoder: Encoder) {
+        self.begin(|| DownloadJob::spawn(client, target.path, encoder, target.append));
+    }
+
+    /// 書き出し先 `path` のチェックポイントから、中断したダウンロードをバックグラウンドで再開します。
+    ///
+    /// 既に取得中の場合とチェックポイントを読み込めない場合は、エラーを結果メッセージに設定します。
+    fn resume(&mut self, client: Arc<Client>, path: String) {
+        self.begin(|| DownloadJob::resume(client, path));
+    }
+
+    fn begin(&mut self, start: impl FnOnce() -> anyhow::Result<DownloadJob>) {
+        if self.job.as_ref().is_some_and(DownloadJob::is_running) {
+            self.status = Some("Error: a download is already running (x to cancel)".to_string());
+            return;
+        }
+        match start() {
+            Ok(job) => self.job = Some(job),
+            Err(e) => self.status = Some(format!("Error: {:#}", e)),
+        }
+    }
+}
@@ -3,14 +3,16 @@
 //! 開始日時・終了日時・フィルタパターンを入力してグループ横断検索を実行します。
 
 use anyhow::Result;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 
 use super::{CurrentScreen, NavigateTo, ScreenAction};
+use crate::filter::FilterPattern;
+use crate::time;
 
 /// 時間範囲とフィルタパターンでイベントを検索するフォームスクリーン。
 ///
 /// `Tab`/`BackTab` でフィールド間を移動し、`Enter` で検索を実行します。
-/// 日時は `YYYY-MM-DD HH:MM:SS` 形式（UTC）で入力します。
+/// 日時は `YYYY-MM-DD HH:MM:SS` 形式（表示タイムゾーン）で入力します。
 pub struct EventSearchScreen {
     /// 検索対象のロググループ名
     pub group_name: String,
@@ -24,6 +26,8 @@ pub struct EventSearchScreen {
     pub event_search_focused: u8,
     /// バリデーションエラーメッセージ
     pub event_search_error: Option<String>,
+    /// 構文エラーのあるパターンを、もう一度 `Enter` で送信する確認中かどうか
+    pub event_search_confirm: bool,
     /// 前の画面（`q`/`Esc` で戻るため保持）
     pub origin: Option<Box<CurrentScreen>>,
 }
@@ -31,17 +35,19 @@ pub struct EventSearchScreen {
 impl EventSearchScreen {
     /// 新しい [`EventSearchScreen`] を生成します。
     ///
-    /// 開始日時は現在時刻の 1 時間前、終了日時は現在時刻で初期化されます。
+    /// 開始日時は現在時刻の 1 時間前、終了日時は現在時刻（いずれも表示タイムゾーン）で
+    /// 初期化されます。
     pub fn new(group_name: String, origin: Box<CurrentScreen>) -> Self {
-        let now = jiff::Zoned::now();
+        let now = time::now();
         let one_hour_ago = now.saturating_sub(jiff::Span::new().hours(1));
         Self {
             group_name,
-            event_search_start: one_hour_ago.strftime("%Y-%m-%d %H:%M:%S").to_string(),
-            event_search_end: now.strftime("%Y-%m-%d %H:%M:%S").to_string(),
+            event_search_start: time::format_zoned(&one_hour_ago),
+            event_search_end: time::format_zoned(&now),
             event_search_pattern: String::new(),
             event_search_focused: 0,
             event_search_error: None,
+            event_search_confirm: false,
             origin: Some(origin),
         }
     }
@@ -49,8 +55,15 @@ impl EventSearchScreen {
     /// キー入力を処理して [`ScreenAction`] を返します。
     ///
     /// `Enter` でフォームを検証し、成功すれば [`NavigateTo::NewGroupEvents`] を返します。
-    /// 日時の解析に失敗した場合は `event_search_error` にエラーメッセージを設定します。
-    pub async fn handle_key(&mut self, code: KeyCode) -> Result<ScreenAction> {
+    /// 日時やフィルタパターンの解析に失敗した場合は `event_search_error` にエラーメッセージを設定します。
+    /// パターンの構文エラーは、もう一度 `Enter` を押すとそのまま送信して AWS に判定を任せます。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        let confirm = std::mem::take(&mut self.event_search_confirm);
+        if confirm && code != KeyCode::Enter {
+            // 確認の案内は入力を続けたら取り消す（構文エラーは入力中の表示に戻る）
+            self.event_search_error = None;
+        }
         match code {
             KeyCode::Char('q') | KeyCode::Esc => {
                 if let Some(origin) = self.origin.take() {
@@ -83,19 +96,19 @@ impl EventSearchScreen {
                 let start_ms_result = if self.event_search_start.is_empty() {
                     Ok(None)
                 } else {
-                    parse_datetime_to_ms(&self.event_search_start).map(Some)
+                    time::parse_datetime_to_ms(&self.event_search_start).map(Some)
                 };
                 let end_ms_result = if self.event_search_end.is_empty() {
                     Ok(None)
                 } else {
-                    parse_datetime_to_ms(&self.event_search_end).map(Some)
+                    time::parse_datetime_to_ms(&self.event_search_end).map(Some)
                 };
-                match (start_ms_result, end_ms_result) {
-                    (Ok(start_ms), Ok(end_ms)) => {
+                let pattern_result = FilterPattern::parse(&self.event_search_pattern)
+                    .map(|_| ())
+                    .or_else(|e| if confirm { Ok(()) } else { Err(e) });
+                match (start_ms_result, end_ms_result, pattern_result) {
+                    (Ok(start_ms), Ok(end_ms), Ok(_)) => {
                         self.event_search_error = None;
-                        let start_display = self.event_search_start.clone();
-                        let end_display = self.event_search_end.clone();
-                        let pattern_display = self.event_search_pattern.clone();
                         let pattern = if self.event_search_pattern.is_empty() {
                             None
                         } else {
@@ -106,19 +119,23 @@ impl EventSearchScreen {
                             start_ms,
                             end_ms,
                             pattern,
-                            start_display,
-                            end_display,
-                            pattern_display,
                         }));
                     }
-                    (Err(_), _) => {
+                    (Err(_), _, _) => {
                         self.event_search_error =
                             Some("開始日時の形式が不正です（例: 2024-01-01 12:00:00）".to_string());
                     }
-                    (_, Err(_)) => {
+                    (_, Err(_), _) => {
                         self.event_search_error =
                             Some("終了日時の形式が不正です（例: 2024-01-01 12:00:00）".to_string());
                     }
+                    (_, _, Err(e)) => {
+                        self.event_search_error = Some(format!(
+                            "フィルタパターンが不正です: {}（Enter でそのまま検索）",
+                            e
+                        ));
+                        self.event_search_confirm = true;
+                    }
                 }
             }
             _ => {}
@@ -126,21 +143,3 @@ impl EventSearchScreen {
         Ok(ScreenAction::None)
     }
 }
-
-/// `YYYY-MM-DD HH:MM:SS` 形式の文字列を UTC Unix ミリ秒に変換します。
-///
-/// # Errors
-///
-/// - 日時の形式が不正な場合
-/// - タイムゾーン変換に失敗した場合
-fn parse_datetime_to_ms(s: &str) -> anyhow::Result<i64> {
-    let iso_str = s.replacen(' ', "T", 1);
-    let dt: jiff::civil::DateTime = iso_str
-        .parse()
-        .map_err(|_| anyhow::anyhow!("日時のフォーマットが不正です（例: 2024-01-01 12:00:00）"))?;
-    let tz = jiff::tz::TimeZone::UTC;
-    let zoned = dt
-        .to_zoned(tz)
-        .map_err(|e| anyhow::anyhow!("タイムゾーン変換に失敗しました: {}", e))?;
-    Ok(zoned.timestamp().as_millisecond())
-}
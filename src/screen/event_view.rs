@@ -0,0 +1,394 @@
+//! イベント一覧画面で共通のローカル表示状態。
+//!
+//! [`EventsScreen`] と [`GroupEventsScreen`] の両方で使用する、ロード済みイベントに対する
+//! 絞り込み（レベルファセット・フィールドクエリ・テンプレート）・ローカル検索・JSON フィールド列・
+//! ヒストグラム・重複の折りたたみ・ブックマーク操作・範囲選択とコピー・差分の比較元・
+//! 指定時刻へのジャンプをまとめて管理します。
+//!
+//! [`EventsScreen`]: crate::screen::events::EventsScreen
+//! [`GroupEventsScreen`]: crate::screen::group_events::GroupEventsScreen
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::column_editor::ColumnEditor;
+use super::dedup::Dedup;
+use super::field_query::FieldQuery;
+use super::histogram::Histogram;
+use super::level_facet::LevelFacets;
+use super::local_search::LocalSearch;
+use super::motion::{Keystroke, MotionKeys};
+use super::time_jump::{PendingJump, TimeJump};
+use crate::app::{LogEvent, StatefulList};
+use crate::bookmarks;
+use crate::clipboard;
+use crate::drain::Template;
+use crate::time;
+
+/// イベント一覧のローカル表示状態。
+pub struct EventView {
+    /// 表示中のロググループ名（ブックマークの出所として使用）
+    pub group: String,
+    /// ロード済みイベントに対するローカル検索の状態
+    pub search: LocalSearch,
+    /// レベルファセットの状態
+    pub facets: LevelFacets,
+    /// JSON フィールド列の状態
+    pub columns: ColumnEditor,
+    /// フィールドクエリの状態
+    pub query: FieldQuery,
+    /// テンプレート一覧で選んだテンプレートによる絞り込み
+    pub template: Option<Template>,
+    /// イベント量ヒストグラムの状態
+    pub histogram: Histogram,
+    /// 重複折りたたみの状態
+    pub dedup: Dedup,
+    /// 範囲選択の起点（`items` 内インデックス、`None` は範囲選択なし）
+    pub visual_anchor: Option<usize>,
+    /// 直前の操作の結果メッセージ（コピー結果など）
+    pub notice: Option<String>,
+    /// 差分の比較元として選んだイベント
+    pub diff_base: Option<LogEvent>,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// 指定時刻へのジャンプの入力状態
+    pub jump: TimeJump,
+}
+
+impl EventView {
+    /// 絞り込みなしの [`EventView`] を生成します。
+    ///
+    /// JSON フィールド列は `group` に保存済みの定義を読み込みます。
+    pub fn new(group: &str) -> Self {
+        Self {
+            group: group.to_string(),
+            search: LocalSearch::new(),
+            facets: LevelFacets::new(),
+            columns: ColumnEditor::new(group),
+            query: FieldQuery::new(),
+            template: None,
+            histogram: Histogram::new(),
+            dedup: Dedup::new(),
+            visual_anchor: None,
+            notice: None,
+            diff_base: None,
+            keys: MotionKeys::new(),
+            jump: TimeJump::new(),
+        }
+    }
+
+    /// 一覧画面で共通のキーを処理します。処理した場合は `true` を返します。
+    ///
+    /// 検索・列指定・クエリ・時刻の入力モード中と、ファセット操作・バケット選択モード中は
+    /// 全てのキーをここで処理します。通常モードではカーソル移動（`j`/`k`・`gg`/`G`・`Ctrl-d`/`Ctrl-u`・
+    /// `PageUp`/`PageDown`・`Home`/`End`・カウント接頭辞）、`?`（ローカル検索）、`n`/`N`（一致箇所の移動）、
+    /// `f`（ファセット操作）、`c`（列指定）、`J`（フィールドクエリ）、`H`（バケット選択）、`t`（時刻入力）、
+    /// `u`（重複の折りたたみ切替）、折りたたみ中の `Space`（塊の展開切替）、`m`（ブックマーク切替）、
+    /// `'`/`` ` ``（次/前のブックマークへ移動）、`v`（範囲選択）、
+    /// `y`（クリップボードへコピー）、指定時刻を探している間の `Esc`（移動の中止）、範囲選択中の `Esc`（選択解除）、
+    /// テンプレートで絞り込み中の `Esc`（絞り込み解除）を処理します。
+    pub fn handle_key(&mut self, key: KeyEvent, list: &mut StatefulList<LogEvent>) -> bool {
+        let code = key.code;
+        self.notice = None;
+        if self.search.editing {
+            self.search.handle_key(code, list);
+            return true;
+        }
+        if self.columns.editing {
+            self.columns.handle_key(code);
+            return true;
+        }
+        if self.jump.editing {
+            self.jump.handle_key(code);
+            return true;
+        }
+        if self.query.editing {
+            if self.query.handle_key(code) {
+                self.refilter(list);
+            }
+            return true;
+        }
+        if self.histogram.focused {
+            self.histogram.handle_key(code, list);
+            return true;
+        }
+        if self.facets.focused {
+            if self.facets.handle_key(code) {
+                self.refilter(list);
+            }
+            return true;
+        }
+        if code == KeyCode::Esc && self.jump.pending.take().is_some() {
+            self.notice = Some("Jump cancelled".to_string());
+            return true;
+        }
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                list.apply_motion(motion);
+                return true;
+            }
+            Keystroke::Pending => return true,
+            Keystroke::Unhandled => {}
+        }
+        match code {
+            KeyCode::Char('?') => self.search.start(list),
+            KeyCode::Char('n') => self.search.next_match(list),
+            KeyCode::Char('N') => self.search.prev_match(list),
+            KeyCode::Char('f') => self.facets.focused = true,
+            KeyCode::Char('c') => self.columns.start(),
+            KeyCode::Char('J') => self.query.start(),
+            KeyCode::Char('H') => self.histogram.start(list),
+            KeyCode::Char('t') => self.jump.start(),
+            KeyCode::Char('u') => {
+                self.dedup.toggle();
+                self.refilter(list);
+            }
+            KeyCode::Char(' ') if self.dedup.enabled => {
+                if let Some(i) = list.selected_index()
+                    && self.dedup.toggle_expand(i)
+                {
+                    self.refilter(list);
+                }
+            }
+            KeyCode::Char('m') => {
+                if let Some(event) = list.selected() {
+                    bookmarks::toggle(&self.group, event);
+                }
+            }
+            KeyCode::Char('\'') => self.jump_bookmark(list, true),
+            KeyCode::Char('`') => self.jump_bookmark(list, false),
+            KeyCode::Char('v') => {
+                self.visual_anchor = match self.visual_anchor {
+                    Some(_) => None,
+                    None => list.selected_index(),
+                };
+            }
+            KeyCode::Char('y') => self.yank(list),
+            KeyCode::Esc if self.visual_anchor.is_some() => self.visual_anchor = None,
+            KeyCode::Esc if self.template.is_some() => self.set_template(None, list),
+            _ => return false,
+        }
+        true
+    }
+
+    /// ロード・追加ロードなどでリストの内容が変わった後に、表示対象と検索結果を再計算します。
+    ///
+    /// 選択中のイベントが引き続き表示される場合はその位置を維持し、
+    /// 非表示になった場合は直後の表示対象イベントを選択します。
+    pub fn refilter(&mut self, list: &mut StatefulList<LogEvent>) {
+        // 折りたたまれる塊に含まれるイベントを選択中の場合は塊の先頭を選択し直す
+        let selected = list.selected_index().map(|i| self.dedup.representative(i));
+        self.query.reset();
+        let filtering = self.facets.is_filtering()
+            || self.query.is_active()
+            || self.template.is_some()
+            || self.dedup.enabled;
+        list.visible_indices = if filtering {
+            let filtered = list
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(i, e)| {
+                    self.facets.accepts(e)
+                        && self.query.accepts(*i, e)
+                        && self.template.as_ref().is_none_or(|t| t.matches(&e.message))
+                })
+                .map(|(i, _)| i);
+            Some(if self.dedup.enabled {
+                self.dedup.collapse(&list.items, filtered)
+            } else {
+                filtered.collect()
+            })
+        } else {
+            None
+        };
+        let new_pos = match &list.visible_indices {
+            Some(v) => match selected {
+                Some(sel) => v
+                    .iter()
+                    .position(|&i| i >= sel)
+                    .or_else(|| v.len().checked_sub(1)),
+                None => (!v.is_empty()).then_some(0),
+            },
+            None => match selected {
+                Some(sel) => Some(sel),
+                None => (!list.items.is_empty()).then_some(0),
+            },
+        };
+        list.state.select(new_pos);
+        self.search.refresh(list);
+    }
+
+    /// テンプレートによる絞り込みを設定（`None` で解除）し、表示対象を再計算します。
+    pub fn set_template(&mut self, template: Option<Template>, list: &mut StatefulList<LogEvent>) {
+        self.template = template;
+        self.refilter(list);
+    }
+
+    /// 表示対象のうち `ms` 以降で最初のイベントを選択します。見つからない場合は `false` を返します。
+    pub fn select_time(&self, list: &mut StatefulList<LogEvent>, ms: i64) -> bool {
+        let pos = list.visible_items().iter().position(|e| e.timestamp >= ms);
+        if pos.is_some() {
+            list.state.select(pos);
+        }
+        pos.is_some()
+    }
+
+    /// 表示対象のうち `ms` 以降で最初のイベントへ移動します。
+    ///
+    /// ロード済みのイベントに見つからず、`ms` から読み込み直す必要がある場合は `list` を空にして
+    /// 移動先を [`TimeJump::pending`] に保持し、`true` を返します（画面側は読み込み開始時刻を `ms` にします）。
+    /// 読み込み開始時刻 `loaded_from` より前の時刻を指定した場合と、未読み込みのページが残っている場合です
+    /// （`can_fetch` が `false` の場合は読み込み直しません）。見つからない場合は結果メッセージに設定します。
+    pub fn jump_to_time(
+        &mut self,
+        list: &mut StatefulList<LogEvent>,
+        ms: i64,
+        loaded_from: Option<i64>,
+        can_fetch: bool,
+    ) -> bool {
+        let before_loaded = loaded_from.is_some_and(|start| ms < start);
+        if !before_loaded && self.select_time(list, ms) {
+            return false;
+        }
+        if can_fetch && (before_loaded || list.next_token.is_some()) {
+            *list = StatefulList::new();
+            let jump = PendingJump { ms, pages: 0 };
+            self.notice = Some(Self::jump_notice(jump, 0));
+            self.jump.pending = Some(jump);
+            return true;
+        }
+        self.notice = Some(Self::no_events_notice(ms));
+        false
+    }
+
+    /// 読み込み直しながら移動先を探している間の結果メッセージを返します。
+    pub fn jump_notice(jump: PendingJump, events: usize) -> String {
+        format!(
+            "Searching for {}… {} pages, {} events loaded (Esc to cancel)",
+            time::format_ms_seconds(jump.ms),
+            jump.pages,
+            events
+        )
+    }
+
+    /// `ms` 以降のイベントが無い場合の結果メッセージを返します。
+    pub fn no_events_notice(ms: i64) -> String {
+        format!("No events at or after {}", time::format_ms_seconds(ms))
+    }
+
+    /// 表示対象のイベントと、その表示行（フィールドクエリが値を返した場合はその値）を返します。
+    pub fn display_rows<'a>(
+        &'a self,
+        list: &'a StatefulList<LogEvent>,
+    ) -> Vec<(&'a LogEvent, &'a str)> {
+        let row = |i: usize| {
+            let event = &list.items[i];
+            let text = self.query.projection(i).unwrap_or(&event.message);
+            (event, text)
+        };
+        match &list.visible_indices {
+            Some(v) => v.iter().map(|&i| row(i)).collect(),
+            None => (0..list.items.len()).map(row).collect(),
+        }
+    }
+
+    /// 表示中のリスト内で、次（`forward` が `false` の場合は前）のブックマークへ移動します。
+    /// 末尾・先頭では反対側に折り返します。
+    fn jump_bookmark(&self, list: &mut StatefulList<LogEvent>, forward: bool) {
+        let visible = list.visible_items();
+        let marked: Vec<usize> = visible
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| bookmarks::contains(&self.group, e))
+            .map(|(pos, _)| pos)
+            .collect();
+        let current = list.state.selected();
+        let target = if forward {
+            marked
+                .iter()
+                .find(|&&p| current.is_none_or(|c| p > c))
+                .or(marked.first())
+        } else {
+            marked
+                .iter()
+                .rev()
+                .find(|&&p| current.is_none_or(|c| p < c))
+                .or(marked.last())
+        };
+        if let Some(&pos) = target {
+            list.state.select(Some(pos));
+        }
+    }
+
+    /// 差分の比較元を選びます。
+    ///
+    /// 比較元が未選択の場合は選択中のイベントを比較元にして `None` を返します。
+    /// 選択済みの場合は比較元と選択中のイベントの組を返し、比較元をクリアします。
+    /// 比較元と同じイベントを選んだ場合は比較元の選択を解除します。
+    pub fn mark_diff(&mut self, list: &StatefulList<LogEvent>) -> Option<(LogEvent, LogEvent)> {
+        let selected = list.selected()?;
+        match self.diff_base.take() {
+            Some(base) if Self::same_event(&base, selected) => {
+                self.notice = Some("Diff base cleared".to_string());
+                None
+            }
+            Some(base) => Some((base, selected.clone())),
+            None => {
+                self.diff_base = Some(selected.clone());
+                self.notice =
+                    Some("Diff base set: press D on another event to compare".to_string());
+                None
+            }
+        }
+    }
+
+    /// `event` が差分の比較元かどうかを返します。
+    pub fn is_diff_base(&self, event: &LogEvent) -> bool {
+        self.diff_base
+            .as_ref()
+            .is_some_and(|base| Self::same_event(base, event))
+    }
+
+    fn same_event(a: &LogEvent, b: &LogEvent) -> bool {
+        a.timestamp == b.timestamp && a.stream == b.stream && a.message == b.message
+    }
+
+    /// 範囲選択中の表示位置の範囲（両端を含む）を返します。範囲選択中でない場合は `None` を返します。
+    pub fn visual_range(&self, list: &StatefulList<LogEvent>) -> Option<(usize, usize)> {
+        let anchor = self.visual_anchor?;
+        let cursor = list.state.selected()?;
+        // 起点が非表示になった場合は直後の表示対象を起点とする
+        let anchor = match &list.visible_indices {
+            Some(v) => v
+                .partition_point(|&i| i < anchor)
+                .min(v.len().saturating_sub(1)),
+            None => anchor,
+        };
+        Some((anchor.min(cursor), anchor.max(cursor)))
+    }
+
+    /// 選択範囲（範囲選択中でない場合は選択中のイベント）を「タイムスタンプ  表示行」の形式で
+    /// クリップボードにコピーし、範囲選択を終了します。
+    fn yank(&mut self, list: &StatefulList<LogEvent>) {
+        let Some((from, to)) = self
+            .visual_range(list)
+            .or_else(|| list.state.selected().map(|i| (i, i)))
+        else {
+            return;
+        };
+        let rows = self.display_rows(list);
+        let Some(selected) = rows.get(from..=to.min(rows.len().saturating_sub(1))) else {
+            return;
+        };
+        let text: String = selected
+            .iter()
+            .map(|(e, text)| format!("{}  {}\n", time::format_ms(e.timestamp), text))
+            .collect();
+        let count = selected.len();
+        self.notice = Some(match clipboard::copy(&text) {
+            Ok(()) => format!("Copied {} event(s) to clipboard", count),
+            Err(e) => format!("Error: {:#}", e),
+        });
+        self.visual_anchor = None;
+    }
+}
@@ -1,21 +1,31 @@
 //! ログイベント一覧スクリーンの状態管理。
 //!
-//! 指定したログストリームのイベントを一覧表示し、
+//! 指定したログストリーム（またはローカルの JSONL ファイル）のイベントを一覧表示し、
 //! フィルタリングと JSONL ダウンロードをサポートします。
 
 use anyhow::Result;
 use aws_sdk_cloudwatchlogs::Client;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 use std::sync::Arc;
 
+use super::download_prompt::DownloadPrompt;
+use super::event_view::EventView;
+use super::paging;
 use super::{CurrentScreen, NavigateTo, ScreenAction};
 use crate::app::{LogEvent, StatefulList};
-use crate::aws;
+use crate::export::{self, ExportSource};
+use crate::filter::FilterPattern;
+use crate::time;
 
 /// ログイベント一覧を表示するスクリーン。
 ///
-/// `j`/`k` でリスト移動、`Enter` で詳細表示、`/` でフィルタ編集、
-/// `d` でダウンロードパス入力、`q` で前の画面に戻ります。
+/// `j`/`k` でリスト移動（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`Enter` で詳細表示、
+/// `/` でフィルタ編集、`?` でロード済みイベントのローカル検索（`n`/`N` で一致箇所を移動）、
+/// `f` でレベルファセット操作、
+/// `c` で JSON フィールド列の指定、`m` でブックマーク、`B` でブックマーク一覧、
+/// `D` で 2 つのイベントの差分表示、`u` で重複の折りたたみ、`t` で指定時刻へ移動、
+/// `P` でテンプレート一覧、`d` でダウンロードパス入力、`x` で全ページのダウンロードを中断、`z` で表示タイムゾーン切替、
+/// `q` で前の画面に戻ります。
 pub struct EventsScreen {
     /// 共有 AWS CloudWatch Logs クライアント
     pub client: Arc<Client>,
@@ -27,16 +37,20 @@ pub struct EventsScreen {
     pub filter_editing: bool,
     /// フィルタ入力バッファ
     pub filter_buffer: String,
-    /// ダウンロードパス入力モードがアクティブかどうか
-    pub download_editing: bool,
-    /// ダウンロードパス入力バッファ
-    pub download_path_buffer: String,
-    /// ダウンロード結果メッセージ（成功またはエラー）
-    pub download_status: Option<String>,
+    /// 構文エラーのあるパターンを、もう一度 `Enter` で送信する確認中かどうか
+    pub filter_confirm: bool,
+    /// ロード済みイベントに対するローカル表示状態（検索・レベルファセット・フィールド列）
+    pub view: EventView,
+    /// ダウンロードパス入力の状態
+    pub download: DownloadPrompt,
     /// 対象のロググループ名
     pub group_name: String,
     /// 対象のログストリーム名
     pub stream_name: String,
+    /// 読み込み開始時刻（Unix ミリ秒、`t` で移動した場合に設定。`None` はストリームの先頭から）
+    pub start_ms: Option<i64>,
+    /// ローカルファイルから開いた場合の全イベント（フィルタはローカルで適用）
+    pub offline_events: Option<Vec<LogEvent>>,
     /// 前の画面（`q` で戻るため保持）
     pub origin: Option<Box<CurrentScreen>>,
 }
@@ -57,45 +71,63 @@ impl EventsScreen {
             filter_input: None,
             filter_editing: false,
             filter_buffer: String::new(),
-            download_editing: false,
-            download_path_buffer: String::new(),
-            download_status: None,
+            filter_confirm: false,
+            view: EventView::new(&group_name),
+            download: DownloadPrompt::new(),
             group_name,
             stream_name,
+            start_ms: None,
+            offline_events: None,
             origin: Some(origin),
         }
     }
 
+    /// ローカルの JSONL ファイルから読み込んだイベントで [`EventsScreen`] を生成します。
+    ///
+    /// フィルタパターンは AWS に送信せず、読み込み済みのイベントに対してローカルで適用します。
+    pub fn from_file(
+        client: Arc<Client>,
+        path: String,
+        events: Vec<LogEvent>,
+        origin: Box<CurrentScreen>,
+    ) -> Self {
+        let mut s = Self::new(client, path, String::new(), origin);
+        s.offline_events = Some(events);
+        s
+    }
+
     /// キー入力を処理して [`ScreenAction`] を返します。
     ///
-    /// ダウンロードパス入力モードとフィルタ入力モードを優先的に処理します。
-    pub async fn handle_key(&mut self, code: KeyCode) -> Result<ScreenAction> {
-        self.download_status = None;
-        if self.download_editing {
-            match code {
-                KeyCode::Enter => {
-                    let path = self.download_path_buffer.clone();
-                    self.write_events_to_jsonl(&path);
-                    self.download_editing = false;
-                }
-                KeyCode::Esc => {
-                    self.download_editing = false;
-                    self.download_path_buffer.clear();
-                }
-                KeyCode::Backspace => {
-                    self.download_path_buffer.pop();
-                }
-                KeyCode::Char(c) => {
-                    self.download_path_buffer.push(c);
-                }
-                _ => {}
+    /// ダウンロードパス入力モード・フィルタ入力モードを優先的に処理し、
+    /// 続いて一覧画面共通のキー（ローカル検索・レベルファセット・ブックマークなど）を [`EventView`] で処理します。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        self.download.clear_status();
+        if self.download.editing {
+            if let Some(target) = self.download.handle_key(code) {
+                self.download.run(
+                    target,
+                    &self.client,
+                    self.source(),
+                    self.view.columns.columns.clone(),
+                    &self.log_events.items,
+                );
             }
             return Ok(ScreenAction::None);
         }
         if self.filter_editing {
+            let confirm = std::mem::take(&mut self.filter_confirm);
             match code {
                 KeyCode::Enter => {
                     let pattern = self.filter_buffer.clone();
+                    // 構文エラーは入力中に表示済み。ローカルファイルではパターンを適用できないため送信せず、
+                    // AWS にはもう一度 `Enter` を押した場合に送信して判定を任せる
+                    if FilterPattern::parse(&pattern).is_err()
+                        && (self.offline_events.is_some() || !confirm)
+                    {
+                        self.filter_confirm = self.offline_events.is_none();
+                        return Ok(ScreenAction::None);
+                    }
                     self.filter_input = if pattern.is_empty() {
                         None
                     } else {
@@ -103,7 +135,10 @@ impl EventsScreen {
                     };
                     self.filter_editing = false;
                     self.log_events = StatefulList::new();
-                    self.load_log_events().await?;
+                    if let Err(e) = self.load_log_events().await {
+                        self.log_events.loading = false;
+                        self.view.notice = Some(e.to_string());
+                    }
                 }
                 KeyCode::Esc => {
                     self.filter_editing = false;
@@ -119,21 +154,34 @@ impl EventsScreen {
             }
             return Ok(ScreenAction::None);
         }
+        if self.view.handle_key(key, &mut self.log_events) {
+            if let Some(ms) = self.view.jump.take_target() {
+                self.jump_to_time(ms);
+            }
+            return Ok(ScreenAction::None);
+        }
         match code {
             KeyCode::Char('q') => {
                 if let Some(origin) = self.origin.take() {
                     return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
                 }
             }
-            KeyCode::Char('j') | KeyCode::Down => self.log_events.next(),
-            KeyCode::Char('k') | KeyCode::Up => self.log_events.previous(),
             KeyCode::Char('/') => {
                 self.filter_editing = true;
                 self.filter_buffer = self.filter_input.clone().unwrap_or_default();
             }
-            KeyCode::Char('d') => {
-                self.download_path_buffer = self.default_download_path();
-                self.download_editing = true;
+            KeyCode::Char('d') => self.download.start(
+                export::default_path(&self.group_name),
+                self.offline_events.is_none(),
+            ),
+            KeyCode::Char('x') => self.download.cancel(),
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            KeyCode::Char('B') => return Ok(ScreenAction::Navigate(NavigateTo::NewBookmarks)),
+            KeyCode::Char('P') => return Ok(ScreenAction::Navigate(NavigateTo::NewPatterns)),
+            KeyCode::Char('D') => {
+                if let Some((left, right)) = self.view.mark_diff(&self.log_events) {
+                    return Ok(ScreenAction::Navigate(NavigateTo::NewDiff { left, right }));
+                }
             }
             KeyCode::Enter => {
                 if let Some(event) = self.log_events.selected().cloned() {
@@ -145,93 +193,56 @@ impl EventsScreen {
         Ok(ScreenAction::None)
     }
 
-    /// カーソルが末尾付近に達した場合にページネーションで追加ロードします。
+    /// カーソルが末尾付近に達した場合などにページネーションで追加ロードします（[`paging::check_pagination`]）。
     ///
     /// メインループ毎フレームで呼び出されます。
-    pub async fn check_pagination(&mut self) -> Result<()> {
-        if let Some(idx) = self.log_events.selected_index() {
-            let len = self.log_events.items.len();
-            if len > 0
-                && idx + 5 >= len
-                && self.log_events.next_token.is_some()
-                && !self.log_events.loading
-            {
-                self.load_more_events().await?;
-            }
+    pub async fn check_pagination(&mut self) {
+        let source = self.source();
+        paging::check_pagination(&self.client, &source, &mut self.log_events, &mut self.view).await;
+    }
+
+    /// 表示対象のうち `ms` 以降で最初のイベントへ移動します。
+    ///
+    /// ロード済みのイベントに見つからず未読み込みのページが残っている場合と、
+    /// 読み込み開始時刻より前の時刻を指定した場合は、`ms` を開始時刻として読み込み直します
+    /// （ページネーションで毎フレーム 1 ページずつ読み込み、`Esc` で中止できます）。
+    fn jump_to_time(&mut self, ms: i64) {
+        let can_fetch = self.offline_events.is_none();
+        if self
+            .view
+            .jump_to_time(&mut self.log_events, ms, self.start_ms, can_fetch)
+        {
+            self.start_ms = Some(ms);
         }
-        Ok(())
     }
 
     /// ログイベントを初回ロードします（現在のフィルタを適用）。
+    ///
+    /// ローカルファイルから開いた場合は AWS に問い合わせず、フィルタをローカルで適用します。
     pub async fn load_log_events(&mut self) -> Result<()> {
-        let filter = self.filter_input.clone();
-        self.log_events.loading = true;
-        let (events, token) = aws::fetch_log_events(
-            &self.client,
-            &self.group_name,
-            Some(&self.stream_name),
-            None,
-            None,
-            filter,
-            None,
-        )
-        .await?;
-        self.log_events.items = events;
-        self.log_events.next_token = token;
-        self.log_events.loading = false;
-        if !self.log_events.items.is_empty() {
-            self.log_events.state.select(Some(0));
+        if let Some(all) = &self.offline_events {
+            let pattern = FilterPattern::parse(self.filter_input.as_deref().unwrap_or(""))?;
+            self.log_events.items = all
+                .iter()
+                .filter(|e| pattern.matches(&e.message))
+                .cloned()
+                .collect();
+            self.view.dedup.reset();
+            self.view.refilter(&mut self.log_events);
+            return Ok(());
         }
-        Ok(())
-    }
-
-    async fn load_more_events(&mut self) -> Result<()> {
-        let filter = self.filter_input.clone();
-        let token = self.log_events.next_token.clone();
-        self.log_events.loading = true;
-        let (events, next) = aws::fetch_log_events(
-            &self.client,
-            &self.group_name,
-            Some(&self.stream_name),
-            None,
-            None,
-            filter,
-            token,
-        )
-        .await?;
-        self.log_events.items.extend(events);
-        self.log_events.next_token = next;
-        self.log_events.loading = false;
-        Ok(())
-    }
-
-    fn default_download_path(&self) -> String {
-        let group_short = self
-            .group_name
-            .rsplit('/')
-            .find(|s| !s.is_empty())
-            .unwrap_or("unknown");
-        let date = jiff::Zoned::now().date();
-        format!("{}-{}.jsonl", group_short, date)
+        let source = self.source();
+        paging::load_first(&self.client, &source, &mut self.log_events, &mut self.view).await
     }
 
-    fn write_events_to_jsonl(&mut self, path: &str) {
-        let lines: Vec<String> = self
-            .log_events
-            .items
-            .iter()
-            .map(|e| {
-                serde_json::json!({
-                    "timestamp": e.timestamp,
-                    "message": e.message,
-                })
-                .to_string()
-            })
-            .collect();
-        let content = lines.join("\n") + if lines.is_empty() { "" } else { "\n" };
-        match std::fs::write(path, content) {
-            Ok(_) => self.download_status = Some(format!("Saved: {}", path)),
-            Err(e) => self.download_status = Some(format!("Error: {}", e)),
+    /// 現在の取得条件を返します（追加ロード・書き出しで使用）。
+    fn source(&self) -> ExportSource {
+        ExportSource {
+            group: self.group_name.clone(),
+            stream: (!self.stream_name.is_empty()).then(|| self.stream_name.clone()),
+            start_ms: self.start_ms,
+            end_ms: None,
+            pattern: self.filter_input.clone(),
         }
     }
 }
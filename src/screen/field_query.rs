@@ -0,0 +1,128 @@
+//! ロード済みイベントに対するフィールドクエリの入力状態。
+//!
+//! `J` でクエリ入力モードに入り、確定したクエリで JSON メッセージを絞り込みます。
+//! クエリが値を返す場合は、その値をイベントの表示行として保持します。
+
+use crossterm::event::KeyCode;
+use std::collections::HashMap;
+
+use crate::app::LogEvent;
+use crate::filter::PatternError;
+use crate::query::{Query, QueryResult};
+
+/// イベント一覧のフィールドクエリの状態。
+pub struct FieldQuery {
+    /// クエリ入力モードがアクティブかどうか
+    pub editing: bool,
+    /// クエリ入力バッファ
+    pub buffer: String,
+    /// 入力中のクエリの構文エラー
+    pub error: Option<PatternError>,
+    /// 現在適用中のクエリ文字列（`None` はクエリなし）
+    pub applied: Option<String>,
+    /// 現在適用中のクエリ
+    query: Option<Query>,
+    /// クエリが値を返したイベントの表示行（`items` 内インデックスをキーとする）
+    projections: HashMap<usize, String>,
+}
+
+impl FieldQuery {
+    /// クエリなしの [`FieldQuery`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            editing: false,
+            buffer: String::new(),
+            error: None,
+            applied: None,
+            query: None,
+            projections: HashMap::new(),
+        }
+    }
+
+    /// 現在のクエリを入力バッファに展開して入力モードを開始します。
+    pub fn start(&mut self) {
+        self.editing = true;
+        self.buffer = self.applied.clone().unwrap_or_default();
+        self.error = None;
+    }
+
+    /// 入力モード中のキーを処理します。適用中のクエリが変わった場合は `true` を返します。
+    ///
+    /// 入力の度に構文を検証し、`Enter` で確定（空の場合はクエリを解除）、`Esc` で変更を破棄します。
+    /// 構文エラーがある間は確定できません。
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Enter => {
+                if self.buffer.trim().is_empty() {
+                    self.editing = false;
+                    self.applied = None;
+                    self.query = None;
+                    return true;
+                }
+                match Query::parse(&self.buffer) {
+                    Ok(query) => {
+                        self.editing = false;
+                        self.applied = Some(self.buffer.clone());
+                        self.query = Some(query);
+                        return true;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            KeyCode::Esc => {
+                self.editing = false;
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                self.validate();
+            }
+            KeyCode::Char(c) => {
+                self.buffer.push(c);
+                self.validate();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// クエリが適用中かどうかを返します。
+    pub fn is_active(&self) -> bool {
+        self.query.is_some()
+    }
+
+    /// 表示行の再計算を始める前に、前回の結果を破棄します。
+    pub fn reset(&mut self) {
+        self.projections.clear();
+    }
+
+    /// イベントが表示対象かどうかを返し、値を返した場合は表示行を記録します。
+    ///
+    /// `index` は `items` 内のインデックスです。クエリが無い場合は常に `true` を返します。
+    pub fn accepts(&mut self, index: usize, event: &LogEvent) -> bool {
+        let Some(query) = &self.query else {
+            return true;
+        };
+        match query.apply(&event.message) {
+            QueryResult::Excluded => false,
+            QueryResult::Matched => true,
+            QueryResult::Projected(line) => {
+                self.projections.insert(index, line);
+                true
+            }
+        }
+    }
+
+    /// イベントの表示行を返します。クエリが値を返していない場合は `None` を返します。
+    pub fn projection(&self, index: usize) -> Option<&str> {
+        self.projections.get(&index).map(String::as_str)
+    }
+
+    fn validate(&mut self) {
+        self.error = if self.buffer.trim().is_empty() {
+            None
+        } else {
+            Query::parse(&self.buffer).err()
+        };
+    }
+}
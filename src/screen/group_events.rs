@@ -6,29 +6,42 @@
 
 use anyhow::Result;
 use aws_sdk_cloudwatchlogs::Client;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 use std::sync::Arc;
 
+use super::download_prompt::DownloadPrompt;
+use super::event_view::EventView;
+use super::paging;
 use super::{CurrentScreen, NavigateTo, ScreenAction};
 use crate::app::{LogEvent, StatefulList};
-use crate::aws;
+use crate::export::{self, ExportSource};
+use crate::time;
 
 /// ロググループ全体を横断して検索したイベント一覧を表示するスクリーン。
 ///
-/// `j`/`k` でリスト移動、`Enter` で詳細表示、`q` で前の画面に戻ります。
+/// `j`/`k` でリスト移動（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`Enter` で詳細表示、
+/// `?` でローカル検索（`n`/`N` で一致箇所を移動）、`f` でレベルファセット操作、`c` で JSON フィールド列の指定、`m` でブックマーク、
+/// `B` でブックマーク一覧、`D` で 2 つのイベントの差分表示、`u` で重複の折りたたみ、
+/// `t` で指定時刻へ移動、`P` でテンプレート一覧、`d` でダウンロードパス入力、`x` で全ページのダウンロードを中断、
+/// `z` で表示タイムゾーン切替、
+/// `q` で前の画面に戻ります。
 pub struct GroupEventsScreen {
     /// 共有 AWS CloudWatch Logs クライアント
     pub client: Arc<Client>,
     /// 検索結果のログイベントリスト状態
     pub log_events: StatefulList<LogEvent>,
+    /// ロード済みイベントに対するローカル表示状態（検索・レベルファセット・フィールド列）
+    pub view: EventView,
     /// 検索対象のロググループ名
     pub group_name: String,
-    /// UI 表示用の検索開始時刻文字列
-    pub start_display: String,
-    /// UI 表示用の検索終了時刻文字列
-    pub end_display: String,
-    /// UI 表示用のフィルタパターン文字列
-    pub pattern_display: String,
+    /// 検索開始時刻（Unix ミリ秒、追加ロードで使用）
+    pub start_ms: Option<i64>,
+    /// 検索終了時刻（Unix ミリ秒、追加ロードで使用）
+    pub end_ms: Option<i64>,
+    /// CloudWatch Logs フィルタパターン（追加ロードで使用）
+    pub pattern: Option<String>,
+    /// ダウンロードパス入力の状態
+    pub download: DownloadPrompt,
     /// 前の画面（`q` で戻るため保持）
     pub origin: Option<Box<CurrentScreen>>,
 }
@@ -36,36 +49,64 @@ pub struct GroupEventsScreen {
 impl GroupEventsScreen {
     /// 新しい [`GroupEventsScreen`] を生成します。
     ///
-    /// `start_display`・`end_display`・`pattern_display` は UI 表示用の文字列です。
-    pub fn new(
-        client: Arc<Client>,
-        group_name: String,
-        start_display: String,
-        end_display: String,
-        pattern_display: String,
-        origin: Box<CurrentScreen>,
-    ) -> Self {
+    /// 検索条件は [`GroupEventsScreen::load_group_events`] で設定します。
+    pub fn new(client: Arc<Client>, group_name: String, origin: Box<CurrentScreen>) -> Self {
         Self {
             client,
             log_events: StatefulList::new(),
+            view: EventView::new(&group_name),
             group_name,
-            start_display,
-            end_display,
-            pattern_display,
+            start_ms: None,
+            end_ms: None,
+            pattern: None,
+            download: DownloadPrompt::new(),
             origin: Some(origin),
         }
     }
 
     /// キー入力を処理して [`ScreenAction`] を返します。
-    pub async fn handle_key(&mut self, code: KeyCode) -> Result<ScreenAction> {
+    ///
+    /// ダウンロードパス入力モードを優先的に処理し、
+    /// 一覧画面共通のキー（ローカル検索・レベルファセット・ブックマークなど）は [`EventView`] で処理します。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        self.download.clear_status();
+        if self.download.editing {
+            if let Some(target) = self.download.handle_key(code) {
+                self.download.run(
+                    target,
+                    &self.client,
+                    self.source(),
+                    self.view.columns.columns.clone(),
+                    &self.log_events.items,
+                );
+            }
+            return Ok(ScreenAction::None);
+        }
+        if self.view.handle_key(key, &mut self.log_events) {
+            if let Some(ms) = self.view.jump.take_target() {
+                self.jump_to_time(ms);
+            }
+            return Ok(ScreenAction::None);
+        }
         match code {
             KeyCode::Char('q') => {
                 if let Some(origin) = self.origin.take() {
                     return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
                 }
             }
-            KeyCode::Char('j') | KeyCode::Down => self.log_events.next(),
-            KeyCode::Char('k') | KeyCode::Up => self.log_events.previous(),
+            KeyCode::Char('d') => self
+                .download
+                .start(export::default_path(&self.group_name), true),
+            KeyCode::Char('x') => self.download.cancel(),
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            KeyCode::Char('B') => return Ok(ScreenAction::Navigate(NavigateTo::NewBookmarks)),
+            KeyCode::Char('P') => return Ok(ScreenAction::Navigate(NavigateTo::NewPatterns)),
+            KeyCode::Char('D') => {
+                if let Some((left, right)) = self.view.mark_diff(&self.log_events) {
+                    return Ok(ScreenAction::Navigate(NavigateTo::NewDiff { left, right }));
+                }
+            }
             KeyCode::Enter => {
                 if let Some(event) = self.log_events.selected().cloned() {
                     return Ok(ScreenAction::Navigate(NavigateTo::NewViewer { event }));
@@ -89,23 +130,43 @@ impl GroupEventsScreen {
         end_ms: Option<i64>,
         pattern: Option<String>,
     ) -> Result<()> {
-        self.log_events.loading = true;
-        let (events, token) = aws::fetch_log_events(
-            &self.client,
-            &self.group_name,
-            None,
-            start_ms,
-            end_ms,
-            pattern,
-            None,
-        )
-        .await?;
-        self.log_events.items = events;
-        self.log_events.next_token = token;
-        self.log_events.loading = false;
-        if !self.log_events.items.is_empty() {
-            self.log_events.state.select(Some(0));
+        self.start_ms = start_ms;
+        self.end_ms = end_ms;
+        self.pattern = pattern;
+        let source = self.source();
+        paging::load_first(&self.client, &source, &mut self.log_events, &mut self.view).await
+    }
+
+    /// 表示対象のうち `ms` 以降で最初のイベントへ移動します。
+    ///
+    /// ロード済みのイベントに見つからず未読み込みのページが残っている場合と、
+    /// 検索開始時刻より前の時刻を指定した場合は、`ms` を検索開始時刻として読み込み直します
+    /// （ページネーションで毎フレーム 1 ページずつ読み込み、`Esc` で中止できます）。
+    fn jump_to_time(&mut self, ms: i64) {
+        if self
+            .view
+            .jump_to_time(&mut self.log_events, ms, self.start_ms, true)
+        {
+            self.start_ms = Some(ms);
+        }
+    }
+
+    /// カーソルが末尾付近に達した場合などにページネーションで追加ロードします（[`paging::check_pagination`]）。
+    ///
+    /// メインループ毎フレームで呼び出されます。
+    pub async fn check_pagination(&mut self) {
+        let source = self.source();
+        paging::check_pagination(&self.client, &source, &mut self.log_events, &mut self.view).await;
+    }
+
+    /// 現在の取得条件を返します（追加ロード・書き出しで使用）。
+    fn source(&self) -> ExportSource {
+        ExportSource {
+            group: self.group_name.clone(),
+            stream: None,
+            start_ms: self.start_ms,
+            end_ms: self.end_ms,
+            pattern: self.pattern.clone(),
         }
-        Ok(())
     }
 }
@@ -0,0 +1,120 @@
+//! イベント量のヒストグラム。
+//!
+//! 表示対象のイベントを時間で等分したバケットに集計し、`H` でバケットを選択して
+//! そのバケットの先頭イベントへ移動できるようにします。
+
+use crossterm::event::KeyCode;
+
+use crate::app::{LogEvent, StatefulList};
+use crate::level::Level;
+
+/// 時間バケットごとのレベル別イベント件数。
+pub struct Buckets {
+    /// 先頭バケットの開始時刻（Unix ミリ秒）
+    pub start_ms: i64,
+    /// 1バケットの幅（ミリ秒、1 以上）
+    pub span_ms: i64,
+    /// バケットごとのレベル別件数（[`Level::ALL`] の順）
+    pub counts: Vec<[usize; Level::ALL.len()]>,
+}
+
+impl Buckets {
+    /// イベントを `n` 個のバケットに集計します。イベントが無い場合は `None` を返します。
+    pub fn compute(events: &[&LogEvent], n: usize) -> Option<Self> {
+        let n = n.max(1);
+        let min = events.iter().map(|e| e.timestamp).min()?;
+        let max = events.iter().map(|e| e.timestamp).max()?;
+        // 末尾のイベントが最後のバケットに収まるよう切り上げる
+        let span_ms = ((max - min) / n as i64 + 1).max(1);
+        let mut buckets = Self {
+            start_ms: min,
+            span_ms,
+            counts: vec![[0; Level::ALL.len()]; n],
+        };
+        for e in events {
+            let i = buckets.index_of(e.timestamp);
+            buckets.counts[i][e.level.index()] += 1;
+        }
+        Some(buckets)
+    }
+
+    /// 時刻が属するバケットのインデックスを返します。
+    pub fn index_of(&self, ts_ms: i64) -> usize {
+        let i = (ts_ms - self.start_ms).max(0) / self.span_ms;
+        (i as usize).min(self.counts.len() - 1)
+    }
+
+    /// バケットの合計件数を返します。
+    pub fn total(&self, i: usize) -> usize {
+        self.counts[i].iter().sum()
+    }
+}
+
+/// ヒストグラムの操作状態。
+pub struct Histogram {
+    /// バケット選択モードがアクティブかどうか
+    pub focused: bool,
+    /// 選択中のバケット
+    pub cursor: usize,
+    /// バケット数（描画時に表示幅に合わせて更新します）
+    pub buckets: usize,
+}
+
+impl Histogram {
+    /// 新しい [`Histogram`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            focused: false,
+            cursor: 0,
+            buckets: 1,
+        }
+    }
+
+    /// バケット選択モードを開始し、選択中のイベントのバケットにカーソルを合わせます。
+    pub fn start(&mut self, list: &StatefulList<LogEvent>) {
+        let visible = list.visible_items();
+        let Some(buckets) = Buckets::compute(&visible, self.buckets) else {
+            return;
+        };
+        self.focused = true;
+        self.cursor = list.selected().map_or(0, |e| buckets.index_of(e.timestamp));
+    }
+
+    /// バケット選択モード中のキーを処理します。
+    ///
+    /// `h`/`l` でバケット移動、`0`/`$` で先頭・末尾のバケット、`Enter` でバケットの先頭イベントへ
+    /// 移動して終了、`H`/`Esc` で終了します。
+    pub fn handle_key(&mut self, code: KeyCode, list: &mut StatefulList<LogEvent>) {
+        let last = self.buckets.saturating_sub(1);
+        match code {
+            KeyCode::Char('h') | KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Char('l') | KeyCode::Right => self.cursor = (self.cursor + 1).min(last),
+            KeyCode::Char('0') | KeyCode::Home => self.cursor = 0,
+            KeyCode::Char('$') | KeyCode::End => self.cursor = last,
+            KeyCode::Enter => {
+                self.jump(list);
+                self.focused = false;
+            }
+            KeyCode::Char('H') | KeyCode::Esc => self.focused = false,
+            _ => {}
+        }
+    }
+
+    /// 選択中のバケット（空の場合は以降で最初にイベントのあるバケット）の先頭イベントを選択します。
+    fn jump(&self, list: &mut StatefulList<LogEvent>) {
+        let visible = list.visible_items();
+        let Some(buckets) = Buckets::compute(&visible, self.buckets) else {
+            return;
+        };
+        let target = visible
+            .iter()
+            .enumerate()
+            .map(|(pos, e)| (pos, buckets.index_of(e.timestamp)))
+            .filter(|&(_, b)| b >= self.cursor)
+            .min_by_key(|&(pos, b)| (b, pos))
+            .map(|(pos, _)| pos);
+        if let Some(pos) = target {
+            list.state.select(Some(pos));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! ログレベルごとの件数集計と表示・非表示の切り替え。
+
+use crossterm::event::KeyCode;
+
+use crate::app::LogEvent;
+use crate::level::Level;
+
+/// イベント一覧上部のレベルファセットの状態。
+///
+/// 無効化したレベルのイベントはリストから除外されます（再取得は行いません）。
+pub struct LevelFacets {
+    /// レベルごとの表示有無（[`Level::ALL`] の順）
+    pub enabled: [bool; Level::ALL.len()],
+    /// ファセット操作モードがアクティブかどうか
+    pub focused: bool,
+    /// ファセット操作モードでのカーソル位置（[`Level::ALL`] のインデックス）
+    pub cursor: usize,
+}
+
+impl LevelFacets {
+    /// 全レベルを表示する [`LevelFacets`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            enabled: [true; Level::ALL.len()],
+            focused: false,
+            cursor: 0,
+        }
+    }
+
+    /// いずれかのレベルが非表示になっているかどうかを返します。
+    pub fn is_filtering(&self) -> bool {
+        self.enabled.iter().any(|e| !e)
+    }
+
+    /// イベントが表示対象かどうかを返します。
+    pub fn accepts(&self, event: &LogEvent) -> bool {
+        self.enabled[event.level.index()]
+    }
+
+    /// レベルごとのイベント件数を集計します。
+    pub fn counts(events: &[LogEvent]) -> [usize; Level::ALL.len()] {
+        let mut counts = [0; Level::ALL.len()];
+        for e in events {
+            counts[e.level.index()] += 1;
+        }
+        counts
+    }
+
+    /// ファセット操作モード中のキーを処理します。表示対象が変わった場合は `true` を返します。
+    ///
+    /// `h`/`l` でカーソル移動、`Space`/`Enter` で切り替え、`o` でカーソル位置のレベルのみ表示、
+    /// `a` で全レベル表示、`1`〜`7` で対応するレベルを直接切り替え、`f`/`Esc` で終了します。
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        let n = Level::ALL.len();
+        match code {
+            KeyCode::Char('f') | KeyCode::Esc => self.focused = false,
+            KeyCode::Char('h') | KeyCode::Left => self.cursor = (self.cursor + n - 1) % n,
+            KeyCode::Char('l') | KeyCode::Right => self.cursor = (self.cursor + 1) % n,
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                self.enabled[self.cursor] = !self.enabled[self.cursor];
+                return true;
+            }
+            KeyCode::Char('o') => {
+                self.enabled = [false; Level::ALL.len()];
+                self.enabled[self.cursor] = true;
+                return true;
+            }
+            KeyCode::Char('a') => {
+                self.enabled = [true; Level::ALL.len()];
+                return true;
+            }
+            KeyCode::Char(c @ '1'..='7') => {
+                let i = c as usize - '1' as usize;
+                self.cursor = i;
+                self.enabled[i] = !self.enabled[i];
+                return true;
+            }
+            _ => {}
+        }
+        false
+    }
+}
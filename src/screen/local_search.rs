@@ -0,0 +1,226 @@
+//! ロード済みイベントに対するクライアント側インクリメンタル検索。
+//!
+//! サーバ側のフィルタ（`/`）とは独立して、既に取得済みのイベント本文を
+//! プレーンテキストまたは正規表現で検索し、一致箇所間を移動します。
+
+use crossterm::event::KeyCode;
+use regex::{Regex, RegexBuilder};
+
+use crate::app::{LogEvent, StatefulList};
+
+/// 検索クエリの解釈方法。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchMode {
+    /// プレーンテキスト（クエリに大文字が含まれない場合は大文字小文字を区別しない）
+    Text,
+    /// 正規表現
+    Regex,
+}
+
+/// イベント一覧画面で使用するローカル検索の状態。
+///
+/// 一致位置は `visible_items()` 上のインデックスで保持するため、
+/// 絞り込み表示中のリストにもそのまま適用できます。
+pub struct LocalSearch {
+    /// 検索クエリ入力モードがアクティブかどうか
+    pub editing: bool,
+    /// 検索クエリ入力バッファ
+    pub buffer: String,
+    /// クエリの解釈方法
+    pub mode: SearchMode,
+    /// 現在有効な検索パターン（`None` は検索なし）
+    pub matcher: Option<Regex>,
+    /// 一致したイベントの表示位置一覧（昇順）
+    pub matches: Vec<usize>,
+    /// `matches` 内の現在位置
+    pub current: Option<usize>,
+    /// 正規表現のコンパイルエラーメッセージ
+    pub error: Option<String>,
+    /// 入力開始時の選択位置（`Esc` で復元するため保持）
+    origin_selection: Option<usize>,
+    /// 入力開始前の確定済みクエリ（`Esc` で復元するため保持）
+    saved: Option<(String, SearchMode)>,
+}
+
+impl LocalSearch {
+    /// 空の [`LocalSearch`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            editing: false,
+            buffer: String::new(),
+            mode: SearchMode::Text,
+            matcher: None,
+            matches: Vec::new(),
+            current: None,
+            error: None,
+            origin_selection: None,
+            saved: None,
+        }
+    }
+
+    /// 検索クエリ入力モードを開始します。
+    pub fn start(&mut self, list: &StatefulList<LogEvent>) {
+        self.editing = true;
+        self.origin_selection = list.state.selected();
+        self.saved = self
+            .matcher
+            .as_ref()
+            .map(|_| (self.buffer.clone(), self.mode));
+    }
+
+    /// 入力モード中のキーを処理します。入力の度に一致箇所を再計算してカーソルを移動します。
+    ///
+    /// `Tab` でテキスト／正規表現を切り替え、`Enter` で確定、`Esc` で入力前の状態に戻します。
+    pub fn handle_key(&mut self, code: KeyCode, list: &mut StatefulList<LogEvent>) {
+        match code {
+            KeyCode::Enter => {
+                self.editing = false;
+                if self.buffer.is_empty() {
+                    self.clear();
+                }
+            }
+            KeyCode::Esc => {
+                self.editing = false;
+                match self.saved.take() {
+                    Some((query, mode)) => {
+                        self.buffer = query;
+                        self.mode = mode;
+                        self.compile();
+                        self.refresh(list);
+                    }
+                    None => self.clear(),
+                }
+                list.state.select(self.origin_selection);
+            }
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    SearchMode::Text => SearchMode::Regex,
+                    SearchMode::Regex => SearchMode::Text,
+                };
+                self.update(list);
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                self.update(list);
+            }
+            KeyCode::Char(c) => {
+                self.buffer.push(c);
+                self.update(list);
+            }
+            _ => {}
+        }
+    }
+
+    /// 検索状態をクリアします。
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.matcher = None;
+        self.matches.clear();
+        self.current = None;
+        self.error = None;
+    }
+
+    /// 検索が有効（確定済みまたは入力中で一致判定可能）かどうかを返します。
+    pub fn is_active(&self) -> bool {
+        self.matcher.is_some()
+    }
+
+    /// リストの内容が変わった後に一致箇所を再計算します。カーソルは移動しません。
+    pub fn refresh(&mut self, list: &StatefulList<LogEvent>) {
+        let Some(re) = &self.matcher else {
+            self.matches.clear();
+            self.current = None;
+            return;
+        };
+        self.matches = list
+            .visible_items()
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| re.is_match(&e.message))
+            .map(|(i, _)| i)
+            .collect();
+        let selected = list.state.selected().unwrap_or(0);
+        self.current = self
+            .matches
+            .iter()
+            .position(|&m| m >= selected)
+            .or_else(|| self.matches.len().checked_sub(1));
+    }
+
+    /// 現在のカーソル位置より後ろの一致箇所へ移動します（末尾では先頭に戻ります）。
+    pub fn next_match(&mut self, list: &mut StatefulList<LogEvent>) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let selected = list.state.selected();
+        let i = match selected {
+            Some(sel) => self.matches.iter().position(|&m| m > sel).unwrap_or(0),
+            None => 0,
+        };
+        self.jump(i, list);
+    }
+
+    /// 現在のカーソル位置より前の一致箇所へ移動します（先頭では末尾に戻ります）。
+    pub fn prev_match(&mut self, list: &mut StatefulList<LogEvent>) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        let i = match list.state.selected() {
+            Some(sel) => self.matches.iter().rposition(|&m| m < sel).unwrap_or(last),
+            None => last,
+        };
+        self.jump(i, list);
+    }
+
+    /// 検索状態の概要（`[2/15]` など）を返します。検索が無効な場合は `None` を返します。
+    pub fn status(&self) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+        Some(match self.current {
+            Some(i) if !self.matches.is_empty() => format!("[{}/{}]", i + 1, self.matches.len()),
+            _ => "[0/0]".to_string(),
+        })
+    }
+
+    fn jump(&mut self, i: usize, list: &mut StatefulList<LogEvent>) {
+        self.current = Some(i);
+        list.state.select(Some(self.matches[i]));
+    }
+
+    /// クエリを再コンパイルし、入力開始位置以降の最初の一致箇所へカーソルを移動します。
+    fn update(&mut self, list: &mut StatefulList<LogEvent>) {
+        self.compile();
+        list.state.select(self.origin_selection);
+        self.refresh(list);
+        if let Some(i) = self.current {
+            self.jump(i, list);
+        }
+    }
+
+    fn compile(&mut self) {
+        self.error = None;
+        if self.buffer.is_empty() {
+            self.matcher = None;
+            return;
+        }
+        let built = match self.mode {
+            SearchMode::Text => {
+                // smart case: 大文字を含む場合のみ大文字小文字を区別する
+                let case_insensitive = !self.buffer.chars().any(char::is_uppercase);
+                RegexBuilder::new(&regex::escape(&self.buffer))
+                    .case_insensitive(case_insensitive)
+                    .build()
+            }
+            SearchMode::Regex => Regex::new(&self.buffer),
+        };
+        match built {
+            Ok(re) => self.matcher = Some(re),
+            Err(e) => {
+                self.matcher = None;
+                self.error = Some(e.to_string().lines().last().unwrap_or("").to_string());
+            }
+        }
+    }
+}
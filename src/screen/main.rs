@@ -1,21 +1,29 @@
 //! メインスクリーンの状態管理。
 //!
 //! ロググループとログストリームの二ペイン表示を管理し、
-//! キーボードナビゲーションとインクリメンタル検索を処理します。
+//! キーボードナビゲーションとインクリメンタル検索、ローカルファイルのオープン、
+//! ロググループのアーカイブを処理します。
 
 use anyhow::Result;
 use aws_sdk_cloudwatchlogs::Client;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 use std::sync::Arc;
 
+use super::archive_prompt::ArchivePrompt;
+use super::motion::{Keystroke, MotionKeys};
 use super::{NavigateTo, ScreenAction};
 use crate::app::{ActivePanel, LogGroup, LogStream, StatefulList};
 use crate::aws;
+use crate::offline;
+use crate::paths;
+use crate::time;
 
 /// ロググループとログストリームを表示するメインスクリーン。
 ///
-/// `h`/`l` でパネル切替、`j`/`k` でリスト移動、`/` で検索、
-/// `Enter` でイベント一覧へ遷移、`g` でイベント検索フォームへ遷移します。
+/// `h`/`l` でパネル切替、`j`/`k` でリスト移動（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`/` で検索、
+/// `Enter` でイベント一覧へ遷移、`s` でイベント検索フォームへ遷移、
+/// `o` でダウンロード済み JSONL ファイルを開き（`Tab` でパスを補完）、`a` で選択中のロググループをアーカイブ（`x` で中断）、
+/// `B` でブックマーク一覧へ遷移、`z` で表示タイムゾーンを切り替えます。
 pub struct MainScreen {
     /// 共有 AWS CloudWatch Logs クライアント
     pub client: Arc<Client>,
@@ -31,6 +39,18 @@ pub struct MainScreen {
     pub main_search_active: bool,
     /// ストリームリロードのトリガー検出用の前回選択グループインデックス
     pub last_selected_group: Option<usize>,
+    /// ファイルパス入力モードがアクティブかどうか
+    pub open_editing: bool,
+    /// 開く JSONL ファイルのパス入力バッファ
+    pub open_path_buffer: String,
+    /// ファイル読み込みエラーメッセージ
+    pub open_error: Option<String>,
+    /// `Tab` で補完した際の候補（候補が複数の場合）
+    pub open_candidates: Vec<String>,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// ロググループのアーカイブの入力状態と実行中のアーカイブ
+    pub archive: ArchivePrompt,
 }
 
 impl MainScreen {
@@ -44,14 +64,65 @@ impl MainScreen {
             main_search_query: String::new(),
             main_search_active: false,
             last_selected_group: None,
+            open_editing: false,
+            open_path_buffer: String::new(),
+            open_error: None,
+            open_candidates: Vec::new(),
+            keys: MotionKeys::new(),
+            archive: ArchivePrompt::new(),
         }
     }
 
     /// キー入力を処理して [`ScreenAction`] を返します。
     ///
-    /// 検索モード中は文字入力・バックスペース・Esc・Enter のみを受け付けます。
+    /// 検索モード・ファイルパス入力モード・アーカイブ入力モード中は入力用のキーのみを受け付けます。
     /// 通常モードでは vim ライクなキーバインドで操作します。
-    pub async fn handle_key(&mut self, code: KeyCode) -> Result<ScreenAction> {
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        self.archive.clear_finished();
+        if self.archive.editing {
+            if self.archive.handle_key(code) {
+                self.archive.spawn(Arc::clone(&self.client));
+            }
+            return Ok(ScreenAction::None);
+        }
+        if self.open_editing {
+            self.open_candidates.clear();
+            match code {
+                KeyCode::Enter => {
+                    let path = self.open_path_buffer.clone();
+                    match paths::expand(path.trim()).and_then(|p| offline::read_jsonl(&p)) {
+                        Ok(events) => {
+                            self.open_editing = false;
+                            self.open_error = None;
+                            return Ok(ScreenAction::Navigate(NavigateTo::NewOfflineEvents {
+                                path,
+                                events,
+                            }));
+                        }
+                        Err(e) => self.open_error = Some(format!("{:#}", e)),
+                    }
+                }
+                KeyCode::Esc => {
+                    self.open_editing = false;
+                    self.open_error = None;
+                }
+                KeyCode::Tab => {
+                    let completion = paths::complete(&self.open_path_buffer);
+                    self.open_path_buffer = completion.input;
+                    self.open_candidates = completion.candidates;
+                    return Ok(ScreenAction::None);
+                }
+                KeyCode::Backspace => {
+                    self.open_path_buffer.pop();
+                }
+                KeyCode::Char(c) => {
+                    self.open_path_buffer.push(c);
+                }
+                _ => {}
+            }
+            return Ok(ScreenAction::None);
+        }
         if self.main_search_active {
             match code {
                 KeyCode::Esc => {
@@ -78,6 +149,18 @@ impl MainScreen {
             return Ok(ScreenAction::None);
         }
 
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                match self.active_panel {
+                    ActivePanel::Groups => self.log_groups.apply_motion(motion),
+                    ActivePanel::Streams => self.log_streams.apply_motion(motion),
+                }
+                return Ok(ScreenAction::None);
+            }
+            Keystroke::Pending => return Ok(ScreenAction::None),
+            Keystroke::Unhandled => {}
+        }
+
         match code {
             KeyCode::Char('q') => return Ok(ScreenAction::Quit),
             KeyCode::Char('l') => {
@@ -86,29 +169,35 @@ impl MainScreen {
             KeyCode::Char('h') => {
                 self.active_panel = ActivePanel::Groups;
             }
-            KeyCode::Char('j') | KeyCode::Down => match self.active_panel {
-                ActivePanel::Groups => self.log_groups.next(),
-                ActivePanel::Streams => self.log_streams.next(),
-            },
-            KeyCode::Char('k') | KeyCode::Up => match self.active_panel {
-                ActivePanel::Groups => self.log_groups.previous(),
-                ActivePanel::Streams => self.log_streams.previous(),
-            },
             KeyCode::Char('/') => {
                 self.main_search_active = true;
             }
-            KeyCode::Char('g') => {
-                if self.log_groups.state.selected().is_some() && !self.log_groups.items.is_empty() {
-                    let group_name = self
-                        .log_groups
-                        .selected()
-                        .map(|g| g.name.clone())
-                        .unwrap_or_default();
-                    return Ok(ScreenAction::Navigate(NavigateTo::NewEventSearch {
-                        group_name,
-                    }));
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            KeyCode::Char('B') => return Ok(ScreenAction::Navigate(NavigateTo::NewBookmarks)),
+            KeyCode::Char('o') => {
+                self.open_editing = true;
+                self.open_error = None;
+            }
+            KeyCode::Char('a') => {
+                if let Some(group) = self.log_groups.selected() {
+                    let group = group.name.clone();
+                    self.archive.start(group);
                 }
             }
+            KeyCode::Char('x') => self.archive.cancel(),
+            KeyCode::Char('s')
+                if self.log_groups.state.selected().is_some()
+                    && !self.log_groups.items.is_empty() =>
+            {
+                let group_name = self
+                    .log_groups
+                    .selected()
+                    .map(|g| g.name.clone())
+                    .unwrap_or_default();
+                return Ok(ScreenAction::Navigate(NavigateTo::NewEventSearch {
+                    group_name,
+                }));
+            }
             KeyCode::Enter => {
                 if self.active_panel == ActivePanel::Streams
                     && !self.log_streams.items.is_empty()
@@ -207,18 +296,20 @@ impl MainScreen {
         Ok(())
     }
 
-    /// カーソルが末尾付近に達した場合にページネーションで追加ロードします。
+    /// カーソルが末尾付近に達した場合、または末尾へのジャンプで全ページを読み込み中の場合に
+    /// ページネーションで追加ロードします。
     ///
     /// メインループ毎フレームで呼び出されます。
     pub async fn check_pagination(&mut self) -> Result<()> {
         if let Some(idx) = self.log_groups.selected_index() {
             let len = self.log_groups.items.len();
             if len > 0
-                && idx + 5 >= len
+                && (idx + 5 >= len || self.log_groups.load_all)
                 && self.log_groups.next_token.is_some()
                 && !self.log_groups.loading
             {
                 self.load_more_groups().await?;
+                self.log_groups.follow_load_all();
             }
         }
         if self.active_panel == ActivePanel::Streams
@@ -226,11 +317,12 @@ impl MainScreen {
         {
             let len = self.log_streams.items.len();
             if len > 0
-                && idx + 5 >= len
+                && (idx + 5 >= len || self.log_streams.load_all)
                 && self.log_streams.next_token.is_some()
                 && !self.log_streams.loading
             {
                 self.load_more_streams().await?;
+                self.log_streams.follow_load_all();
             }
         }
         Ok(())
@@ -239,7 +331,7 @@ impl MainScreen {
     /// ロググループを初回ロードします（ページ先頭から取得）。
     pub async fn load_log_groups(&mut self) -> Result<()> {
         self.log_groups.loading = true;
-        let (groups, token) = aws::fetch_log_groups(&self.client, None).await?;
+        let (groups, token) = aws::fetch_log_groups(&self.client, None, None).await?;
         self.log_groups.items = groups;
         self.log_groups.next_token = token;
         self.log_groups.loading = false;
@@ -252,7 +344,7 @@ impl MainScreen {
     async fn load_more_groups(&mut self) -> Result<()> {
         self.log_groups.loading = true;
         let token = self.log_groups.next_token.clone();
-        let (groups, next) = aws::fetch_log_groups(&self.client, token).await?;
+        let (groups, next) = aws::fetch_log_groups(&self.client, None, token).await?;
         self.log_groups.items.extend(groups);
         self.log_groups.next_token = next;
         self.log_groups.loading = false;
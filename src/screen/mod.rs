@@ -3,16 +3,34 @@
 //! [`CurrentScreen`] が現在の画面を保持し、
 //! [`ScreenAction`] を通じてメインループへ操作を通知します。
 
+pub mod archive_prompt;
+pub mod bookmarks;
+pub mod column_editor;
+pub mod dedup;
+pub mod diff;
+pub mod download_prompt;
 pub mod event_search;
+pub mod event_view;
 pub mod events;
+pub mod field_query;
 pub mod group_events;
+pub mod histogram;
+pub mod level_facet;
+pub mod local_search;
 pub mod main;
+pub mod motion;
+pub mod paging;
+pub mod patterns;
+pub mod time_jump;
 pub mod viewer;
 
+pub use bookmarks::BookmarksScreen;
+pub use diff::DiffScreen;
 pub use event_search::EventSearchScreen;
 pub use events::EventsScreen;
 pub use group_events::GroupEventsScreen;
 pub use main::MainScreen;
+pub use patterns::PatternsScreen;
 pub use viewer::ViewerScreen;
 
 use crate::app::LogEvent;
@@ -36,6 +54,13 @@ pub enum NavigateTo {
         /// 対象のログストリーム名
         stream_name: String,
     },
+    /// ローカルの JSONL ファイルをイベント一覧画面で開く
+    NewOfflineEvents {
+        /// 読み込んだファイルのパス
+        path: String,
+        /// ファイルから読み込んだ全イベント
+        events: Vec<LogEvent>,
+    },
     /// ログイベント詳細ビューア画面へ遷移する
     NewViewer {
         /// 表示するログイベント
@@ -56,12 +81,17 @@ pub enum NavigateTo {
         end_ms: Option<i64>,
         /// CloudWatch Logs フィルタパターン
         pattern: Option<String>,
-        /// UI 表示用の開始時刻文字列
-        start_display: String,
-        /// UI 表示用の終了時刻文字列
-        end_display: String,
-        /// UI 表示用のパターン文字列
-        pattern_display: String,
+    },
+    /// ブックマーク一覧画面へ遷移する
+    NewBookmarks,
+    /// 現在の一覧画面のテンプレート一覧画面へ遷移する
+    NewPatterns,
+    /// 2 つのログイベントの差分画面へ遷移する
+    NewDiff {
+        /// 比較元のログイベント
+        left: LogEvent,
+        /// 比較先のログイベント
+        right: LogEvent,
     },
     /// 元の画面に戻る
     Restore(Box<CurrentScreen>),
@@ -79,6 +109,12 @@ pub enum CurrentScreen {
     EventSearch(EventSearchScreen),
     /// グループ横断イベント一覧スクリーン
     GroupEvents(GroupEventsScreen),
+    /// ブックマーク一覧スクリーン
+    Bookmarks(BookmarksScreen),
+    /// イベント差分スクリーン
+    Diff(DiffScreen),
+    /// ログテンプレート一覧スクリーン
+    Patterns(PatternsScreen),
     /// 画面遷移中の一時状態（`mem::replace` で使用）
     Transitioning,
 }
@@ -0,0 +1,140 @@
+//! リスト・ビューア共通のカーソル移動キー。
+//!
+//! `gg`/`G`・`Ctrl-d`/`Ctrl-u`・`PageUp`/`PageDown`・`Home`/`End` と、
+//! `50j` のような数値のカウント接頭辞を解釈して [`Motion`] に変換します。
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// カウント接頭辞の上限（桁あふれ防止）
+const MAX_COUNT: usize = 1_000_000;
+
+/// カーソル移動の種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// 指定行数だけ下へ
+    Down(usize),
+    /// 指定行数だけ上へ
+    Up(usize),
+    /// 半ページ × 指定回数だけ下へ
+    HalfPageDown(usize),
+    /// 半ページ × 指定回数だけ上へ
+    HalfPageUp(usize),
+    /// 1 ページ × 指定回数だけ下へ
+    PageDown(usize),
+    /// 1 ページ × 指定回数だけ上へ
+    PageUp(usize),
+    /// 先頭へ
+    First,
+    /// 末尾へ（未ロードのページがあれば全て読み込む）
+    Last,
+    /// 指定行（1 始まり）へ
+    Line(usize),
+}
+
+impl Motion {
+    /// 現在位置 `pos` から移動した先の位置を返します。
+    ///
+    /// `len` は項目数、`page` は 1 ページの行数です。結果は `0..len` に収めます
+    /// （`len` が 0 の場合は 0）。
+    pub fn apply(self, pos: usize, len: usize, page: usize) -> usize {
+        let page = page.max(1);
+        let half = (page / 2).max(1);
+        let target = match self {
+            Self::Down(n) => pos.saturating_add(n),
+            Self::Up(n) => pos.saturating_sub(n),
+            Self::HalfPageDown(n) => pos.saturating_add(half.saturating_mul(n)),
+            Self::HalfPageUp(n) => pos.saturating_sub(half.saturating_mul(n)),
+            Self::PageDown(n) => pos.saturating_add(page.saturating_mul(n)),
+            Self::PageUp(n) => pos.saturating_sub(page.saturating_mul(n)),
+            Self::First => 0,
+            Self::Last => usize::MAX,
+            Self::Line(n) => n.saturating_sub(1),
+        };
+        target.min(len.saturating_sub(1))
+    }
+}
+
+/// [`MotionKeys::handle`] の結果。
+pub enum Keystroke {
+    /// 移動が確定した
+    Motion(Motion),
+    /// カウントや `g` の続きを待っている
+    Pending,
+    /// 移動キーではない（画面側で処理する）
+    Unhandled,
+}
+
+/// カウント接頭辞と `gg` の入力途中の状態。
+#[derive(Default)]
+pub struct MotionKeys {
+    /// 入力中のカウント（0 は未入力）
+    count: usize,
+    /// `g` が 1 回押された状態かどうか
+    pending_g: bool,
+}
+
+impl MotionKeys {
+    /// 入力途中の状態がない [`MotionKeys`] を生成します。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 入力途中のカウントと `g` を破棄します。
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.pending_g = false;
+    }
+
+    /// キー入力を解釈します。移動キー以外の場合は入力途中の状態を破棄して
+    /// [`Keystroke::Unhandled`] を返します。
+    pub fn handle(&mut self, key: KeyEvent) -> Keystroke {
+        let count = self.count.max(1);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let motion = match key.code {
+            KeyCode::Char(c @ '0'..='9')
+                if !ctrl && !self.pending_g && (c != '0' || self.count > 0) =>
+            {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                self.count = (self.count * 10 + digit).min(MAX_COUNT);
+                return Keystroke::Pending;
+            }
+            KeyCode::Char('g') if !ctrl => {
+                if !self.pending_g {
+                    self.pending_g = true;
+                    return Keystroke::Pending;
+                }
+                if self.count > 0 {
+                    Motion::Line(self.count)
+                } else {
+                    Motion::First
+                }
+            }
+            _ if self.pending_g => {
+                // `g` に続く未対応のキーは `g` ごと破棄する
+                self.reset();
+                return Keystroke::Pending;
+            }
+            KeyCode::Char('d') if ctrl => Motion::HalfPageDown(count),
+            KeyCode::Char('u') if ctrl => Motion::HalfPageUp(count),
+            KeyCode::Char('j') | KeyCode::Down if !ctrl => Motion::Down(count),
+            KeyCode::Char('k') | KeyCode::Up if !ctrl => Motion::Up(count),
+            KeyCode::PageDown => Motion::PageDown(count),
+            KeyCode::PageUp => Motion::PageUp(count),
+            KeyCode::Home => Motion::First,
+            KeyCode::End => Motion::Last,
+            KeyCode::Char('G') if !ctrl => {
+                if self.count > 0 {
+                    Motion::Line(self.count)
+                } else {
+                    Motion::Last
+                }
+            }
+            _ => {
+                self.reset();
+                return Keystroke::Unhandled;
+            }
+        };
+        self.reset();
+        Keystroke::Motion(motion)
+    }
+}
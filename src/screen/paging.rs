@@ -0,0 +1,145 @@
+//! イベント一覧画面で共通の、AWS からのイベントの読み込みとページネーション。
+//!
+//! [`EventsScreen`] と [`GroupEventsScreen`] は取得条件（[`ExportSource`]）だけが異なるため、
+//! 初回ロード・追加ロード・指定時刻へ読み込み直しながらの移動をここにまとめます。
+//!
+//! [`EventsScreen`]: crate::screen::events::EventsScreen
+//! [`GroupEventsScreen`]: crate::screen::group_events::GroupEventsScreen
+
+use anyhow::Result;
+use aws_sdk_cloudwatchlogs::Client;
+
+use super::event_view::EventView;
+use super::time_jump::PendingJump;
+use crate::app::{LogEvent, StatefulList};
+use crate::aws;
+use crate::export::ExportSource;
+
+/// `source` の条件で最初のページを取得し、`list` のイベントを置き換えます。
+///
+/// # Errors
+///
+/// - AWS API の呼び出しに失敗した場合
+pub async fn load_first(
+    client: &Client,
+    source: &ExportSource,
+    list: &mut StatefulList<LogEvent>,
+    view: &mut EventView,
+) -> Result<()> {
+    load_page(client, source, list, view, None).await
+}
+
+/// `list` の `next_token` に続くページを取得し、`list` に追加します。
+///
+/// # Errors
+///
+/// - AWS API の呼び出しに失敗した場合
+pub async fn load_more(
+    client: &Client,
+    source: &ExportSource,
+    list: &mut StatefulList<LogEvent>,
+    view: &mut EventView,
+) -> Result<()> {
+    let token = list.next_token.clone();
+    load_page(client, source, list, view, token).await
+}
+
+async fn load_page(
+    client: &Client,
+    source: &ExportSource,
+    list: &mut StatefulList<LogEvent>,
+    view: &mut EventView,
+    token: Option<String>,
+) -> Result<()> {
+    let more = token.is_some();
+    list.loading = true;
+    let result = aws::fetch_log_events(
+        client,
+        &source.group,
+        source.stream.as_deref().into(),
+        source.start_ms,
+        source.end_ms,
+        source.pattern.clone(),
+        token,
+    )
+    .await;
+    list.loading = false;
+    let (events, next) = result?;
+    if more {
+        list.items.extend(events);
+    } else {
+        list.items = events;
+        view.dedup.reset();
+    }
+    list.next_token = next;
+    view.refilter(list);
+    Ok(())
+}
+
+/// 指定時刻へ読み込み直しながら移動している場合は次のページを読み込み、
+/// カーソルが末尾付近に達した場合、または末尾へのジャンプで全ページを読み込み中の場合は
+/// ページネーションで追加ロードします。
+///
+/// メインループ毎フレームで呼び出され、1 回に 1 ページだけ読み込みます。
+/// 読み込みに失敗した場合はエラーを結果メッセージに設定し、それ以上の追加ロードを止めます。
+pub async fn check_pagination(
+    client: &Client,
+    source: &ExportSource,
+    list: &mut StatefulList<LogEvent>,
+    view: &mut EventView,
+) {
+    if list.loading {
+        return;
+    }
+    if let Some(jump) = view.jump.pending {
+        seek(client, source, list, view, jump).await;
+        return;
+    }
+    // 絞り込み中も表示上の位置で判定する
+    if let Some(idx) = list.state.selected() {
+        let len = list.visible_len();
+        if len > 0 && (idx + 5 >= len || list.load_all) && list.next_token.is_some() {
+            match load_more(client, source, list, view).await {
+                Ok(()) => list.follow_load_all(),
+                Err(e) => {
+                    list.next_token = None;
+                    list.load_all = false;
+                    view.notice = Some(format!("Error: {:#}", e));
+                }
+            }
+        }
+    }
+}
+
+/// 指定時刻への移動先を探して 1 ページ読み込みます。
+///
+/// フィルタに一致するイベントが無いページも返るため、見つかるか最後のページに達するまで
+/// 移動先を保持し、次のフレームで続きを読み込みます。
+async fn seek(
+    client: &Client,
+    source: &ExportSource,
+    list: &mut StatefulList<LogEvent>,
+    view: &mut EventView,
+    jump: PendingJump,
+) {
+    let result = if jump.pages == 0 {
+        load_first(client, source, list, view).await
+    } else {
+        load_more(client, source, list, view).await
+    };
+    view.jump.pending = None;
+    if let Err(e) = result {
+        view.notice = Some(format!("Error: {:#}", e));
+    } else if view.select_time(list, jump.ms) {
+        view.notice = None;
+    } else if list.next_token.is_none() {
+        view.notice = Some(EventView::no_events_notice(jump.ms));
+    } else {
+        let jump = PendingJump {
+            pages: jump.pages + 1,
+            ..jump
+        };
+        view.notice = Some(EventView::jump_notice(jump, list.items.len()));
+        view.jump.pending = Some(jump);
+    }
+}
@@ -0,0 +1,90 @@
+//! ログテンプレート一覧スクリーンの状態管理。
+//!
+//! 元の一覧画面でロード済みのイベントを Drain 方式でテンプレートごとにまとめて一覧表示し、
+//! 選択したテンプレートのイベントだけを元の一覧画面で表示できるようにします。
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::motion::{Keystroke, MotionKeys};
+use super::{CurrentScreen, NavigateTo, ScreenAction};
+use crate::app::StatefulList;
+use crate::drain::{Cluster, Drain};
+use crate::time;
+
+/// ロード済みイベントのテンプレートを一覧表示するスクリーン。
+///
+/// `j`/`k` でリスト移動（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`Enter` で選択したテンプレートのイベントに絞り込んで元の画面に戻り、
+/// `z` で表示タイムゾーン切替、`q` で絞り込まずに元の画面に戻ります。
+pub struct PatternsScreen {
+    /// テンプレートのリスト状態（件数の多い順）
+    pub clusters: StatefulList<Cluster>,
+    /// 集計対象のイベント数
+    pub total: usize,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// 前の画面（`q` で戻るため保持）
+    pub origin: Option<Box<CurrentScreen>>,
+}
+
+impl PatternsScreen {
+    /// 元の画面のロード済みイベントからテンプレートを抽出して [`PatternsScreen`] を生成します。
+    pub fn new(origin: Box<CurrentScreen>) -> Self {
+        let events = match origin.as_ref() {
+            CurrentScreen::Events(s) => s.log_events.items.as_slice(),
+            CurrentScreen::GroupEvents(s) => s.log_events.items.as_slice(),
+            _ => &[],
+        };
+        let total = events.len();
+        let mut clusters = StatefulList::new();
+        clusters.items = Drain::cluster(events);
+        if !clusters.items.is_empty() {
+            clusters.state.select(Some(0));
+        }
+        Self {
+            clusters,
+            total,
+            keys: MotionKeys::new(),
+            origin: Some(origin),
+        }
+    }
+
+    /// キー入力を処理して [`ScreenAction`] を返します。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                self.clusters.apply_motion(motion);
+                return Ok(ScreenAction::None);
+            }
+            Keystroke::Pending => return Ok(ScreenAction::None),
+            Keystroke::Unhandled => {}
+        }
+        match code {
+            KeyCode::Char('q') => {
+                if let Some(origin) = self.origin.take() {
+                    return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
+                }
+            }
+            KeyCode::Char('z') => time::toggle_display_zone(),
+            KeyCode::Enter => {
+                if let Some(template) = self.clusters.selected().map(|c| c.template.clone())
+                    && let Some(mut origin) = self.origin.take()
+                {
+                    match origin.as_mut() {
+                        CurrentScreen::Events(s) => {
+                            s.view.set_template(Some(template), &mut s.log_events)
+                        }
+                        CurrentScreen::GroupEvents(s) => {
+                            s.view.set_template(Some(template), &mut s.log_events)
+                        }
+                        _ => {}
+                    }
+                    return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
+                }
+            }
+            _ => {}
+        }
+        Ok(ScreenAction::None)
+    }
+}
@@ -0,0 +1,86 @@
+//! イベント一覧で指定時刻へ移動するための入力状態。
+//!
+//! `t` で時刻入力モードに入り、確定した時刻を画面側に渡します。
+//! ロード済みのイベントに見つからない場合は、読み込み直しながら探す移動先（[`PendingJump`]）として
+//! 保持し、ページネーションで 1 ページずつ読み込みます。
+//! 時刻の形式は [`time::parse_time_spec_to_ms`] を参照してください。
+
+use crossterm::event::KeyCode;
+
+use crate::time;
+
+/// 読み込み直しながら探している移動先。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingJump {
+    /// 移動先の時刻（Unix ミリ秒）
+    pub ms: i64,
+    /// これまでに読み込んだページ数
+    pub pages: usize,
+}
+
+/// 指定時刻へのジャンプの入力状態。
+pub struct TimeJump {
+    /// 時刻入力モードがアクティブかどうか
+    pub editing: bool,
+    /// 時刻入力バッファ
+    pub buffer: String,
+    /// 確定しようとした時刻の解析エラー
+    pub error: Option<String>,
+    /// 確定した移動先の時刻（Unix ミリ秒、画面側で取り出すまで保持）
+    target: Option<i64>,
+    /// 読み込み直しながら探している移動先（`Esc` で中止）
+    pub pending: Option<PendingJump>,
+}
+
+impl TimeJump {
+    /// 入力前の [`TimeJump`] を生成します。
+    pub fn new() -> Self {
+        Self {
+            editing: false,
+            buffer: String::new(),
+            error: None,
+            target: None,
+            pending: None,
+        }
+    }
+
+    /// 前回の入力を残したまま入力モードを開始します。
+    pub fn start(&mut self) {
+        self.editing = true;
+        self.error = None;
+    }
+
+    /// 入力モード中のキーを処理します。
+    ///
+    /// `Enter` で時刻を解析して確定し、`Esc` で入力を中止します。
+    /// 解析できない場合はエラーを表示して入力モードを続けます。
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => match time::parse_time_spec_to_ms(&self.buffer) {
+                Ok(ms) => {
+                    self.editing = false;
+                    self.target = Some(ms);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            },
+            KeyCode::Esc => {
+                self.editing = false;
+                self.error = None;
+            }
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                self.error = None;
+            }
+            KeyCode::Char(c) => {
+                self.buffer.push(c);
+                self.error = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// 確定した移動先の時刻を取り出します。
+    pub fn take_target(&mut self) -> Option<i64> {
+        self.target.take()
+    }
+}
@@ -3,19 +3,36 @@
 //! 選択されたログイベントのメッセージ全文をスクロール表示します。
 
 use anyhow::Result;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
 
+use super::motion::{Keystroke, Motion, MotionKeys};
 use super::{CurrentScreen, NavigateTo, ScreenAction};
 use crate::app::LogEvent;
+use crate::clipboard;
+use crate::json_path::JsonPath;
+use crate::time;
 
 /// ログイベントの詳細を全画面表示するスクリーン。
 ///
-/// `j`/`k` でスクロール、`q` で前の画面に戻ります。
+/// `j`/`k` でスクロール（`gg`/`G`・`Ctrl-d`/`Ctrl-u`・カウント接頭辞などにも対応）、`y` でメッセージ全体をコピー、`Y` で指定フィールドをコピー、
+/// `z` で表示タイムゾーン切替、`q` で前の画面に戻ります。
 pub struct ViewerScreen {
     /// 表示対象のログイベント
     pub selected_event: LogEvent,
     /// 現在のスクロールオフセット（行数）
     pub viewer_scroll: u16,
+    /// 折り返し後の本文の行数（描画時に更新）
+    pub line_count: usize,
+    /// 本文の表示領域の行数（描画時に更新）
+    pub page_size: usize,
+    /// カウント接頭辞と `gg` の入力途中の状態
+    pub keys: MotionKeys,
+    /// コピーするフィールドパスの入力モードがアクティブかどうか
+    pub field_editing: bool,
+    /// フィールドパス入力バッファ
+    pub field_buffer: String,
+    /// コピー結果メッセージ（成功またはエラー）
+    pub status: Option<String>,
     /// 前の画面（`q` で戻るため保持）
     pub origin: Option<Box<CurrentScreen>>,
 }
@@ -26,29 +43,109 @@ impl ViewerScreen {
         Self {
             selected_event: event,
             viewer_scroll: 0,
+            line_count: 0,
+            page_size: 1,
+            keys: MotionKeys::new(),
+            field_editing: false,
+            field_buffer: String::new(),
+            status: None,
             origin: Some(origin),
         }
     }
 
     /// キー入力を処理して [`ScreenAction`] を返します。
     ///
-    /// `j`/`Down` でスクロールダウン、`k`/`Up` でスクロールアップ、
-    /// `q` で前の画面に戻ります。
-    pub async fn handle_key(&mut self, code: KeyCode) -> Result<ScreenAction> {
+    /// `j`/`Down` でスクロールダウン、`k`/`Up` でスクロールアップ（その他の移動キーは [`Motion`] を参照）、
+    /// `y` でメッセージ全体を、`Y` で入力したパスのフィールドをクリップボードにコピーし、
+    /// `z` で表示タイムゾーンを切り替え、`q` で前の画面に戻ります。
+    pub async fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction> {
+        let code = key.code;
+        self.status = None;
+        if self.field_editing {
+            match code {
+                KeyCode::Enter => {
+                    self.field_editing = false;
+                    self.status = Some(match self.copy_field() {
+                        Ok(()) => format!("Copied {}", self.field_buffer.trim()),
+                        Err(e) => format!("Error: {:#}", e),
+                    });
+                }
+                KeyCode::Esc => self.field_editing = false,
+                KeyCode::Backspace => {
+                    self.field_buffer.pop();
+                }
+                KeyCode::Char(c) => self.field_buffer.push(c),
+                _ => {}
+            }
+            return Ok(ScreenAction::None);
+        }
+        match self.keys.handle(key) {
+            Keystroke::Motion(motion) => {
+                self.scroll(motion);
+                return Ok(ScreenAction::None);
+            }
+            Keystroke::Pending => return Ok(ScreenAction::None),
+            Keystroke::Unhandled => {}
+        }
         match code {
             KeyCode::Char('q') => {
                 if let Some(origin) = self.origin.take() {
                     return Ok(ScreenAction::Navigate(NavigateTo::Restore(origin)));
                 }
             }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.viewer_scroll = self.viewer_scroll.saturating_add(1);
+            KeyCode::Char('y') => {
+                self.status = Some(match clipboard::copy(&self.message_text()) {
+                    Ok(()) => "Copied message to clipboard".to_string(),
+                    Err(e) => format!("Error: {:#}", e),
+                });
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.viewer_scroll = self.viewer_scroll.saturating_sub(1);
+            KeyCode::Char('Y') => {
+                if self.json().is_some() {
+                    self.field_editing = true;
+                } else {
+                    self.status = Some("Error: message is not JSON".to_string());
+                }
             }
+            KeyCode::Char('z') => time::toggle_display_zone(),
             _ => {}
         }
         Ok(ScreenAction::None)
     }
+
+    /// スクロール位置を移動します。最終行が表示領域の下端に来る位置より先には進みません。
+    fn scroll(&mut self, motion: Motion) {
+        let positions = self.line_count.saturating_sub(self.page_size) + 1;
+        let pos = motion.apply(self.viewer_scroll as usize, positions, self.page_size);
+        self.viewer_scroll = pos.min(u16::MAX as usize) as u16;
+    }
+
+    /// メッセージを JSON として解析します。JSON でなければ `None` を返します。
+    fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(self.selected_event.message.trim()).ok()
+    }
+
+    /// コピー用のメッセージ本文。JSON なら整形済みの文字列を返します。
+    fn message_text(&self) -> String {
+        self.json()
+            .and_then(|v| serde_json::to_string_pretty(&v).ok())
+            .unwrap_or_else(|| self.selected_event.message.clone())
+    }
+
+    /// 入力されたパスのフィールド値をコピーします。
+    ///
+    /// 文字列はそのまま、それ以外は整形済み JSON としてコピーします。
+    fn copy_field(&self) -> Result<()> {
+        let path = JsonPath::parse(self.field_buffer.trim()).map_err(anyhow::Error::msg)?;
+        let json = self
+            .json()
+            .ok_or_else(|| anyhow::anyhow!("message is not JSON"))?;
+        let value = path
+            .lookup(&json)
+            .ok_or_else(|| anyhow::anyhow!("field not found: {}", path))?;
+        let text = match value {
+            serde_json::Value::String(s) => s.clone(),
+            v => serde_json::to_string_pretty(v)?,
+        };
+        clipboard::copy(&text)
+    }
 }
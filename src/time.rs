@@ -0,0 +1,219 @@
+//! タイムスタンプの表示・解析に使うタイムゾーン設定。
+//!
+//! 全画面で共通の表示タイムゾーンをプロセス全体で 1 つだけ保持し、
+//! 描画時のフォーマットと入力日時の解析の両方でこれを使用します。
+//! 初期値は環境変数 `CLEAM_TZ`（`UTC`・`local`・IANA タイムゾーン名）で指定できます。
+
+use anyhow::{Result, anyhow};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use std::sync::{LazyLock, RwLock};
+
+/// 画面表示・入力で使用する日時フォーマット（`YYYY-MM-DD HH:MM:SS`）
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 表示タイムゾーンを指定する環境変数名
+pub const TZ_ENV_VAR: &str = "CLEAM_TZ";
+
+/// 現在のタイムゾーンと、トグル時に切り替える代替タイムゾーンの組。
+struct ZoneSetting {
+    current: TimeZone,
+    alternate: TimeZone,
+}
+
+impl ZoneSetting {
+    fn new(zone: TimeZone) -> Self {
+        // UTC 指定時の切り替え先はローカル、それ以外の切り替え先は UTC
+        let alternate = if is_utc(&zone) {
+            TimeZone::system()
+        } else {
+            TimeZone::UTC
+        };
+        Self {
+            current: zone,
+            alternate,
+        }
+    }
+}
+
+static DISPLAY_ZONE: LazyLock<RwLock<ZoneSetting>> =
+    LazyLock::new(|| RwLock::new(ZoneSetting::new(TimeZone::UTC)));
+
+/// 環境変数 `CLEAM_TZ` から表示タイムゾーンを初期化します。
+///
+/// 未設定の場合は UTC のままです。
+///
+/// # Errors
+///
+/// - 指定されたタイムゾーン名が解決できない場合
+pub fn init_from_env() -> Result<()> {
+    match std::env::var(TZ_ENV_VAR) {
+        Ok(spec) if !spec.trim().is_empty() => set_display_zone(parse_zone(&spec)?),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// タイムゾーン指定文字列を [`TimeZone`] に変換します。
+///
+/// `UTC`（大文字小文字不問）、`local`（システムのタイムゾーン）、
+/// または `Asia/Tokyo` のような IANA タイムゾーン名を受け付けます。
+///
+/// # Errors
+///
+/// - IANA タイムゾーン名が見つからない場合
+pub fn parse_zone(spec: &str) -> Result<TimeZone> {
+    let spec = spec.trim();
+    if spec.eq_ignore_ascii_case("utc") {
+        Ok(TimeZone::UTC)
+    } else if spec.eq_ignore_ascii_case("local") {
+        Ok(TimeZone::system())
+    } else {
+        TimeZone::get(spec).map_err(|e| anyhow!("Unknown time zone `{}`: {}", spec, e))
+    }
+}
+
+/// 表示タイムゾーンを設定します。トグル先の代替タイムゾーンも再計算されます。
+pub fn set_display_zone(zone: TimeZone) {
+    let mut setting = DISPLAY_ZONE.write().unwrap_or_else(|e| e.into_inner());
+    *setting = ZoneSetting::new(zone);
+}
+
+/// 現在の表示タイムゾーンを返します。
+pub fn display_zone() -> TimeZone {
+    DISPLAY_ZONE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .current
+        .clone()
+}
+
+/// 表示タイムゾーンを UTC とローカル（または設定済みのタイムゾーン）の間で切り替えます。
+pub fn toggle_display_zone() {
+    let mut setting = DISPLAY_ZONE.write().unwrap_or_else(|e| e.into_inner());
+    let ZoneSetting { current, alternate } = &mut *setting;
+    std::mem::swap(current, alternate);
+}
+
+/// 表示タイムゾーンの名前（`UTC`・`Asia/Tokyo` など）を返します。
+///
+/// IANA 名を持たないシステムタイムゾーンの場合は `local` を返します。
+pub fn zone_label() -> String {
+    display_zone().iana_name().unwrap_or("local").to_string()
+}
+
+/// 表示タイムゾーンでの現在時刻を返します。
+pub fn now() -> Zoned {
+    Zoned::now().with_time_zone(display_zone())
+}
+
+/// Unix ミリ秒を `YYYY-MM-DD HH:MM:SS.mmm` 形式（表示タイムゾーン）に変換します。
+pub fn format_ms(ts_ms: i64) -> String {
+    match Timestamp::from_millisecond(ts_ms) {
+        Ok(ts) => {
+            let ms = ts_ms.rem_euclid(1000);
+            format!(
+                "{}.{:03}",
+                ts.to_zoned(display_zone()).strftime(DATETIME_FORMAT),
+                ms
+            )
+        }
+        Err(_) => String::from("0000-00-00 00:00:00.000"),
+    }
+}
+
+/// Unix ミリ秒を `YYYY-MM-DD HH:MM:SS` 形式（表示タイムゾーン）に変換します。
+pub fn format_ms_seconds(ts_ms: i64) -> String {
+    match Timestamp::from_millisecond(ts_ms) {
+        Ok(ts) => format_zoned(&ts.to_zoned(display_zone())),
+        Err(_) => String::from("0000-00-00 00:00:00"),
+    }
+}
+
+/// [`Zoned`] を `YYYY-MM-DD HH:MM:SS` 形式に変換します。
+pub fn format_zoned(zoned: &Zoned) -> String {
+    zoned.strftime(DATETIME_FORMAT).to_string()
+}
+
+/// `YYYY-MM-DD HH:MM:SS` 形式の文字列を表示タイムゾーンで解釈し、Unix ミリ秒に変換します。
+///
+/// # Errors
+///
+/// - 日時の形式が不正な場合
+/// - タイムゾーン変換に失敗した場合
+pub fn parse_datetime_to_ms(s: &str) -> Result<i64> {
+    let iso_str = s.trim().replacen(' ', "T", 1);
+    let dt: jiff::civil::DateTime = iso_str
+        .parse()
+        .map_err(|_| anyhow!("日時のフォーマットが不正です（例: 2024-01-01 12:00:00）"))?;
+    let zoned = dt
+        .to_zoned(display_zone())
+        .map_err(|e| anyhow!("タイムゾーン変換に失敗しました: {}", e))?;
+    Ok(zoned.timestamp().as_millisecond())
+}
+
+/// 絶対時刻または相対時刻を表示タイムゾーンで解釈し、Unix ミリ秒に変換します。
+///
+/// 次の形式を受け付けます。
+///
+/// - `YYYY-MM-DD HH:MM:SS`（秒や時刻は省略可）
+/// - `HH:MM[:SS]`（今日の時刻）
+/// - `now`
+/// - `15m ago`・`-15m`（現在から遡った時刻。単位は `s`・`m`・`h`・`d`・`w`）
+///
+/// # Errors
+///
+/// - いずれの形式にも当てはまらない場合
+/// - タイムゾーン変換に失敗した場合
+pub fn parse_time_spec_to_ms(s: &str) -> Result<i64> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("now") {
+        return Ok(Timestamp::now().as_millisecond());
+    }
+    let relative = s
+        .strip_suffix("ago")
+        .map(str::trim_end)
+        .or_else(|| s.strip_prefix('-'));
+    if let Some(spec) = relative {
+        let ago_ms = parse_duration_ms(spec.trim())?;
+        return Ok(Timestamp::now().as_millisecond() - ago_ms);
+    }
+    if let Ok(ms) = parse_datetime_to_ms(s) {
+        return Ok(ms);
+    }
+    // 日付付きの文字列も時刻として解釈できてしまうため、日時の解析に失敗した場合のみ試す
+    let t: jiff::civil::Time = s.parse().map_err(|_| {
+        anyhow!("時刻のフォーマットが不正です（例: 2024-01-01 12:00・12:00・15m ago）")
+    })?;
+    let zoned = now()
+        .date()
+        .to_datetime(t)
+        .to_zoned(display_zone())
+        .map_err(|e| anyhow!("タイムゾーン変換に失敗しました: {}", e))?;
+    Ok(zoned.timestamp().as_millisecond())
+}
+
+/// `15m` のような「数値 + 単位」の期間をミリ秒に変換します。
+fn parse_duration_ms(spec: &str) -> Result<i64> {
+    let split = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    let (digits, unit) = spec.split_at(split);
+    let n: i64 = digits
+        .parse()
+        .map_err(|_| anyhow!("期間のフォーマットが不正です（例: 15m）"))?;
+    let unit_ms = match unit.trim() {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        other => return Err(anyhow!("不明な期間の単位です: {}", other)),
+    };
+    n.checked_mul(unit_ms)
+        .ok_or_else(|| anyhow!("期間が大きすぎます: {}", spec))
+}
+
+fn is_utc(zone: &TimeZone) -> bool {
+    zone.iana_name() == Some("UTC")
+}
@@ -0,0 +1,70 @@
+use crossterm::style::{Attribute, SetAttribute, SetForegroundColor};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::fmt::Write;
+
+use super::viewer_screen;
+use crate::app::LogEvent;
+use crate::time;
+
+/// One event as printed by `cleam tail`: `time stream message`, the same
+/// layout as text exports. The message is colored like the viewer, by level
+/// keyword; with `pretty`, JSON messages are pretty-printed and highlighted
+/// over the following lines. Without `color` no escape sequences are emitted.
+pub fn event_text(event: &LogEvent, pretty: bool, color: bool) -> String {
+    let mut prefix = vec![Span::styled(
+        time::format_ms(event.timestamp),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if let Some(stream) = &event.stream {
+        prefix.push(Span::raw(" "));
+        prefix.push(Span::styled(
+            stream.clone(),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    prefix.push(Span::raw(" "));
+
+    let message = event.message.trim_end();
+    let mut lines = if pretty {
+        viewer_screen::render_message(message)
+    } else {
+        vec![viewer_screen::plain_line(message)]
+    };
+    if lines.is_empty() {
+        lines.push(Line::default());
+    }
+    prefix.append(&mut lines[0].spans);
+    lines[0].spans = prefix;
+
+    let mut out = String::new();
+    for line in &lines {
+        out.push_str(&to_ansi(line, color));
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a styled line as text with ANSI colors (foreground and bold only).
+fn to_ansi(line: &Line, color: bool) -> String {
+    let mut out = String::new();
+    for span in &line.spans {
+        let fg = span.style.fg.filter(|c| *c != Color::Reset);
+        let bold = span.style.add_modifier.contains(Modifier::BOLD);
+        if !color || (fg.is_none() && !bold) {
+            out.push_str(&span.content);
+            continue;
+        }
+        if let Some(fg) = fg {
+            let _ = write!(out, "{}", SetForegroundColor(fg.into()));
+        }
+        if bold {
+            let _ = write!(out, "{}", SetAttribute(Attribute::Bold));
+        }
+        out.push_str(&span.content);
+        let _ = write!(out, "{}", SetAttribute(Attribute::Reset));
+    }
+    out
+}
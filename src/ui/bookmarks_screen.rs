@@ -0,0 +1,173 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use super::event_list;
+use crate::redact;
+use crate::screen::BookmarksScreen;
+use crate::time;
+
+pub fn draw(f: &mut Frame, screen: &mut BookmarksScreen) {
+    let area = f.area();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // header
+            Constraint::Min(0),    // list
+            Constraint::Length(1), // footer / export input
+        ])
+        .split(area);
+
+    // Header
+    let header = Paragraph::new(format!(
+        " Bookmarks ({})  │  {}{}",
+        screen.bookmarks.items.len(),
+        time::zone_label(),
+        redact::indicator()
+    ))
+    .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    f.render_widget(header, chunks[0]);
+
+    screen.bookmarks.page_size = (chunks[1].height as usize).saturating_sub(2).max(1);
+
+    // Bookmarks list: timestamp, source and first line of the message
+    let block = Block::default()
+        .title(" Bookmarks ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    if screen.bookmarks.items.is_empty() {
+        let msg =
+            Paragraph::new("  No bookmarks. Press [m] on an event to bookmark it.").block(block);
+        f.render_widget(msg, chunks[1]);
+    } else {
+        // subtract: borders(2) + highlight symbol(3) + timestamp(23) + separators(4)
+        let available = (chunks[1].width as usize).saturating_sub(2 + 3 + 23 + 4);
+        let items: Vec<ListItem> = screen
+            .bookmarks
+            .items
+            .iter()
+            .map(|b| {
+                let source = b.source();
+                let message = b
+                    .event
+                    .message
+                    .lines()
+                    .map(str::trim)
+                    .find(|l| !l.is_empty())
+                    .unwrap_or("");
+                let source = event_list::truncate_chars(&source, available / 3);
+                let message = event_list::truncate_chars(
+                    message,
+                    available.saturating_sub(source.chars().count()),
+                );
+                let mut spans = vec![
+                    Span::styled(
+                        time::format_ms(b.event.timestamp),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(source, Style::default().fg(Color::Cyan)),
+                    Span::raw("  "),
+                ];
+                spans.extend(event_list::colorize_level_keyword(&message));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .bg(Color::Rgb(50, 50, 70))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶ ");
+
+        f.render_stateful_widget(list, chunks[1], &mut screen.bookmarks.state);
+    }
+
+    // Footer / export input
+    if screen.export_editing {
+        let line = match &screen.export_confirm {
+            Some(confirm) => event_list::confirm_prompt(confirm, false),
+            None => export_prompt(screen),
+        };
+        let footer = Paragraph::new(line).style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[2]);
+    } else if let Some(status) = &screen.export_status {
+        let fg = if status.starts_with("Error") {
+            Color::Red
+        } else {
+            Color::Green
+        };
+        let footer = Paragraph::new(Line::from(vec![Span::styled(
+            format!(" {}", status),
+            Style::default().fg(fg).add_modifier(Modifier::BOLD),
+        )]))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[2]);
+    } else {
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled(" [j/k ↑↓]", Style::default().fg(Color::Yellow)),
+            Span::raw(" scroll  "),
+            Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+            Span::raw(" open  "),
+            Span::styled("[x]", Style::default().fg(Color::Yellow)),
+            Span::raw(" remove  "),
+            Span::styled("[e]", Style::default().fg(Color::Yellow)),
+            Span::raw(" export  "),
+            Span::styled("[z]", Style::default().fg(Color::Yellow)),
+            Span::raw(" UTC/local  "),
+            Span::styled("[q]", Style::default().fg(Color::Yellow)),
+            Span::raw(" back"),
+        ]))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[2]);
+    }
+}
+
+/// Footer line for the export path prompt (`e`), with the expansion error or
+/// the completion candidates after a `Tab` with several matches.
+fn export_prompt(screen: &BookmarksScreen) -> Line<'static> {
+    let key = |k: &str| Span::styled(k.to_string(), Style::default().fg(Color::DarkGray));
+    let mut spans = vec![
+        Span::styled(
+            " export to: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(screen.export_path_buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::Cyan)),
+        Span::raw("   "),
+    ];
+    if let Some(err) = &screen.export_error {
+        spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if !screen.export_candidates.is_empty() {
+        spans.push(Span::styled(
+            format!("{}  ", screen.export_candidates.join("  ")),
+            Style::default().fg(Color::Yellow),
+        ));
+        return Line::from(spans);
+    }
+    spans.extend([
+        key("(.md → Markdown, otherwise JSONL)  "),
+        key("[Tab]"),
+        Span::raw(" complete  "),
+        key("[Enter]"),
+        Span::raw(" save  "),
+        key("[Esc]"),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
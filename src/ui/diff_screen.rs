@@ -0,0 +1,122 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::app::LogEvent;
+use crate::diff::{Change, Row};
+use crate::redact;
+use crate::screen::DiffScreen;
+use crate::time;
+
+pub fn draw(f: &mut Frame, screen: &mut DiffScreen) {
+    let area = f.area();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // header
+            Constraint::Min(0),    // panes
+            Constraint::Length(1), // footer
+        ])
+        .split(area);
+
+    // Header
+    let kind = if screen.structural {
+        "JSON diff"
+    } else {
+        "Line diff"
+    };
+    let header = Paragraph::new(format!(
+        " {}  │  {} changed line(s)  │  {}{}",
+        kind,
+        screen.change_count(),
+        time::zone_label(),
+        redact::indicator()
+    ))
+    .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    f.render_widget(header, chunks[0]);
+
+    // Side-by-side panes, scrolled together
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
+    screen.page_size = (chunks[1].height as usize).saturating_sub(2).max(1);
+
+    let left = pane(
+        &screen.left,
+        &screen.rows,
+        |r| r.left.as_deref(),
+        ("- ", Color::Red),
+    );
+    let right = pane(
+        &screen.right,
+        &screen.rows,
+        |r| r.right.as_deref(),
+        ("+ ", Color::Green),
+    );
+    f.render_widget(left.scroll((screen.scroll, 0)), panes[0]);
+    f.render_widget(right.scroll((screen.scroll, 0)), panes[1]);
+
+    // Footer
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(" [j/k ↑↓]", Style::default().fg(Color::Yellow)),
+        Span::raw(" scroll  "),
+        Span::styled("[n/N]", Style::default().fg(Color::Yellow)),
+        Span::raw(" next/prev change  "),
+        Span::styled("[z]", Style::default().fg(Color::Yellow)),
+        Span::raw(" UTC/local  "),
+        Span::styled("[q]", Style::default().fg(Color::Yellow)),
+        Span::raw(" back"),
+    ]))
+    .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    f.render_widget(footer, chunks[2]);
+}
+
+/// One side of the diff. Lines missing on this side are left blank so both
+/// panes stay aligned row by row; `only` is the marker and color of lines
+/// present only on this side.
+fn pane<'a>(
+    event: &LogEvent,
+    rows: &'a [Row],
+    side: impl Fn(&'a Row) -> Option<&'a str>,
+    only: (&'static str, Color),
+) -> Paragraph<'a> {
+    let title = match &event.stream {
+        Some(stream) => format!(" {}  {} ", time::format_ms(event.timestamp), stream),
+        None => format!(" {} ", time::format_ms(event.timestamp)),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|row| {
+            let Some(text) = side(row) else {
+                return Line::default();
+            };
+            let (marker, style) = match row.change {
+                Change::Same => ("  ", Style::default()),
+                Change::Changed => (
+                    "~ ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Change::Removed | Change::Added => (only.0, Style::default().fg(only.1)),
+            };
+            Line::from(vec![
+                Span::styled(marker, style),
+                Span::styled(text.replace('\t', "    "), style),
+            ])
+        })
+        .collect();
+
+    Paragraph::new(Text::from(lines)).block(block)
+}
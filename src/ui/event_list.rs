@@ -0,0 +1,821 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::ListItem,
+};
+use regex::Regex;
+
+use crate::app::{LogEvent, StatefulList};
+use crate::bookmarks;
+use crate::columns::Column;
+use crate::download::{DownloadJob, JobState};
+use crate::level::Level;
+use crate::screen::column_editor::ColumnEditor;
+use crate::screen::download_prompt::{Confirm, DownloadPrompt};
+use crate::screen::event_view::EventView;
+use crate::screen::field_query::FieldQuery;
+use crate::screen::histogram::Buckets;
+use crate::screen::level_facet::LevelFacets;
+use crate::screen::local_search::{LocalSearch, SearchMode};
+use crate::screen::time_jump::TimeJump;
+use crate::time;
+
+// timestamp col width: "YYYY-MM-DD HH:MM:SS.mmm" = 23
+const TS_WIDTH: usize = 23;
+
+// bookmark mark "◆ " (or diff base mark "Δ ") before the timestamp
+const GUTTER_WIDTH: usize = 2;
+
+/// Build one list row per event: timestamp + single-line text, with level
+/// keywords colored and local search matches highlighted. The text is the
+/// message, or the field query's projected value when it returned one. When
+/// JSON field columns are configured, their values are inserted between the
+/// timestamp and the text. Collapsed duplicate runs get a `×N` badge with the
+/// time of their last event. Bookmarked events and the diff base get a gutter
+/// mark, and rows in the visual range around `selected` get a selection
+/// background.
+pub fn event_items(
+    rows: &[(&LogEvent, &str)],
+    width: u16,
+    view: &EventView,
+    list: &StatefulList<LogEvent>,
+) -> Vec<ListItem<'static>> {
+    let columns = view.columns.columns.as_slice();
+    let search = view.search.matcher.as_ref();
+    let visual = view.visual_range(list);
+    // subtract: borders(2) + highlight symbol "▶ " (▶ renders as 2 cols + space = 3) + gutter(2)
+    // + separator "  "(2)
+    let available = (width as usize)
+        .saturating_sub(2 + 3 + GUTTER_WIDTH + TS_WIDTH + 2)
+        .saturating_sub(columns_width(columns));
+
+    rows.iter()
+        .enumerate()
+        .map(|(pos, &(e, text))| {
+            let ts = time::format_ms(e.timestamp);
+            let index = list.visible_indices.as_ref().map_or(pos, |v| v[pos]);
+            let badge = view.dedup.run(index).map(|(run, expanded)| {
+                let last = time::format_ms(list.items[run[run.len() - 1]].timestamp);
+                // same-day runs are the common case, so show only the time of day
+                let last = if last[..10] == ts[..10] {
+                    last[11..].to_string()
+                } else {
+                    last
+                };
+                let mark = if expanded { '▾' } else { '×' };
+                format!("{}{} → {}  ", mark, run.len(), last)
+            });
+            let available =
+                available.saturating_sub(badge.as_ref().map_or(0, |b| b.chars().count()));
+            // 全行を trim して空行を除き、スペース区切りで1行に結合
+            let joined = text
+                .lines()
+                .map(|l| l.trim().replace('\t', " "))
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            // 文字数ではなく「表示列数」でtruncate
+            let msg = truncate_chars(&joined, available);
+            let gutter = if view.is_diff_base(e) {
+                Span::styled("Δ ", Style::default().fg(Color::LightMagenta))
+            } else if bookmarks::contains(&view.group, e) {
+                Span::styled("◆ ", Style::default().fg(Color::LightRed))
+            } else {
+                Span::raw("  ")
+            };
+            let mut spans = vec![
+                gutter,
+                Span::styled(ts, Style::default().fg(Color::DarkGray)),
+                Span::raw("  "),
+            ];
+            if let Some(badge) = badge {
+                spans.push(Span::styled(
+                    badge,
+                    Style::default()
+                        .fg(Color::LightMagenta)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if !columns.is_empty() {
+                let json = serde_json::from_str::<serde_json::Value>(&e.message).ok();
+                for column in columns {
+                    let cell = json.as_ref().map(|v| column.cell(v)).unwrap_or_default();
+                    spans.push(Span::styled(
+                        fit_width(&cell, column.width),
+                        Style::default().fg(Color::LightBlue),
+                    ));
+                    spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+                }
+            }
+            let body = colorize_level_keyword(&msg);
+            match search {
+                Some(re) => spans.extend(highlight_matches(body, &msg, re)),
+                None => spans.extend(body),
+            }
+            let item = ListItem::new(Line::from(spans));
+            match visual {
+                Some((from, to)) if (from..=to).contains(&pos) => {
+                    item.style(Style::default().bg(Color::Rgb(40, 60, 40)))
+                }
+                _ => item,
+            }
+        })
+        .collect()
+}
+
+/// Header row naming the JSON field columns, aligned with `event_items`.
+pub fn column_header(columns: &[Column]) -> Line<'static> {
+    let style = Style::default()
+        .fg(Color::Gray)
+        .add_modifier(Modifier::BOLD);
+    // left border(1) + highlight symbol "▶ "(2) + gutter(2)
+    let mut spans = vec![
+        Span::raw("     "),
+        Span::styled(fit_width("timestamp", TS_WIDTH), style),
+        Span::raw("  "),
+    ];
+    for column in columns {
+        spans.push(Span::styled(
+            fit_width(&column.label(), column.width),
+            style,
+        ));
+        spans.push(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
+    }
+    spans.push(Span::styled("message", style));
+    Line::from(spans)
+}
+
+/// Footer line for the column spec prompt (`c`).
+pub fn column_prompt(editor: &ColumnEditor) -> Line<'static> {
+    let mut spans = vec![
+        Span::styled(
+            " columns: ",
+            Style::default()
+                .fg(Color::LightBlue)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(editor.buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::LightBlue)),
+        Span::raw("  "),
+    ];
+    match &editor.error {
+        Some(err) => spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        )),
+        None => spans.push(Span::styled(
+            "path[:width], …  ",
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+    spans.extend([
+        Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" apply & save  "),
+        Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
+
+/// Total display width taken by the column cells and their separators.
+fn columns_width(columns: &[Column]) -> usize {
+    columns.iter().map(|c| c.width + 3).sum()
+}
+
+/// Truncate or right-pad `s` to exactly `width` Unicode scalar values.
+fn fit_width(s: &str, width: usize) -> String {
+    let len = s.chars().count();
+    if len > width {
+        let mut cut: String = s.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    } else {
+        format!("{}{}", s, " ".repeat(width - len))
+    }
+}
+
+/// Footer line for the local search prompt (`?`).
+pub fn search_prompt(search: &LocalSearch) -> Line<'static> {
+    let mode = match search.mode {
+        SearchMode::Text => "text",
+        SearchMode::Regex => "regex",
+    };
+    let mut spans = vec![
+        Span::styled(
+            format!(" search [{}]: ", mode),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(search.buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::Magenta)),
+        Span::raw("  "),
+    ];
+    if let Some(err) = &search.error {
+        spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        ));
+    } else if let Some(status) = search.status() {
+        spans.push(Span::styled(
+            format!("{}  ", status),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+    spans.extend([
+        Span::styled("[Tab]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" text/regex  "),
+        Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" confirm  "),
+        Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
+
+/// Color used for a level in the facet bar.
+pub fn level_color(level: Level) -> Color {
+    match level {
+        Level::Fatal | Level::Error => Color::Red,
+        Level::Warn => Color::Yellow,
+        Level::Info => Color::Green,
+        Level::Debug => Color::Cyan,
+        Level::Trace => Color::Magenta,
+        Level::Unknown => Color::Gray,
+    }
+}
+
+/// One-line facet bar: per-level counts over all loaded events. Hidden
+/// levels are dimmed and struck through; the cursor is reversed while the
+/// facet mode is focused. The active field query, if any, follows the counts.
+pub fn facet_bar(view: &EventView, events: &[LogEvent], shown: usize) -> Line<'static> {
+    let facets = &view.facets;
+    let counts = LevelFacets::counts(events);
+    let mut spans = vec![Span::raw(" ")];
+    for level in Level::ALL {
+        let i = level.index();
+        let mut style = if facets.enabled[i] {
+            Style::default()
+                .fg(level_color(level))
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT)
+        };
+        if facets.focused && facets.cursor == i {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(
+            format!("{}:{}", level.label(), counts[i]),
+            style,
+        ));
+        spans.push(Span::raw("  "));
+    }
+    if let Some(template) = &view.template {
+        spans.push(Span::styled(
+            format!("│ pattern: {}  ", truncate_chars(&template.to_string(), 60)),
+            Style::default().fg(Color::LightCyan),
+        ));
+    }
+    if view.dedup.enabled {
+        spans.push(Span::styled(
+            "│ dedup  ",
+            Style::default().fg(Color::LightMagenta),
+        ));
+    }
+    if let Some(query) = &view.query.applied {
+        spans.push(Span::styled(
+            format!("│ jq: {}  ", query),
+            Style::default().fg(Color::LightMagenta),
+        ));
+    }
+    if shown != events.len() {
+        spans.push(Span::styled(
+            format!("│ showing {}/{}", shown, events.len()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Rows of bar cells in the histogram strip (each cell has 8 sub-levels).
+pub const HISTOGRAM_BAR_ROWS: u16 = 3;
+
+/// Event volume histogram: `HISTOGRAM_BAR_ROWS` rows of bars (one column per
+/// bucket, errors stacked red at the bottom, warnings yellow above) followed
+/// by a label row with the time range and the highlighted bucket's counts.
+/// The highlighted bucket is the cursor while focused, otherwise the bucket of
+/// the selected event.
+pub fn histogram(view: &EventView, list: &StatefulList<LogEvent>) -> Vec<Line<'static>> {
+    let visible = list.visible_items();
+    let Some(buckets) = Buckets::compute(&visible, view.histogram.buckets) else {
+        return vec![Line::from(Span::styled(
+            " (no events)",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    };
+    let highlighted = if view.histogram.focused {
+        Some(view.histogram.cursor)
+    } else {
+        list.selected().map(|e| buckets.index_of(e.timestamp))
+    };
+
+    const BLOCKS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let full = HISTOGRAM_BAR_ROWS as usize * 8;
+    let max = (0..buckets.counts.len())
+        .map(|i| buckets.total(i))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    // scale to eighths; any non-empty bucket gets at least one
+    let eighths = |n: usize| (n * full).div_ceil(max);
+
+    let mut lines: Vec<Line> = (0..HISTOGRAM_BAR_ROWS as usize)
+        .rev()
+        .map(|row| {
+            let spans: Vec<Span> = (0..buckets.counts.len())
+                .map(|i| {
+                    let c = &buckets.counts[i];
+                    let errors = c[Level::Fatal.index()] + c[Level::Error.index()];
+                    let warns = c[Level::Warn.index()];
+                    let height = eighths(buckets.total(i));
+                    let fill = height.saturating_sub(row * 8).min(8);
+                    // color by the band covering the middle of this cell
+                    let mid = row * 8 + 4;
+                    let fg = if mid < eighths(errors) {
+                        Color::Red
+                    } else if mid < eighths(errors + warns) {
+                        Color::Yellow
+                    } else {
+                        Color::Blue
+                    };
+                    let mut style = Style::default().fg(fg);
+                    if highlighted == Some(i) {
+                        style = style.bg(Color::Rgb(50, 50, 70));
+                    }
+                    Span::styled(BLOCKS[fill].to_string(), style)
+                })
+                .collect();
+            let mut line = vec![Span::raw(" ")];
+            line.extend(spans);
+            Line::from(line)
+        })
+        .collect();
+
+    let end_ms = buckets.start_ms + buckets.span_ms * buckets.counts.len() as i64 - 1;
+    let mut label = vec![Span::styled(
+        format!(
+            " {} → {}",
+            time::format_ms_seconds(buckets.start_ms),
+            time::format_ms_seconds(end_ms)
+        ),
+        Style::default().fg(Color::DarkGray),
+    )];
+    if let Some(i) = highlighted {
+        let c = &buckets.counts[i];
+        let from = buckets.start_ms + buckets.span_ms * i as i64;
+        label.push(Span::styled(
+            format!(
+                "  │ ▲ {} +{}s: {} events ({} error, {} warn)",
+                time::format_ms_seconds(from),
+                (buckets.span_ms as f64 / 1000.0).ceil() as i64,
+                buckets.total(i),
+                c[Level::Fatal.index()] + c[Level::Error.index()],
+                c[Level::Warn.index()],
+            ),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+    lines.push(Line::from(label));
+    lines
+}
+
+/// Footer line while a histogram bucket is being selected (`H`).
+pub fn histogram_hint() -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            " histogram: ",
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("[h/l]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" move  "),
+        Span::styled("[0/$]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" first/last  "),
+        Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" jump to bucket  "),
+        Span::styled("[H/Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" done"),
+    ])
+}
+
+/// Header suffix shown while jumping to the end loads the remaining pages.
+pub fn load_all_label<T>(list: &StatefulList<T>) -> String {
+    if list.load_all {
+        format!("  │  loading all pages… ({} loaded)", list.items.len())
+    } else {
+        String::new()
+    }
+}
+
+/// Footer line while a visual range is being selected (`v`).
+pub fn visual_hint(count: usize) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!(" VISUAL ({} selected)  ", count),
+            Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("[j/k]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" extend  "),
+        Span::styled("[y]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" copy  "),
+        Span::styled("[v/Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" cancel"),
+    ])
+}
+
+/// Footer line for a one-shot notice such as a copy result; notices starting
+/// with "Error" are shown in red.
+pub fn notice_line(notice: &str) -> Line<'static> {
+    let fg = if notice.starts_with("Error") {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    Line::from(Span::styled(
+        format!(" {}", notice),
+        Style::default().fg(fg).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// Footer line for the field query prompt (`J`), with the live syntax error.
+pub fn query_prompt(query: &FieldQuery) -> Line<'static> {
+    let mut spans = vec![
+        Span::styled(
+            " jq: ",
+            Style::default()
+                .fg(Color::LightMagenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(query.buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::LightMagenta)),
+        Span::raw("  "),
+    ];
+    if let Some(err) = &query.error {
+        spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    spans.extend([
+        Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" apply  "),
+        Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
+
+/// Footer line for the download path prompt (`d`), with the output format
+/// (inferred from the extension unless chosen with the arrows) and scope.
+/// While a confirmation is pending it asks that question instead; after a
+/// `Tab` with several matches it lists the completion candidates.
+pub fn download_prompt(download: &DownloadPrompt) -> Line<'static> {
+    let key = |k: &str| Span::styled(k.to_string(), Style::default().fg(Color::DarkGray));
+    if let Some(confirm) = &download.confirm {
+        return confirm_prompt(confirm, download.format().can_append());
+    }
+    let mut spans = vec![
+        Span::styled(
+            " save to: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(download.buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::Cyan)),
+        Span::raw("   "),
+    ];
+    if let Some(err) = &download.error {
+        spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if !download.candidates.is_empty() {
+        spans.push(Span::styled(
+            format!("{}  ", download.candidates.join("  ")),
+            Style::default().fg(Color::Yellow),
+        ));
+        return Line::from(spans);
+    }
+    spans.extend([
+        Span::styled(
+            format!(
+                "{}{} · {}",
+                download.format().label(),
+                download
+                    .compression()
+                    .label()
+                    .map(|c| format!(" ({})", c))
+                    .unwrap_or_default(),
+                download.scope.label()
+            ),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw("  "),
+        key("[Tab]"),
+        Span::raw(" complete  "),
+        key("[↑↓]"),
+        Span::raw(" format  "),
+    ]);
+    if download.can_fetch {
+        spans.extend([key("[S-Tab]"), Span::raw(" scope  ")]);
+    }
+    spans.extend([
+        key("[Enter]"),
+        Span::raw(" save  "),
+        key("[Esc]"),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
+
+/// Footer line asking how to proceed with a confirmation pending on an
+/// output path. `[a]` (append) is offered only when `can_append` is set.
+pub fn confirm_prompt(confirm: &Confirm, can_append: bool) -> Line<'static> {
+    let key = |k: &str| Span::styled(k.to_string(), Style::default().fg(Color::DarkGray));
+    let mut spans = vec![Span::styled(
+        match confirm {
+            Confirm::CreateDir { dir, .. } => format!(" {} does not exist   ", dir),
+            Confirm::Exists(path) => format!(" {} already exists   ", path),
+        },
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )];
+    match confirm {
+        Confirm::CreateDir { .. } => {
+            spans.extend([key("[y]"), Span::raw(" create directory  ")]);
+        }
+        Confirm::Exists(_) => {
+            spans.extend([key("[o]"), Span::raw(" overwrite  ")]);
+            if can_append {
+                spans.extend([key("[a]"), Span::raw(" append  ")]);
+            }
+            spans.extend([key("[r]"), Span::raw(" rename  ")]);
+        }
+    }
+    spans.extend([key("[Esc]"), Span::raw(" back")]);
+    Line::from(spans)
+}
+
+/// Width of the progress bar cells in the download progress line.
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// Footer line for a background "all pages" download: a bar for how far the
+/// written events have advanced through the time range, then the event and
+/// byte counts and the elapsed time. Once finished it reports whether the
+/// file is complete or partial.
+pub fn download_progress(job: &DownloadJob) -> Line<'static> {
+    let p = job.progress();
+    let stats = format!(
+        "{} events, {} pages, {}, {}",
+        p.events,
+        p.pages,
+        format_bytes(p.bytes),
+        format_elapsed(p.elapsed.as_secs())
+    );
+    let (label, color, detail) = match &p.state {
+        JobState::Running => {
+            let bar = match p.fraction() {
+                Some(frac) => {
+                    let filled = (frac * PROGRESS_BAR_WIDTH as f64).round() as usize;
+                    format!(
+                        "[{}{}] {:>3.0}%",
+                        "█".repeat(filled),
+                        "░".repeat(PROGRESS_BAR_WIDTH - filled),
+                        frac * 100.0
+                    )
+                }
+                None => format!("[{}]   …", "░".repeat(PROGRESS_BAR_WIDTH)),
+            };
+            return Line::from(vec![
+                Span::styled(
+                    " ⬇ downloading ",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(bar, Style::default().fg(Color::Cyan)),
+                Span::raw(format!("  {}  → {}   ", stats, job.path)),
+                Span::styled("[x]", Style::default().fg(Color::DarkGray)),
+                Span::raw(" cancel"),
+            ]);
+        }
+        JobState::Complete => ("✓ complete", Color::Green, String::new()),
+        JobState::Cancelled => ("✗ partial (cancelled)", Color::Yellow, String::new()),
+        JobState::Failed(e) => ("✗ partial (failed)", Color::Red, format!(": {}", e)),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!(" {}", label),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(": {} → {}{}", stats, job.path, detail),
+            Style::default().fg(color),
+        ),
+    ])
+}
+
+/// Human-readable byte count (`512 B`, `1.5 MB`).
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Elapsed time as `m:ss`.
+pub fn format_elapsed(secs: u64) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Footer line for the jump-to-time prompt (`t`), with the parse error if
+/// the last attempt to confirm failed.
+pub fn jump_prompt(jump: &TimeJump) -> Line<'static> {
+    let mut spans = vec![
+        Span::styled(
+            " jump to: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(jump.buffer.clone()),
+        Span::styled("█", Style::default().fg(Color::Cyan)),
+        Span::raw("  "),
+    ];
+    match &jump.error {
+        Some(err) => spans.push(Span::styled(
+            format!("✗ {}  ", err),
+            Style::default().fg(Color::Red),
+        )),
+        None => spans.push(Span::styled(
+            "e.g. 2024-01-01 12:00, 12:00, 15m ago  ",
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+    spans.extend([
+        Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" jump  "),
+        Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" cancel"),
+    ]);
+    Line::from(spans)
+}
+
+/// Footer line while the level facet bar is focused (`f`).
+pub fn facet_hint() -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            " levels: ",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("[h/l]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" move  "),
+        Span::styled("[Space]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" toggle  "),
+        Span::styled("[1-7]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" toggle level  "),
+        Span::styled("[o]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" only  "),
+        Span::styled("[a]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" all  "),
+        Span::styled("[f/Esc]", Style::default().fg(Color::DarkGray)),
+        Span::raw(" done"),
+    ])
+}
+
+/// Truncate to at most `max` Unicode scalar values, appending `…` if cut.
+pub fn truncate_chars(s: &str, max: usize) -> String {
+    let mut chars = s.chars();
+    let mut result: String = chars.by_ref().take(max).collect();
+    if chars.next().is_some() {
+        result.push('…');
+    }
+    result
+}
+
+/// Scan `line` for the first log-level keyword and return Spans with only
+/// that keyword colored; the rest of the text is left in the default color.
+pub fn colorize_level_keyword(line: &str) -> Vec<Span<'static>> {
+    // Keywords ordered so longer matches win (CRITICAL before ERROR, etc.)
+    const KEYWORDS: &[(&str, Color)] = &[
+        ("CRITICAL", Color::Red),
+        ("FATAL", Color::Red),
+        ("ERROR", Color::Red),
+        ("ERR", Color::Red),
+        ("WARNING", Color::Yellow),
+        ("WARN", Color::Yellow),
+        ("INFO", Color::Green),
+        ("DEBUG", Color::Cyan),
+        ("TRACE", Color::Magenta),
+    ];
+
+    let upper = line.to_uppercase();
+    for (kw, color) in KEYWORDS {
+        if let Some(pos) = upper.find(kw) {
+            let end = pos + kw.len();
+            // Use original-case slice for display
+            let (Some(before), Some(keyword), Some(after)) =
+                (line.get(..pos), line.get(pos..end), line.get(end..))
+            else {
+                // to_uppercase changed byte offsets (non-ASCII text); skip coloring
+                break;
+            };
+
+            let mut spans = Vec::new();
+            if !before.is_empty() {
+                spans.push(Span::raw(before.to_owned()));
+            }
+            spans.push(Span::styled(
+                keyword.to_owned(),
+                Style::default().fg(*color).add_modifier(Modifier::BOLD),
+            ));
+            if !after.is_empty() {
+                spans.push(Span::raw(after.to_owned()));
+            }
+            return spans;
+        }
+    }
+
+    // No keyword found — plain white
+    vec![Span::raw(line.to_owned())]
+}
+
+/// Re-split `spans` (which together render `text`) so that every match of
+/// `re` gets the search highlight style on top of its existing style.
+pub fn highlight_matches(spans: Vec<Span<'static>>, text: &str, re: &Regex) -> Vec<Span<'static>> {
+    let ranges: Vec<(usize, usize)> = re
+        .find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    if ranges.is_empty() {
+        return spans;
+    }
+    let hl = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+
+    let mut out = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let content = span.content.into_owned();
+        let start = offset;
+        let end = offset + content.len();
+        offset = end;
+
+        let mut cursor = start;
+        for &(m_start, m_end) in &ranges {
+            let (s, e) = (m_start.max(start), m_end.min(end));
+            if s >= e {
+                continue;
+            }
+            if cursor < s {
+                out.push(Span::styled(
+                    content[cursor - start..s - start].to_owned(),
+                    span.style,
+                ));
+            }
+            out.push(Span::styled(
+                content[s - start..e - start].to_owned(),
+                span.style.patch(hl),
+            ));
+            cursor = e;
+        }
+        if cursor < end {
+            out.push(Span::styled(
+                content[cursor - start..].to_owned(),
+                span.style,
+            ));
+        }
+    }
+    out
+}
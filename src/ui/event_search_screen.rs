@@ -6,7 +6,10 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
+use super::pattern_input;
+use crate::redact;
 use crate::screen::EventSearchScreen;
+use crate::time;
 
 pub fn draw(f: &mut Frame, screen: &mut EventSearchScreen) {
     let area = f.area();
@@ -22,8 +25,10 @@ pub fn draw(f: &mut Frame, screen: &mut EventSearchScreen) {
 
     // Header
     let header = Paragraph::new(format!(
-        " Log Event Search  │  Group: {}",
-        screen.group_name
+        " Log Event Search  │  Group: {}  │  {}{}",
+        screen.group_name,
+        time::zone_label(),
+        redact::indicator()
     ))
     .style(Style::default().bg(Color::DarkGray).fg(Color::White));
     f.render_widget(header, outer[0]);
@@ -44,32 +49,37 @@ pub fn draw(f: &mut Frame, screen: &mut EventSearchScreen) {
         f,
         body_layout[0],
         "開始日時 (Start)",
-        &screen.event_search_start,
+        vec![Span::raw(screen.event_search_start.clone())],
         screen.event_search_focused == 0,
     );
     render_field(
         f,
         body_layout[1],
         "終了日時 (End)",
-        &screen.event_search_end,
+        vec![Span::raw(screen.event_search_end.clone())],
         screen.event_search_focused == 1,
     );
+    let (pattern_spans, pattern_error) = pattern_input::pattern_spans(&screen.event_search_pattern);
     render_field(
         f,
         body_layout[2],
         "Filter Pattern",
-        &screen.event_search_pattern,
+        pattern_spans,
         screen.event_search_focused == 2,
     );
 
-    // Error message
-    if let Some(err) = &screen.event_search_error {
+    // Error message (submit error first, otherwise the live pattern syntax error)
+    let error = screen
+        .event_search_error
+        .clone()
+        .or_else(|| pattern_error.map(|e| format!("フィルタパターン: {}", e)));
+    if let Some(err) = error {
         let error_line = Paragraph::new(Line::from(vec![
             Span::styled(
                 " ✗ ",
                 Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
             ),
-            Span::styled(err.as_str(), Style::default().fg(Color::Red)),
+            Span::styled(err, Style::default().fg(Color::Red)),
         ]));
         f.render_widget(error_line, body_layout[3]);
     }
@@ -89,7 +99,7 @@ pub fn draw(f: &mut Frame, screen: &mut EventSearchScreen) {
     f.render_widget(footer, outer[2]);
 }
 
-fn render_field(f: &mut Frame, area: Rect, label: &str, value: &str, focused: bool) {
+fn render_field(f: &mut Frame, area: Rect, label: &str, value: Vec<Span<'static>>, focused: bool) {
     let border_color = if focused {
         Color::Cyan
     } else {
@@ -113,6 +123,8 @@ fn render_field(f: &mut Frame, area: Rect, label: &str, value: &str, focused: bo
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let content = Paragraph::new(Line::from(vec![Span::raw(value.to_owned()), cursor]));
+    let mut spans = value;
+    spans.push(cursor);
+    let content = Paragraph::new(Line::from(spans));
     f.render_widget(content, inner);
 }
@@ -3,20 +3,31 @@ use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, Paragraph},
 };
 
+use super::{event_list, pattern_input};
+use crate::redact;
 use crate::screen::EventsScreen;
+use crate::time;
 
 pub fn draw(f: &mut Frame, screen: &mut EventsScreen) {
     let area = f.area();
+    let column_header_height = if screen.view.columns.columns.is_empty() {
+        0
+    } else {
+        1
+    };
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // header
-            Constraint::Min(0),    // list
-            Constraint::Length(1), // footer / filter input
+            Constraint::Length(1),                                  // header
+            Constraint::Length(event_list::HISTOGRAM_BAR_ROWS + 1), // volume histogram
+            Constraint::Length(1),                                  // level facets
+            Constraint::Length(column_header_height),               // JSON field column names
+            Constraint::Min(0),                                     // list
+            Constraint::Length(1),                                  // footer / filter input
         ])
         .split(area);
 
@@ -25,21 +36,62 @@ pub fn draw(f: &mut Frame, screen: &mut EventsScreen) {
         Some(f) => format!("  │  filter: {}", f),
         None => String::new(),
     };
+    let start_display = match screen.start_ms {
+        Some(ms) => format!("  │  from: {}", time::format_ms_seconds(ms)),
+        None => String::new(),
+    };
+    let source = if screen.offline_events.is_some() {
+        format!("file: {}", screen.group_name)
+    } else {
+        format!("{} › {}", screen.group_name, screen.stream_name)
+    };
     let header_text = format!(
-        " {} › {}{}",
-        screen.group_name, screen.stream_name, filter_display
+        " {}{}{}  │  {}{}{}",
+        source,
+        start_display,
+        filter_display,
+        time::zone_label(),
+        redact::indicator(),
+        event_list::load_all_label(&screen.log_events)
     );
     let header =
         Paragraph::new(header_text).style(Style::default().bg(Color::DarkGray).fg(Color::White));
     f.render_widget(header, chunks[0]);
 
+    // Rows visible in the list (minus borders), used for page-wise motions
+    screen.log_events.page_size = (chunks[4].height as usize).saturating_sub(2).max(1);
+
+    // Volume histogram (one bucket per column)
+    screen.view.histogram.buckets = (chunks[1].width as usize).saturating_sub(2).max(1);
+    let histogram = event_list::histogram(&screen.view, &screen.log_events);
+    f.render_widget(Paragraph::new(histogram), chunks[1]);
+
+    // Level facets
+    let facets = event_list::facet_bar(
+        &screen.view,
+        &screen.log_events.items,
+        screen.log_events.visible_len(),
+    );
+    f.render_widget(Paragraph::new(facets), chunks[2]);
+
+    // JSON field column names
+    if column_header_height > 0 {
+        let header = event_list::column_header(&screen.view.columns.columns);
+        f.render_widget(Paragraph::new(header), chunks[3]);
+    }
+
     // Events list
     let loading = screen.log_events.loading;
-    let block_title = if loading {
-        " Events (loading…) "
-    } else {
-        " Events "
-    };
+    let block_title = format!(
+        " Events{} {}",
+        if loading { " (loading…)" } else { "" },
+        screen
+            .view
+            .search
+            .status()
+            .map(|st| format!("{} ", st))
+            .unwrap_or_default()
+    );
     let block = Block::default()
         .title(block_title)
         .borders(Borders::ALL)
@@ -47,37 +99,11 @@ pub fn draw(f: &mut Frame, screen: &mut EventsScreen) {
 
     if screen.log_events.items.is_empty() && !loading {
         let msg = Paragraph::new("  No events found.").block(block);
-        f.render_widget(msg, chunks[1]);
+        f.render_widget(msg, chunks[4]);
     } else {
-        // timestamp col width: "YYYY-MM-DD HH:MM:SS.mmm" = 23
-        let ts_width: usize = 23;
-        // subtract: borders(2) + highlight symbol "▶ " (▶ renders as 2 cols + space = 3) + separator "  "(2)
-        let available = (chunks[1].width as usize).saturating_sub(2 + 3 + ts_width + 2);
-
-        let items: Vec<ListItem> = screen
-            .log_events
-            .items
-            .iter()
-            .map(|e| {
-                let ts = format_timestamp(e.timestamp);
-                // 全行を trim して空行を除き、スペース区切りで1行に結合
-                let joined = e
-                    .message
-                    .lines()
-                    .map(|l| l.trim().replace('\t', " "))
-                    .filter(|l| !l.is_empty())
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                // 文字数ではなく「表示列数」でtruncate
-                let msg = truncate_chars(&joined, available);
-                let mut spans = vec![
-                    Span::styled(ts, Style::default().fg(Color::DarkGray)),
-                    Span::raw("  "),
-                ];
-                spans.extend(colorize_level_keyword(&msg));
-                ListItem::new(Line::from(spans))
-            })
-            .collect();
+        let rows = screen.view.display_rows(&screen.log_events);
+        let items =
+            event_list::event_items(&rows, chunks[4].width, &screen.view, &screen.log_events);
 
         let list = List::new(items)
             .block(block)
@@ -88,138 +114,131 @@ pub fn draw(f: &mut Frame, screen: &mut EventsScreen) {
             )
             .highlight_symbol("▶ ");
 
-        f.render_stateful_widget(list, chunks[1], &mut screen.log_events.state);
+        f.render_stateful_widget(list, chunks[4], &mut screen.log_events.state);
     }
 
     // Footer / filter input / download input
-    if screen.download_editing {
-        let footer = Paragraph::new(Line::from(vec![
-            Span::styled(
-                " save to: ",
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(screen.download_path_buffer.as_str()),
-            Span::styled("█", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
-            Span::raw(" save  "),
-            Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
-            Span::raw(" cancel"),
-        ]))
-        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
-        f.render_widget(footer, chunks[2]);
+    if screen.download.editing {
+        let footer = Paragraph::new(event_list::download_prompt(&screen.download))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.search.editing {
+        let footer = Paragraph::new(event_list::search_prompt(&screen.view.search))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.histogram.focused {
+        let footer = Paragraph::new(event_list::histogram_hint())
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.query.editing {
+        let footer = Paragraph::new(event_list::query_prompt(&screen.view.query))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.jump.editing {
+        let footer = Paragraph::new(event_list::jump_prompt(&screen.view.jump))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.columns.editing {
+        let footer = Paragraph::new(event_list::column_prompt(&screen.view.columns))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if screen.view.facets.focused {
+        let footer = Paragraph::new(event_list::facet_hint())
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
     } else if screen.filter_editing {
-        let footer = Paragraph::new(Line::from(vec![
-            Span::styled(
-                " filter: ",
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::raw(screen.filter_buffer.as_str()),
-            Span::styled("█", Style::default().fg(Color::Yellow)),
-            Span::raw("   "),
+        let (pattern, error) = pattern_input::pattern_spans(&screen.filter_buffer);
+        let mut spans = vec![Span::styled(
+            " filter: ",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )];
+        spans.extend(pattern);
+        spans.push(Span::styled("█", Style::default().fg(Color::Yellow)));
+        spans.push(Span::raw("   "));
+        if let Some(err) = error {
+            spans.push(Span::styled(
+                format!("✗ {}  ", err),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let apply = if screen.filter_confirm {
+            " send anyway  "
+        } else {
+            " apply  "
+        };
+        spans.extend([
             Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
-            Span::raw(" apply  "),
+            Span::raw(apply),
             Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
             Span::raw(" cancel"),
-        ]))
-        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
-        f.render_widget(footer, chunks[2]);
-    } else if let Some(status) = &screen.download_status {
-        let (fg, prefix) = if status.starts_with("Error") {
-            (Color::Red, "")
-        } else {
-            (Color::Green, "")
-        };
-        let footer = Paragraph::new(Line::from(vec![Span::styled(
-            format!(" {}{}", prefix, status),
-            Style::default().fg(fg).add_modifier(Modifier::BOLD),
-        )]))
-        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
-        f.render_widget(footer, chunks[2]);
+        ]);
+        let footer =
+            Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if let Some(job) = &screen.download.job {
+        let footer = Paragraph::new(event_list::download_progress(job))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if let Some(status) = &screen.download.status {
+        let footer = Paragraph::new(event_list::notice_line(status))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if let Some(notice) = &screen.view.notice {
+        let footer = Paragraph::new(event_list::notice_line(notice))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+    } else if let Some((from, to)) = screen.view.visual_range(&screen.log_events) {
+        let footer = Paragraph::new(event_list::visual_hint(to - from + 1))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
     } else {
         let footer = Paragraph::new(Line::from(vec![
             Span::styled(" [/]", Style::default().fg(Color::Yellow)),
             Span::raw(" filter  "),
+            Span::styled("[?]", Style::default().fg(Color::Yellow)),
+            Span::raw(" search  "),
+            Span::styled("[n/N]", Style::default().fg(Color::Yellow)),
+            Span::raw(" next/prev match  "),
+            Span::styled("[f]", Style::default().fg(Color::Yellow)),
+            Span::raw(" levels  "),
+            Span::styled("[c]", Style::default().fg(Color::Yellow)),
+            Span::raw(" columns  "),
+            Span::styled("[J]", Style::default().fg(Color::Yellow)),
+            Span::raw(" jq  "),
+            Span::styled("[H]", Style::default().fg(Color::Yellow)),
+            Span::raw(" histogram  "),
+            Span::styled("[m]", Style::default().fg(Color::Yellow)),
+            Span::raw(" mark  "),
+            Span::styled("['/`]", Style::default().fg(Color::Yellow)),
+            Span::raw(" next/prev mark  "),
+            Span::styled("[B]", Style::default().fg(Color::Yellow)),
+            Span::raw(" bookmarks  "),
+            Span::styled("[v]", Style::default().fg(Color::Yellow)),
+            Span::raw(" select  "),
+            Span::styled("[y]", Style::default().fg(Color::Yellow)),
+            Span::raw(" copy  "),
+            Span::styled("[D]", Style::default().fg(Color::Yellow)),
+            Span::raw(" diff  "),
+            Span::styled("[u]", Style::default().fg(Color::Yellow)),
+            Span::raw(" dedup  "),
+            Span::styled("[t]", Style::default().fg(Color::Yellow)),
+            Span::raw(" jump to time  "),
+            Span::styled("[P]", Style::default().fg(Color::Yellow)),
+            Span::raw(" patterns  "),
             Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
             Span::raw(" open  "),
             Span::styled("[j/k ↑↓]", Style::default().fg(Color::Yellow)),
             Span::raw(" scroll  "),
             Span::styled("[d]", Style::default().fg(Color::Yellow)),
             Span::raw(" download  "),
+            Span::styled("[z]", Style::default().fg(Color::Yellow)),
+            Span::raw(" UTC/local  "),
             Span::styled("[q]", Style::default().fg(Color::Yellow)),
             Span::raw(" back"),
         ]))
         .style(Style::default().bg(Color::Rgb(30, 30, 30)));
-        f.render_widget(footer, chunks[2]);
+        f.render_widget(footer, chunks[5]);
     }
 }
-
-// ── helpers ──────────────────────────────────────────────────────────────────
-
-fn format_timestamp(ts_ms: i64) -> String {
-    match jiff::Timestamp::from_millisecond(ts_ms) {
-        Ok(ts) => {
-            let ms = ts_ms.rem_euclid(1000);
-            format!("{}.{:03}", ts.strftime("%Y-%m-%d %H:%M:%S"), ms)
-        }
-        Err(_) => String::from("0000-00-00 00:00:00.000"),
-    }
-}
-
-/// Truncate to at most `max` Unicode scalar values, appending `…` if cut.
-fn truncate_chars(s: &str, max: usize) -> String {
-    let mut chars = s.chars();
-    let mut result: String = chars.by_ref().take(max).collect();
-    if chars.next().is_some() {
-        result.push('…');
-    }
-    result
-}
-
-/// Scan `line` for the first log-level keyword and return Spans with only
-/// that keyword colored; the rest of the text is left in the default color.
-fn colorize_level_keyword(line: &str) -> Vec<Span<'static>> {
-    // Keywords ordered so longer matches win (CRITICAL before ERROR, etc.)
-    const KEYWORDS: &[(&str, Color)] = &[
-        ("CRITICAL", Color::Red),
-        ("FATAL", Color::Red),
-        ("ERROR", Color::Red),
-        ("ERR", Color::Red),
-        ("WARNING", Color::Yellow),
-        ("WARN", Color::Yellow),
-        ("INFO", Color::Green),
-        ("DEBUG", Color::Cyan),
-        ("TRACE", Color::Magenta),
-    ];
-
-    let upper = line.to_uppercase();
-    for (kw, color) in KEYWORDS {
-        if let Some(pos) = upper.find(kw) {
-            let end = pos + kw.len();
-            // Use original-case slice for display
-            let before = line[..pos].to_owned();
-            let keyword = line[pos..end].to_owned();
-            let after = line[end..].to_owned();
-
-            let mut spans = Vec::new();
-            if !before.is_empty() {
-                spans.push(Span::raw(before));
-            }
-            spans.push(Span::styled(
-                keyword,
-                Style::default().fg(*color).add_modifier(Modifier::BOLD),
-            ));
-            if !after.is_empty() {
-                spans.push(Span::raw(after));
-            }
-            return spans;
-        }
-    }
-
-    // No keyword found — plain white
-    vec![Span::raw(line.to_owned())]
-}
@@ -3,54 +3,89 @@ use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, Paragraph},
 };
 
+use super::event_list;
+use crate::redact;
 use crate::screen::GroupEventsScreen;
+use crate::time;
 
 pub fn draw(f: &mut Frame, screen: &mut GroupEventsScreen) {
     let area = f.area();
+    let column_header_height = if screen.view.columns.columns.is_empty() {
+        0
+    } else {
+        1
+    };
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // header
-            Constraint::Min(0),    // list
-            Constraint::Length(1), // footer
+            Constraint::Length(1),                                  // header
+            Constraint::Length(event_list::HISTOGRAM_BAR_ROWS + 1), // volume histogram
+            Constraint::Length(1),                                  // level facets
+            Constraint::Length(column_header_height),               // JSON field column names
+            Constraint::Min(0),                                     // list
+            Constraint::Length(1),                                  // footer
         ])
         .split(area);
 
     // Header: group name + search condition summary
-    let start_disp = if screen.start_display.is_empty() {
-        "*"
-    } else {
-        screen.start_display.as_str()
-    };
-    let end_disp = if screen.end_display.is_empty() {
-        "*"
-    } else {
-        screen.end_display.as_str()
-    };
-    let pattern_disp = if screen.pattern_display.is_empty() {
-        String::new()
-    } else {
-        format!("  │  pattern: {}", screen.pattern_display)
+    // Format the range on every draw so it follows the display zone toggled with `z`
+    let time_disp = |ms: Option<i64>| ms.map_or_else(|| "*".to_string(), time::format_ms);
+    let pattern_disp = match &screen.pattern {
+        Some(pattern) => format!("  │  pattern: {}", pattern),
+        None => String::new(),
     };
     let header_text = format!(
-        " {}  │  {} → {}{}",
-        screen.group_name, start_disp, end_disp, pattern_disp
+        " {}  │  {} → {}{}  │  {}{}{}",
+        screen.group_name,
+        time_disp(screen.start_ms),
+        time_disp(screen.end_ms),
+        pattern_disp,
+        time::zone_label(),
+        redact::indicator(),
+        event_list::load_all_label(&screen.log_events)
     );
     let header =
         Paragraph::new(header_text).style(Style::default().bg(Color::DarkGray).fg(Color::White));
     f.render_widget(header, chunks[0]);
 
+    // Rows visible in the list (minus borders), used for page-wise motions
+    screen.log_events.page_size = (chunks[4].height as usize).saturating_sub(2).max(1);
+
+    // Volume histogram (one bucket per column)
+    screen.view.histogram.buckets = (chunks[1].width as usize).saturating_sub(2).max(1);
+    let histogram = event_list::histogram(&screen.view, &screen.log_events);
+    f.render_widget(Paragraph::new(histogram), chunks[1]);
+
+    // Level facets
+    let facets = event_list::facet_bar(
+        &screen.view,
+        &screen.log_events.items,
+        screen.log_events.visible_len(),
+    );
+    f.render_widget(Paragraph::new(facets), chunks[2]);
+
+    // JSON field column names
+    if column_header_height > 0 {
+        let header = event_list::column_header(&screen.view.columns.columns);
+        f.render_widget(Paragraph::new(header), chunks[3]);
+    }
+
     // Events list
     let loading = screen.log_events.loading;
-    let block_title = if loading {
-        " Group Events (loading…) "
-    } else {
-        " Group Events "
-    };
+    let block_title = format!(
+        " Group Events{} {}",
+        if loading { " (loading…)" } else { "" },
+        screen
+            .view
+            .search
+            .status()
+            .map(|st| format!("{} ", st))
+            .unwrap_or_default()
+    );
     let block = Block::default()
         .title(block_title)
         .borders(Borders::ALL)
@@ -58,33 +93,11 @@ pub fn draw(f: &mut Frame, screen: &mut GroupEventsScreen) {
 
     if screen.log_events.items.is_empty() && !loading {
         let msg = Paragraph::new("  No events found.").block(block);
-        f.render_widget(msg, chunks[1]);
+        f.render_widget(msg, chunks[4]);
     } else {
-        let ts_width: usize = 23;
-        let available = (chunks[1].width as usize).saturating_sub(2 + 3 + ts_width + 2);
-
-        let items: Vec<ListItem> = screen
-            .log_events
-            .items
-            .iter()
-            .map(|e| {
-                let ts = format_timestamp(e.timestamp);
-                let joined = e
-                    .message
-                    .lines()
-                    .map(|l| l.trim().replace('\t', " "))
-                    .filter(|l| !l.is_empty())
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                let msg = truncate_chars(&joined, available);
-                let mut spans = vec![
-                    Span::styled(ts, Style::default().fg(Color::DarkGray)),
-                    Span::raw("  "),
-                ];
-                spans.extend(colorize_level_keyword(&msg));
-                ListItem::new(Line::from(spans))
-            })
-            .collect();
+        let rows = screen.view.display_rows(&screen.log_events);
+        let items =
+            event_list::event_items(&rows, chunks[4].width, &screen.view, &screen.log_events);
 
         let list = List::new(items)
             .block(block)
@@ -95,74 +108,118 @@ pub fn draw(f: &mut Frame, screen: &mut GroupEventsScreen) {
             )
             .highlight_symbol("▶ ");
 
-        f.render_stateful_widget(list, chunks[1], &mut screen.log_events.state);
+        f.render_stateful_widget(list, chunks[4], &mut screen.log_events.state);
     }
 
-    // Footer
+    // Footer / input prompts / mode hints / notice
+    if screen.download.editing {
+        let footer = Paragraph::new(event_list::download_prompt(&screen.download))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.search.editing {
+        let footer = Paragraph::new(event_list::search_prompt(&screen.view.search))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.histogram.focused {
+        let footer = Paragraph::new(event_list::histogram_hint())
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.query.editing {
+        let footer = Paragraph::new(event_list::query_prompt(&screen.view.query))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.jump.editing {
+        let footer = Paragraph::new(event_list::jump_prompt(&screen.view.jump))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.columns.editing {
+        let footer = Paragraph::new(event_list::column_prompt(&screen.view.columns))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if screen.view.facets.focused {
+        let footer = Paragraph::new(event_list::facet_hint())
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if let Some(job) = &screen.download.job {
+        let footer = Paragraph::new(event_list::download_progress(job))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if let Some(status) = &screen.download.status {
+        let footer = Paragraph::new(event_list::notice_line(status))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if let Some(notice) = &screen.view.notice {
+        let footer = Paragraph::new(event_list::notice_line(notice))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
+    if let Some((from, to)) = screen.view.visual_range(&screen.log_events) {
+        let footer = Paragraph::new(event_list::visual_hint(to - from + 1))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[5]);
+        return;
+    }
     let footer = Paragraph::new(Line::from(vec![
         Span::styled(" [j/k ↑↓]", Style::default().fg(Color::Yellow)),
         Span::raw(" スクロール  "),
         Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
         Span::raw(" 詳細  "),
+        Span::styled("[?]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 検索  "),
+        Span::styled("[n/N]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 次/前の一致  "),
+        Span::styled("[f]", Style::default().fg(Color::Yellow)),
+        Span::raw(" レベル  "),
+        Span::styled("[c]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 列  "),
+        Span::styled("[J]", Style::default().fg(Color::Yellow)),
+        Span::raw(" jq  "),
+        Span::styled("[H]", Style::default().fg(Color::Yellow)),
+        Span::raw(" ヒストグラム  "),
+        Span::styled("[m]", Style::default().fg(Color::Yellow)),
+        Span::raw(" マーク  "),
+        Span::styled("['/`]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 次/前のマーク  "),
+        Span::styled("[B]", Style::default().fg(Color::Yellow)),
+        Span::raw(" ブックマーク  "),
+        Span::styled("[v]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 範囲選択  "),
+        Span::styled("[y]", Style::default().fg(Color::Yellow)),
+        Span::raw(" コピー  "),
+        Span::styled("[D]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 差分  "),
+        Span::styled("[u]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 重複折りたたみ  "),
+        Span::styled("[t]", Style::default().fg(Color::Yellow)),
+        Span::raw(" 時刻へ移動  "),
+        Span::styled("[P]", Style::default().fg(Color::Yellow)),
+        Span::raw(" テンプレート  "),
+        Span::styled("[d]", Style::default().fg(Color::Yellow)),
+        Span::raw(" ダウンロード  "),
+        Span::styled("[z]", Style::default().fg(Color::Yellow)),
+        Span::raw(" UTC/ローカル  "),
         Span::styled("[q]", Style::default().fg(Color::Yellow)),
         Span::raw(" 検索に戻る"),
     ]))
     .style(Style::default().bg(Color::Rgb(30, 30, 30)));
-    f.render_widget(footer, chunks[2]);
-}
-
-fn format_timestamp(ts_ms: i64) -> String {
-    match jiff::Timestamp::from_millisecond(ts_ms) {
-        Ok(ts) => {
-            let ms = ts_ms.rem_euclid(1000);
-            format!("{}.{:03}", ts.strftime("%Y-%m-%d %H:%M:%S"), ms)
-        }
-        Err(_) => String::from("0000-00-00 00:00:00.000"),
-    }
-}
-
-fn truncate_chars(s: &str, max: usize) -> String {
-    let mut chars = s.chars();
-    let mut result: String = chars.by_ref().take(max).collect();
-    if chars.next().is_some() {
-        result.push('…');
-    }
-    result
-}
-
-fn colorize_level_keyword(line: &str) -> Vec<Span<'static>> {
-    const KEYWORDS: &[(&str, Color)] = &[
-        ("CRITICAL", Color::Red),
-        ("FATAL", Color::Red),
-        ("ERROR", Color::Red),
-        ("ERR", Color::Red),
-        ("WARNING", Color::Yellow),
-        ("WARN", Color::Yellow),
-        ("INFO", Color::Green),
-        ("DEBUG", Color::Cyan),
-        ("TRACE", Color::Magenta),
-    ];
-
-    let upper = line.to_uppercase();
-    for (kw, color) in KEYWORDS {
-        if let Some(pos) = upper.find(kw) {
-            let end = pos + kw.len();
-            let before = line[..pos].to_owned();
-            let keyword = line[pos..end].to_owned();
-            let after = line[end..].to_owned();
-            let mut spans = Vec::new();
-            if !before.is_empty() {
-                spans.push(Span::raw(before));
-            }
-            spans.push(Span::styled(
-                keyword,
-                Style::default().fg(*color).add_modifier(Modifier::BOLD),
-            ));
-            if !after.is_empty() {
-                spans.push(Span::raw(after));
-            }
-            return spans;
-        }
-    }
-    vec![Span::raw(line.to_owned())]
+    f.render_widget(footer, chunks[5]);
 }
@@ -1,4 +1,3 @@
-use jiff::Timestamp;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout},
@@ -7,13 +6,23 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 
+use super::event_list::{format_bytes, format_elapsed};
+use crate::archive::{ArchiveJob, MANIFEST_FILE};
+use crate::download::JobState;
+use crate::redact;
 use crate::screen::MainScreen;
+use crate::screen::archive_prompt::{ArchiveField, ArchivePrompt};
+use crate::time;
 
 pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
     let area = f.area();
 
     // Layout: header / panels / [search bar] / footer
-    let show_search_bar = screen.main_search_active || !screen.main_search_query.is_empty();
+    let show_search_bar = screen.main_search_active
+        || !screen.main_search_query.is_empty()
+        || screen.open_editing
+        || screen.archive.editing
+        || screen.archive.job.is_some();
     let constraints = if show_search_bar {
         vec![
             Constraint::Length(1),
@@ -35,12 +44,14 @@ pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
 
     // Header
     let header = Paragraph::new(format!(
-        " cleam  |  {}",
+        " cleam  |  {}  |  {}{}",
         if screen.log_groups.loading {
             "Loading..."
         } else {
             "AWS CloudWatch Logs"
-        }
+        },
+        time::zone_label(),
+        redact::indicator()
     ))
     .style(Style::default().bg(Color::DarkGray).fg(Color::White));
     f.render_widget(header, chunks[0]);
@@ -50,6 +61,9 @@ pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
         .split(chunks[1]);
+    let page_size = (chunks[1].height as usize).saturating_sub(2).max(1);
+    screen.log_groups.page_size = page_size;
+    screen.log_streams.page_size = page_size;
 
     // --- Groups pane ---
     use crate::app::ActivePanel;
@@ -115,12 +129,7 @@ pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
         .into_iter()
         .map(|s| {
             let time_str = match s.last_event_time {
-                Some(ms) => {
-                    let Ok(ts) = Timestamp::from_millisecond(ms) else {
-                        return ("--/-- --:--".to_string(), s.name.clone());
-                    };
-                    ts.to_zoned(jiff::tz::TimeZone::UTC).to_string()
-                }
+                Some(ms) => time::format_ms_seconds(ms),
                 None => "--/-- --:--".to_string(),
             };
             (time_str, s.name.clone())
@@ -166,7 +175,43 @@ pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
     }
 
     // --- Search bar (shown when search is active or query is non-empty) ---
-    if show_search_bar {
+    if screen.open_editing {
+        let mut spans = vec![
+            Span::styled(
+                " open file: ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(screen.open_path_buffer.as_str()),
+            Span::styled("█", Style::default().fg(Color::Cyan)),
+            Span::raw("   "),
+        ];
+        if let Some(err) = &screen.open_error {
+            spans.push(Span::styled(
+                format!("✗ {}", err),
+                Style::default().fg(Color::Red),
+            ));
+        } else if !screen.open_candidates.is_empty() {
+            spans.push(Span::styled(
+                screen.open_candidates.join("  "),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        let open_bar =
+            Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::DarkGray));
+        f.render_widget(open_bar, chunks[2]);
+    } else if screen.archive.editing {
+        let bar = Paragraph::new(archive_prompt(&screen.archive))
+            .style(Style::default().bg(Color::DarkGray));
+        f.render_widget(bar, chunks[2]);
+    } else if let (Some(job), false) = (
+        &screen.archive.job,
+        screen.main_search_active || !screen.main_search_query.is_empty(),
+    ) {
+        let bar = Paragraph::new(archive_progress(job)).style(Style::default().bg(Color::DarkGray));
+        f.render_widget(bar, chunks[2]);
+    } else if show_search_bar {
         let search_text = if screen.main_search_active {
             format!(" Search: {}_", screen.main_search_query)
         } else {
@@ -188,11 +233,134 @@ pub fn draw(f: &mut Frame, screen: &mut MainScreen) {
         Span::raw(" Open Stream  "),
         Span::styled("[/]", Style::default().fg(Color::Yellow)),
         Span::raw(" Search  "),
+        Span::styled("[s]", Style::default().fg(Color::Yellow)),
+        Span::raw(" Search Events  "),
+        Span::styled("[o]", Style::default().fg(Color::Yellow)),
+        Span::raw(" Open File  "),
+        Span::styled("[a]", Style::default().fg(Color::Yellow)),
+        Span::raw(" Archive Group  "),
+        Span::styled("[B]", Style::default().fg(Color::Yellow)),
+        Span::raw(" Bookmarks  "),
         Span::styled("[Esc]", Style::default().fg(Color::Yellow)),
         Span::raw(" Clear Search  "),
+        Span::styled("[z]", Style::default().fg(Color::Yellow)),
+        Span::raw(" UTC/local  "),
         Span::styled("[q]", Style::default().fg(Color::Yellow)),
         Span::raw(" Quit "),
     ]))
     .style(Style::default().bg(Color::DarkGray));
     f.render_widget(footer, chunks[footer_idx]);
 }
+
+/// Bar for the archive prompt (`a`): output directory and time range, with
+/// the focused field marked by the cursor and the last error if any.
+fn archive_prompt(archive: &ArchivePrompt) -> Line<'static> {
+    let field = |label: &str, value: &str, focused: bool| {
+        let mut spans = vec![
+            Span::styled(
+                format!(" {}: ", label),
+                Style::default()
+                    .fg(if focused { Color::Cyan } else { Color::Gray })
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(value.to_string()),
+        ];
+        if focused {
+            spans.push(Span::styled("█", Style::default().fg(Color::Cyan)));
+        }
+        spans.push(Span::raw(" "));
+        spans
+    };
+    let mut spans = vec![Span::styled(
+        format!(" archive {} →", archive.group),
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )];
+    spans.extend(field(
+        "dir",
+        &archive.dir,
+        archive.focused == ArchiveField::Dir,
+    ));
+    spans.extend(field(
+        "from",
+        &archive.start,
+        archive.focused == ArchiveField::Start,
+    ));
+    spans.extend(field(
+        "to",
+        &archive.end,
+        archive.focused == ArchiveField::End,
+    ));
+    match &archive.error {
+        Some(err) => spans.push(Span::styled(
+            format!("  ✗ {}", err),
+            Style::default().fg(Color::Red),
+        )),
+        None => spans.extend([
+            Span::styled("  [Tab]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" field  "),
+            Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" start  "),
+            Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" cancel"),
+        ]),
+    }
+    Line::from(spans)
+}
+
+/// Bar for a background group archive: streams done out of the total, event
+/// and byte counts and the elapsed time; once finished, whether the archive
+/// is complete and where the manifest was written.
+fn archive_progress(job: &ArchiveJob) -> Line<'static> {
+    let p = job.progress();
+    let streams = match p.streams {
+        Some(total) => format!("{}/{} streams", p.done, total),
+        None => "listing streams".to_string(),
+    };
+    let stats = format!(
+        "{}, {} events, {}, {}",
+        streams,
+        p.events,
+        format_bytes(p.bytes),
+        format_elapsed(p.elapsed.as_secs())
+    );
+    let dir = job.dir.display().to_string();
+    let (label, color, detail) = match &p.state {
+        JobState::Running => {
+            return Line::from(vec![
+                Span::styled(
+                    " ⬇ archiving ",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!("{}  → {}   ", stats, dir)),
+                Span::styled("[x]", Style::default().fg(Color::DarkGray)),
+                Span::raw(" cancel"),
+            ]);
+        }
+        JobState::Complete => ("✓ archived", Color::Green, String::new()),
+        JobState::Cancelled => (
+            "✗ partial archive (cancelled)",
+            Color::Yellow,
+            String::new(),
+        ),
+        JobState::Failed(e) => ("✗ partial archive (failed)", Color::Red, format!(": {}", e)),
+    };
+    Line::from(vec![
+        Span::styled(
+            format!(" {}", label),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                ": {} → {}{}",
+                stats,
+                job.dir.join(MANIFEST_FILE).display(),
+                detail
+            ),
+            Style::default().fg(color),
+        ),
+    ])
+}
@@ -3,10 +3,16 @@
 //! 各スクリーンに対応したサブモジュールと、現在の画面を描画するエントリポイント
 //! [`draw`] を公開します。
 
+pub mod ansi;
+mod bookmarks_screen;
+mod diff_screen;
+mod event_list;
 mod event_search_screen;
 mod events_screen;
 mod group_events_screen;
 mod main_screen;
+mod pattern_input;
+mod patterns_screen;
 mod viewer_screen;
 
 use ratatui::Frame;
@@ -23,6 +29,9 @@ pub fn draw(f: &mut Frame, screen: &mut CurrentScreen) {
         CurrentScreen::Viewer(s) => viewer_screen::draw(f, s),
         CurrentScreen::EventSearch(s) => event_search_screen::draw(f, s),
         CurrentScreen::GroupEvents(s) => group_events_screen::draw(f, s),
+        CurrentScreen::Bookmarks(s) => bookmarks_screen::draw(f, s),
+        CurrentScreen::Diff(s) => diff_screen::draw(f, s),
+        CurrentScreen::Patterns(s) => patterns_screen::draw(f, s),
         CurrentScreen::Transitioning => {}
     }
 }
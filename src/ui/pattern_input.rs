@@ -0,0 +1,94 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+use crate::filter::lexer::TokenKind;
+use crate::filter::{self, PatternError};
+
+/// Syntax-highlight a (possibly incomplete) filter pattern.
+///
+/// Returns the colored spans plus the first syntax error, if any. The
+/// character at the error position is rendered with a red background; an
+/// error at the end of input (e.g. an unclosed `{`) is shown as a trailing
+/// red marker.
+pub fn pattern_spans(input: &str) -> (Vec<Span<'static>>, Option<PatternError>) {
+    let (tokens, error) = filter::analyze(input);
+
+    // (start, end, style) segments covering the whole input
+    let mut segments: Vec<(usize, usize, Style)> = Vec::new();
+    let mut pos = 0;
+    for t in &tokens {
+        if pos < t.start {
+            segments.push((pos, t.start, Style::default()));
+        }
+        segments.push((t.start, t.end, token_style(&t.kind)));
+        pos = t.end;
+    }
+    if pos < input.len() {
+        // Text the lexer could not consume (after a lexical error)
+        segments.push((pos, input.len(), Style::default().fg(Color::Gray)));
+    }
+
+    let marker = error.as_ref().map(|e| {
+        let byte = input
+            .char_indices()
+            .nth(e.column)
+            .map_or(input.len(), |(i, _)| i);
+        let len = input[byte..].chars().next().map_or(0, char::len_utf8);
+        (byte, byte + len)
+    });
+    let error_style = Style::default()
+        .fg(Color::White)
+        .bg(Color::Red)
+        .add_modifier(Modifier::BOLD);
+
+    let mut spans = Vec::new();
+    for (start, end, style) in segments {
+        match marker {
+            Some((m_start, m_end)) if m_start < end && m_end > start => {
+                if start < m_start {
+                    spans.push(Span::styled(input[start..m_start].to_owned(), style));
+                }
+                spans.push(Span::styled(
+                    input[m_start..m_end].to_owned(),
+                    style.patch(error_style),
+                ));
+                if m_end < end {
+                    spans.push(Span::styled(input[m_end..end].to_owned(), style));
+                }
+            }
+            _ => spans.push(Span::styled(input[start..end].to_owned(), style)),
+        }
+    }
+    if let Some((m_start, _)) = marker
+        && m_start >= input.len()
+    {
+        spans.push(Span::styled("◂", Style::default().fg(Color::Red)));
+    }
+    (spans, error)
+}
+
+fn token_style(kind: &TokenKind) -> Style {
+    let style = Style::default();
+    match kind {
+        TokenKind::Word(_) => style,
+        TokenKind::Quoted(_) => style.fg(Color::Green),
+        TokenKind::Regex(_) => style.fg(Color::LightMagenta),
+        TokenKind::Number(_) => style.fg(Color::LightBlue),
+        TokenKind::Selector(_) => style.fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        TokenKind::Keyword(_) => style.fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        TokenKind::Op(_) | TokenKind::And | TokenKind::Or => {
+            style.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        }
+        TokenKind::Minus => style.fg(Color::Red).add_modifier(Modifier::BOLD),
+        TokenKind::Question => style.fg(Color::LightCyan).add_modifier(Modifier::BOLD),
+        TokenKind::LBrace
+        | TokenKind::RBrace
+        | TokenKind::LBracket
+        | TokenKind::RBracket
+        | TokenKind::LParen
+        | TokenKind::RParen => style.fg(Color::LightYellow),
+        TokenKind::Comma | TokenKind::Ellipsis => style.fg(Color::DarkGray),
+    }
+}
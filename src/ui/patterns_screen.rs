@@ -0,0 +1,136 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+use super::event_list;
+use crate::drain::WILDCARD;
+use crate::redact;
+use crate::screen::PatternsScreen;
+use crate::time;
+
+// "count(7)  share(6)  first(23)  last(23)  " before the template
+const STATS_WIDTH: usize = 7 + 2 + 6 + 2 + 23 + 2 + 23 + 2;
+
+pub fn draw(f: &mut Frame, screen: &mut PatternsScreen) {
+    let area = f.area();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // header
+            Constraint::Min(0),    // list
+            Constraint::Length(1), // footer
+        ])
+        .split(area);
+
+    // Header
+    let header = Paragraph::new(format!(
+        " Patterns: {} templates from {} events  │  {}{}",
+        screen.clusters.items.len(),
+        screen.total,
+        time::zone_label(),
+        redact::indicator()
+    ))
+    .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    f.render_widget(header, chunks[0]);
+
+    screen.clusters.page_size = (chunks[1].height as usize).saturating_sub(2).max(1);
+
+    let block = Block::default()
+        .title(" count   share  first                    last                     template ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+
+    if screen.clusters.items.is_empty() {
+        let msg = Paragraph::new("  No events loaded.").block(block);
+        f.render_widget(msg, chunks[1]);
+    } else {
+        // subtract: borders(2) + highlight symbol(3) + stats
+        let available = (chunks[1].width as usize).saturating_sub(2 + 3 + STATS_WIDTH);
+        let total = screen.total.max(1);
+        let items: Vec<ListItem> = screen
+            .clusters
+            .items
+            .iter()
+            .map(|c| {
+                let count = c.members.len();
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{:>7}", count),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("{:>5.1}%", count as f64 * 100.0 / total as f64),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(
+                        time::format_ms(c.first_ms),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(
+                        time::format_ms(c.last_ms),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw("  "),
+                ];
+                spans.extend(template_spans(&c.template.to_string(), available));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(
+                Style::default()
+                    .bg(Color::Rgb(50, 50, 70))
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶ ");
+
+        f.render_stateful_widget(list, chunks[1], &mut screen.clusters.state);
+    }
+
+    // Footer
+    let footer = Paragraph::new(Line::from(vec![
+        Span::styled(" [j/k ↑↓]", Style::default().fg(Color::Yellow)),
+        Span::raw(" scroll  "),
+        Span::styled("[Enter]", Style::default().fg(Color::Yellow)),
+        Span::raw(" show events  "),
+        Span::styled("[z]", Style::default().fg(Color::Yellow)),
+        Span::raw(" UTC/local  "),
+        Span::styled("[q]", Style::default().fg(Color::Yellow)),
+        Span::raw(" back"),
+    ]))
+    .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    f.render_widget(footer, chunks[2]);
+}
+
+/// Template text truncated to `width`, with variable slots highlighted.
+fn template_spans(template: &str, width: usize) -> Vec<Span<'static>> {
+    let text = event_list::truncate_chars(template, width);
+    let slot = Style::default()
+        .fg(Color::LightMagenta)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(pos) = rest.find(WILDCARD) {
+        if pos > 0 {
+            spans.push(Span::raw(rest[..pos].to_string()));
+        }
+        spans.push(Span::styled(WILDCARD, slot));
+        rest = &rest[pos + WILDCARD.len()..];
+    }
+    if !rest.is_empty() {
+        spans.push(Span::raw(rest.to_string()));
+    }
+    spans
+}
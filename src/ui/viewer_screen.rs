@@ -6,7 +6,10 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
+use super::event_list;
+use crate::redact;
 use crate::screen::ViewerScreen;
+use crate::time;
 
 pub fn draw(f: &mut Frame, screen: &mut ViewerScreen) {
     let area = f.area();
@@ -21,9 +24,14 @@ pub fn draw(f: &mut Frame, screen: &mut ViewerScreen) {
         .split(area);
 
     // Header
-    let ts_display = format_timestamp(screen.selected_event.timestamp);
-    let header = Paragraph::new(format!(" {} ", ts_display))
-        .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    let ts_display = time::format_ms(screen.selected_event.timestamp);
+    let header = Paragraph::new(format!(
+        " {} {}{} ",
+        ts_display,
+        time::zone_label(),
+        redact::indicator()
+    ))
+    .style(Style::default().bg(Color::DarkGray).fg(Color::White));
     f.render_widget(header, chunks[0]);
 
     // Content
@@ -34,6 +42,14 @@ pub fn draw(f: &mut Frame, screen: &mut ViewerScreen) {
         .border_style(Style::default().fg(Color::DarkGray));
 
     let content_lines = render_message(message);
+
+    // Approximate wrapped height so page-wise motions and `G` stop at the last line
+    let inner_width = (chunks[1].width as usize).saturating_sub(2).max(1);
+    screen.line_count = content_lines
+        .iter()
+        .map(|l| l.width().div_ceil(inner_width).max(1))
+        .sum();
+    screen.page_size = (chunks[1].height as usize).saturating_sub(2).max(1);
     let content = Paragraph::new(Text::from(content_lines))
         .block(block)
         .wrap(Wrap { trim: false })
@@ -41,10 +57,43 @@ pub fn draw(f: &mut Frame, screen: &mut ViewerScreen) {
 
     f.render_widget(content, chunks[1]);
 
-    // Footer
+    // Footer / field path input / copy status
+    if screen.field_editing {
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled(
+                " copy field: ",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(screen.field_buffer.as_str()),
+            Span::styled("█", Style::default().fg(Color::Cyan)),
+            Span::raw("   "),
+            Span::styled("(e.g. .request.id)  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("[Enter]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" copy  "),
+            Span::styled("[Esc]", Style::default().fg(Color::DarkGray)),
+            Span::raw(" cancel"),
+        ]))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[2]);
+        return;
+    }
+    if let Some(status) = &screen.status {
+        let footer = Paragraph::new(event_list::notice_line(status))
+            .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+        f.render_widget(footer, chunks[2]);
+        return;
+    }
     let footer = Paragraph::new(Line::from(vec![
         Span::styled(" [j/k ↑↓]", Style::default().fg(Color::Yellow)),
         Span::raw(" scroll  "),
+        Span::styled("[y]", Style::default().fg(Color::Yellow)),
+        Span::raw(" copy  "),
+        Span::styled("[Y]", Style::default().fg(Color::Yellow)),
+        Span::raw(" copy field  "),
+        Span::styled("[z]", Style::default().fg(Color::Yellow)),
+        Span::raw(" UTC/local  "),
         Span::styled("[q]", Style::default().fg(Color::Yellow)),
         Span::raw(" back"),
     ]))
@@ -54,7 +103,9 @@ pub fn draw(f: &mut Frame, screen: &mut ViewerScreen) {
 
 // ── message rendering ─────────────────────────────────────────────────────────
 
-fn render_message(msg: &str) -> Vec<Line<'static>> {
+/// Styled lines for a message: pretty-printed and highlighted when it is JSON,
+/// otherwise one line per message line colored by level keyword.
+pub fn render_message(msg: &str) -> Vec<Line<'static>> {
     let trimmed = msg.trim();
 
     // Try JSON
@@ -69,7 +120,8 @@ fn render_message(msg: &str) -> Vec<Line<'static>> {
     msg.lines().map(plain_line).collect()
 }
 
-fn plain_line(line: &str) -> Line<'static> {
+/// A plain-text line colored as a whole by the first level keyword it contains.
+pub fn plain_line(line: &str) -> Line<'static> {
     let upper = line.to_uppercase();
     let color = if upper.contains("ERROR") || upper.contains("FATAL") || upper.contains("CRITICAL")
     {
@@ -174,13 +226,3 @@ fn value_spans(value: &str, comma: &str) -> Vec<Span<'static>> {
     }
     spans
 }
-
-fn format_timestamp(ts_ms: i64) -> String {
-    match jiff::Timestamp::from_millisecond(ts_ms) {
-        Ok(ts) => {
-            let ms = ts_ms.rem_euclid(1000);
-            format!("{}.{:03} UTC", ts.strftime("%Y-%m-%d %H:%M:%S"), ms)
-        }
-        Err(_) => String::from("0000-00-00 00:00:00.000 UTC"),
-    }
-}